@@ -0,0 +1,371 @@
+//! Batch analysis of all ELF, PE and Mach-O files contained in a directory tree,
+//! e.g. the root file system of an unpacked firmware image.
+//!
+//! Each binary is analyzed by a separate worker process of the cwe_checker,
+//! so that a crash during the analysis of one binary does not affect the analysis of other binaries.
+//! The results of all workers are aggregated into one JSON report.
+
+use cwe_checker_lib::utils::error::{CweCheckerError, ErrorKind};
+use cwe_checker_lib::utils::log::{CweWarning, JsonOutput};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Instant;
+
+/// The aggregated report of a batch analysis.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct BatchReport {
+    /// The results for all successfully analyzed binaries, keyed by file path.
+    pub results: BTreeMap<String, BinaryResult>,
+    /// The error messages for all binaries whose analysis failed, keyed by file path.
+    pub failures: BTreeMap<String, BinaryFailure>,
+    /// The running time of the whole batch analysis in milliseconds.
+    pub duration_ms: u64,
+}
+
+impl BatchReport {
+    /// Add the result of the analysis of the given binary to the report.
+    fn add_result(&mut self, binary: &Path, result: Result<BinaryResult, BinaryFailure>) {
+        let binary_name = binary.to_string_lossy().to_string();
+        match result {
+            Ok(binary_result) => {
+                self.results.insert(binary_name, binary_result);
+            }
+            Err(failure) => {
+                self.failures.insert(binary_name, failure);
+            }
+        }
+    }
+}
+
+/// The result of the analysis of a single binary.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct BinaryResult {
+    /// The CWE warnings found in the binary.
+    pub warnings: Vec<CweWarning>,
+    /// Set if the analysis results are partial,
+    /// because the disassembly of the binary is incomplete (e.g. because the Ghidra analysis timed out).
+    #[serde(default)]
+    pub is_partial: bool,
+    /// The log messages printed by the worker process, e.g. the statistics if `--statistics` is set.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub logs: Vec<String>,
+    /// The running time of the analysis of the binary in milliseconds.
+    pub duration_ms: u64,
+}
+
+/// Information about a failed analysis of a single binary.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct BinaryFailure {
+    /// The error message of the worker process.
+    pub error: String,
+    /// The exit code of the worker process, if it terminated normally.
+    pub exit_code: Option<i32>,
+    /// The category of the error, if the worker process reported it.
+    #[serde(default)]
+    pub kind: Option<ErrorKind>,
+    /// The running time of the (failed) analysis of the binary in milliseconds.
+    pub duration_ms: u64,
+}
+
+/// Analyze all ELF, PE and Mach-O files contained in the directory tree at `directory`
+/// using `num_workers` worker processes in parallel.
+///
+/// The `worker_args` are additional command line arguments that are forwarded to each worker process.
+pub fn run_batch_analysis(
+    directory: &Path,
+    num_workers: usize,
+    worker_args: &[String],
+) -> Result<BatchReport, String> {
+    let start_time = Instant::now();
+    let cwe_checker_path = std::env::current_exe().map_err(|err| err.to_string())?;
+    let mut binaries = Vec::new();
+    collect_binaries(directory, &mut binaries);
+    binaries.sort();
+    // Reverse the order so that popping from the queue yields the binaries in sorted order.
+    binaries.reverse();
+
+    let queue = Arc::new(Mutex::new(binaries));
+    let (result_sender, result_receiver) = mpsc::channel();
+    let mut worker_threads = Vec::new();
+    for worker_index in 0..num_workers.max(1) {
+        let queue = queue.clone();
+        let result_sender = result_sender.clone();
+        let cwe_checker_path = cwe_checker_path.clone();
+        let worker_args = worker_args.to_vec();
+        // Each worker writes its CWE warnings to its own output file,
+        // so that they are not mixed with the log messages printed to stdout.
+        let output_path = std::env::temp_dir().join(format!(
+            "cwe_checker_batch_{}_{}.json",
+            std::process::id(),
+            worker_index
+        ));
+        worker_threads.push(std::thread::spawn(move || loop {
+            let binary = match queue.lock().unwrap().pop() {
+                Some(binary) => binary,
+                None => return,
+            };
+            let result =
+                analyze_in_worker_process(&cwe_checker_path, &binary, &output_path, &worker_args);
+            let _ = std::fs::remove_file(&output_path);
+            if result_sender.send((binary, result)).is_err() {
+                return;
+            }
+        }));
+    }
+    drop(result_sender);
+
+    let mut report = BatchReport::default();
+    for (binary, result) in result_receiver {
+        report.add_result(&binary, result);
+    }
+    for thread in worker_threads {
+        thread
+            .join()
+            .map_err(|_| "A batch worker thread has panicked.".to_string())?;
+    }
+    report.duration_ms = start_time.elapsed().as_millis() as u64;
+    Ok(report)
+}
+
+/// Analyze the given binary by executing the cwe_checker in a new process.
+///
+/// The worker writes the CWE warnings as JSON to the file at `output_path`.
+/// Everything printed to stdout by a successful worker is collected as log messages.
+fn analyze_in_worker_process(
+    cwe_checker_path: &Path,
+    binary: &Path,
+    output_path: &Path,
+    worker_args: &[String],
+) -> Result<BinaryResult, BinaryFailure> {
+    let start_time = Instant::now();
+    let output = Command::new(cwe_checker_path)
+        .arg(binary)
+        .arg("--json")
+        .arg(format!("--out={}", output_path.display()))
+        .args(worker_args)
+        .output();
+    let duration_ms = start_time.elapsed().as_millis() as u64;
+    let output = output.map_err(|err| BinaryFailure {
+        error: format!("Could not execute worker process: {}", err),
+        exit_code: None,
+        kind: None,
+        duration_ms,
+    })?;
+    if !output.status.success() {
+        // Workers print their errors as JSON objects to stdout.
+        let reported_error = serde_json::from_slice::<serde_json::Value>(&output.stdout)
+            .ok()
+            .and_then(|json| serde_json::from_value::<CweCheckerError>(json["error"].clone()).ok());
+        return Err(match reported_error {
+            Some(error) => BinaryFailure {
+                error: error.message,
+                exit_code: output.status.code(),
+                kind: Some(error.kind),
+                duration_ms,
+            },
+            None => BinaryFailure {
+                error: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+                exit_code: output.status.code(),
+                kind: None,
+                duration_ms,
+            },
+        });
+    }
+    let logs = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect();
+    let json_output = std::fs::read(output_path)
+        .map_err(|err| err.to_string())
+        .and_then(|content| {
+            serde_json::from_slice::<JsonOutput>(&content).map_err(|err| err.to_string())
+        });
+    match json_output {
+        Ok(json_output) => Ok(BinaryResult {
+            warnings: json_output.warnings,
+            is_partial: json_output.is_partial,
+            logs,
+            duration_ms,
+        }),
+        Err(err) => Err(BinaryFailure {
+            error: format!("Could not parse the output of the worker process: {}", err),
+            exit_code: output.status.code(),
+            kind: None,
+            duration_ms,
+        }),
+    }
+}
+
+/// Recursively collect the paths of all ELF, PE and Mach-O files in the given directory.
+///
+/// Hidden files and directories are included.
+/// Symbolic links are not followed,
+/// since file systems of firmware images often contain many links to the same binary.
+fn collect_binaries(directory: &Path, binaries: &mut Vec<PathBuf>) {
+    let entries = match std::fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        match std::fs::symlink_metadata(&path) {
+            Ok(metadata) if metadata.is_dir() => collect_binaries(&path, binaries),
+            Ok(metadata) if metadata.is_file() && is_binary_file(&path) => binaries.push(path),
+            _ => (),
+        }
+    }
+}
+
+/// Check the magic bytes of the given file to determine whether it is an ELF, PE or Mach-O file.
+fn is_binary_file(path: &Path) -> bool {
+    let mut file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(_) => return false,
+    };
+    let mut header = [0u8; 64];
+    if file.read_exact(&mut header).is_err() {
+        return false;
+    }
+    if header[0..4] == *b"\x7fELF" {
+        return true;
+    }
+    match header[0..4] {
+        // Thin Mach-O files in big-endian and little-endian byte order
+        [0xfe, 0xed, 0xfa, 0xce | 0xcf] | [0xce | 0xcf, 0xfa, 0xed, 0xfe] => return true,
+        [0xca, 0xfe, 0xba, 0xbe | 0xbf] => {
+            // Fat Mach-O files share their magic number with Java class files.
+            // They are distinguished by the number of contained architectures,
+            // which is smaller than the major version number of any Java class file.
+            let num_architectures =
+                u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+            return num_architectures > 0 && num_architectures < 45;
+        }
+        _ => (),
+    }
+    if header[0..2] == *b"MZ" {
+        // The offset of the PE signature is stored at offset 0x3c of the DOS header.
+        let pe_header_offset = u32::from_le_bytes([header[60], header[61], header[62], header[63]]);
+        let mut signature = [0u8; 4];
+        return file.seek(SeekFrom::Start(pe_header_offset as u64)).is_ok()
+            && file.read_exact(&mut signature).is_ok()
+            && signature == *b"PE\0\0";
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create a file with the given content, padded with zeros to 64 bytes.
+    fn write_file(path: &Path, content: &[u8]) {
+        let mut bytes = content.to_vec();
+        bytes.resize(std::cmp::max(content.len(), 64), 0);
+        std::fs::write(path, bytes).unwrap();
+    }
+
+    #[test]
+    fn binary_file_detection() {
+        let dir = std::env::temp_dir().join(format!(
+            "cwe_checker_batch_detection_{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut pe_header = vec![0u8; 68];
+        pe_header[0..2].copy_from_slice(b"MZ");
+        pe_header[60] = 64;
+        pe_header[64..68].copy_from_slice(b"PE\0\0");
+        let files: Vec<(&str, Vec<u8>, bool)> = vec![
+            ("elf", b"\x7fELF".to_vec(), true),
+            ("pe", pe_header.clone(), true),
+            ("macho", vec![0xcf, 0xfa, 0xed, 0xfe], true),
+            ("fat_macho", vec![0xca, 0xfe, 0xba, 0xbe, 0, 0, 0, 2], true),
+            (
+                "java_class",
+                vec![0xca, 0xfe, 0xba, 0xbe, 0, 0, 0, 52],
+                false,
+            ),
+            ("dos_executable", pe_header[0..64].to_vec(), false),
+            ("text", b"#!/bin/sh\necho ELF".to_vec(), false),
+        ];
+        for (name, content, is_binary) in files {
+            let path = dir.join(name);
+            write_file(&path, &content);
+            assert_eq!(is_binary_file(&path), is_binary, "{}", name);
+        }
+        // Files shorter than the header are no binaries.
+        std::fs::write(dir.join("short"), b"\x7fELF").unwrap();
+        assert!(!is_binary_file(&dir.join("short")));
+        assert!(!is_binary_file(&dir.join("nonexisting")));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn binary_collection() {
+        let dir = std::env::temp_dir().join(format!(
+            "cwe_checker_batch_collection_{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(dir.join("usr/bin")).unwrap();
+        std::fs::create_dir_all(dir.join(".hidden_dir")).unwrap();
+        for path in ["bin", "usr/bin/nested", ".hidden", ".hidden_dir/binary"] {
+            write_file(&dir.join(path), b"\x7fELF");
+        }
+        write_file(&dir.join("usr/readme.txt"), b"Not a binary");
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(dir.join("bin"), dir.join("usr/bin/link")).unwrap();
+
+        let mut binaries = Vec::new();
+        collect_binaries(&dir, &mut binaries);
+        binaries.sort();
+        // Hidden files and directories are searched, symbolic links are not followed.
+        let expected: Vec<PathBuf> = [".hidden", ".hidden_dir/binary", "bin", "usr/bin/nested"]
+            .iter()
+            .map(|path| dir.join(path))
+            .collect();
+        assert_eq!(binaries, expected);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn report_aggregation() {
+        let mut report = BatchReport::default();
+        report.add_result(
+            Path::new("/bin/good"),
+            Ok(BinaryResult {
+                warnings: Vec::new(),
+                is_partial: true,
+                logs: Vec::new(),
+                duration_ms: 10,
+            }),
+        );
+        report.add_result(
+            Path::new("/bin/bad"),
+            Err(BinaryFailure {
+                error: "Ghidra failed".to_string(),
+                exit_code: Some(4),
+                kind: Some(ErrorKind::Frontend),
+                duration_ms: 20,
+            }),
+        );
+        assert_eq!(report.results.len(), 1);
+        assert_eq!(report.results["/bin/good"].duration_ms, 10);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures["/bin/bad"].exit_code, Some(4));
+
+        // Empty log lists are omitted in the JSON report.
+        let json = serde_json::to_value(&report).unwrap();
+        assert!(json["results"]["/bin/good"].get("logs").is_none());
+        assert_eq!(json["results"]["/bin/good"]["is_partial"], true);
+        assert_eq!(json["failures"]["/bin/bad"]["kind"], "Frontend");
+        let parsed: BatchReport = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, report);
+    }
+}
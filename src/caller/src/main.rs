@@ -3,24 +3,50 @@
 
 extern crate cwe_checker_lib; // Needed for the docstring-link to work
 
+use anyhow::{anyhow, Error};
 use cwe_checker_lib::analysis::graph;
-use cwe_checker_lib::utils::binary::{BareMetalConfig, RuntimeMemoryImage};
-use cwe_checker_lib::utils::log::{print_all_messages, LogLevel};
-use cwe_checker_lib::utils::{get_ghidra_plugin_path, read_config_file};
-use cwe_checker_lib::AnalysisResults;
-use cwe_checker_lib::{intermediate_representation::Project, utils::log::LogMessage};
-use nix::{sys::stat, unistd};
-use std::collections::HashSet;
+use cwe_checker_lib::frontend::ir_file::{export_ir, import_ir};
+use cwe_checker_lib::frontend::{
+    Frontend, GhidraFrontend, PcodeCache, PcodeJsonFrontend, DEFAULT_ANALYSIS_TIMEOUT,
+};
+use cwe_checker_lib::intermediate_representation::Project;
+use cwe_checker_lib::pipeline::{get_runtime_memory_image, select_modules};
+use cwe_checker_lib::utils::binary::BareMetalConfig;
+use cwe_checker_lib::utils::error::{CweCheckerError, ErrorKind, WithErrorKind};
+use cwe_checker_lib::utils::fingerprint::compare_with_baseline;
+use cwe_checker_lib::utils::html::generate_html_report;
+use cwe_checker_lib::utils::log::{
+    print_all_messages, write_output, Confidence, CweWarning, JsonOutput, LogLevel, OutputFormat,
+    Severity,
+};
+use cwe_checker_lib::utils::prototypes::PrototypeDatabase;
+use cwe_checker_lib::utils::read_config_file;
+use cwe_checker_lib::{analyze, AnalysisOptions, BinaryFile};
+use serde::de::DeserializeOwned;
+use std::panic::AssertUnwindSafe;
 use std::path::{Path, PathBuf};
-use std::process::Command;
-use std::thread;
 use structopt::StructOpt;
 
+mod batch;
+
 #[derive(Debug, StructOpt)]
 /// Find vulnerable patterns in binary executables
+#[structopt(after_help = "EXIT CODES:
+    0    The analysis finished successfully
+    1    Invalid command line arguments
+    2    The cwe_checker is misconfigured, e.g. a configuration file is missing or invalid
+    3    The binary could not be read or its file format is not supported
+    4    The frontend (e.g. Ghidra) failed to disassemble the binary
+    5    The analysis crashed
+    6    Reading or writing other files failed, e.g. the output file
+
+If the --json flag is set, errors are printed to stdout as a JSON object.")]
 struct CmdlineArgs {
     /// The path to the binary.
-    #[structopt(required_unless("module-versions"), validator(check_file_existence))]
+    #[structopt(
+        required_unless_one(&["module-versions", "prune-cache", "batch"]),
+        validator(check_file_existence)
+    )]
     binary: Option<String>,
 
     /// Path to a custom configuration file to use instead of the standard one.
@@ -42,6 +68,38 @@ struct CmdlineArgs {
     #[structopt(long, short)]
     json: bool,
 
+    /// Generate output in the SARIF 2.1.0 format, e.g. for GitHub code scanning.
+    ///
+    /// Log messages are contained in the SARIF output instead of being printed to stdout.
+    #[structopt(long, conflicts_with_all(&["json", "batch"]))]
+    sarif: bool,
+
+    /// Generate a self-contained HTML report, e.g. for reviewers.
+    ///
+    /// The report groups the CWE warnings by function and by CWE
+    /// and shows the P-Code of the blocks containing the warnings.
+    /// Log messages are contained in the report instead of being printed to stdout.
+    #[structopt(long, conflicts_with_all(&["json", "sarif", "batch"]))]
+    html: bool,
+
+    /// Only report CWE warnings with at least the given severity (low, medium or high).
+    #[structopt(long, default_value = "low", possible_values(&["low", "medium", "high"]))]
+    min_severity: Severity,
+
+    /// Only report CWE warnings with at least the given confidence (low, medium or high).
+    ///
+    /// The confidence depends on how exactly a check was able to track the values that a warning was derived from.
+    #[structopt(long, default_value = "low", possible_values(&["low", "medium", "high"]))]
+    min_confidence: Confidence,
+
+    /// Compare the CWE warnings with the CWE warnings of a previous run, e.g. on an older version of the binary.
+    ///
+    /// The previous warnings have to be given as a file generated with the --json flag.
+    /// Warnings are matched by fingerprints that do not depend on absolute addresses.
+    /// Each warning is reported as new, unchanged or fixed.
+    #[structopt(long, validator(check_file_existence), conflicts_with("batch"))]
+    baseline: Option<String>,
+
     /// Do not print log messages. This prevents polluting stdout for json output.
     #[structopt(long, short)]
     quiet: bool,
@@ -61,6 +119,81 @@ struct CmdlineArgs {
     #[structopt(long)]
     bare_metal_config: Option<String>,
 
+    /// Read the output of the P-Code Extractor Ghidra plugin from the given JSON file instead of running Ghidra.
+    ///
+    /// The JSON file must have been generated from the binary given as input.
+    /// With this option the analysis can be run without a local Ghidra installation.
+    #[structopt(long, validator(check_file_existence))]
+    pcode_raw: Option<String>,
+
+    /// Write the normalized intermediate representation of the binary to the given file.
+    ///
+    /// The file can be loaded again via the `--import-ir` command line option.
+    #[structopt(long)]
+    export_ir: Option<String>,
+
+    /// Read the normalized intermediate representation of the binary from the given file instead of running Ghidra.
+    ///
+    /// The file must have been generated via the `--export-ir` command line option.
+    /// The binary itself still has to be provided, since the analysis also reads from its memory image.
+    #[structopt(long, validator(check_file_existence), conflicts_with("pcode-raw"))]
+    import_ir: Option<String>,
+
+    /// Path to a C header file with function prototypes.
+    /// Can be given several times.
+    ///
+    /// The prototypes are used for extern symbols without known signature
+    /// in addition to the bundled prototypes of common C standard library functions.
+    /// Preprocessor directives in the file are ignored.
+    #[structopt(
+        long,
+        number_of_values(1),
+        validator(check_file_existence),
+        conflicts_with("import-ir")
+    )]
+    prototypes: Vec<String>,
+
+    /// The architecture to analyze if the binary is a fat Mach-O binary containing several architectures,
+    /// e.g. `x86_64` or `arm64`.
+    #[structopt(long)]
+    macho_arch: Option<String>,
+
+    /// Analyze all ELF, PE and Mach-O files contained in the given directory and its subdirectories.
+    ///
+    /// Each binary is analyzed by a separate worker process of the cwe_checker.
+    /// The output is one JSON report containing the CWE warnings, failures and running times of all binaries,
+    /// keyed by the file paths of the binaries.
+    #[structopt(
+        long,
+        validator(check_directory_existence),
+        conflicts_with_all(&["binary", "bare-metal-config", "pcode-raw", "import-ir", "export-ir", "debug"])
+    )]
+    batch: Option<String>,
+
+    /// The number of worker processes to run in parallel in batch mode.
+    #[structopt(long, default_value = "1")]
+    jobs: usize,
+
+    /// The timeout in seconds for the analysis of the binary by Ghidra.
+    ///
+    /// If the timeout fires, the P-Code of the already disassembled parts of the binary is still analyzed,
+    /// but the results are partial. This is indicated by an error message in the log.
+    /// If not set, the default timeout of the Ghidra frontend (one hour) is used.
+    #[structopt(long)]
+    ghidra_timeout: Option<u64>,
+
+    /// Do not use the cache of Ghidra outputs.
+    ///
+    /// Ghidra is always executed and its output is not stored in the cache.
+    #[structopt(long)]
+    no_cache: bool,
+
+    /// Remove all entries from the cache of Ghidra outputs.
+    ///
+    /// If no binary is given, the cwe_checker quits after pruning the cache.
+    #[structopt(long)]
+    prune_cache: bool,
+
     /// Prints out the version numbers of all known modules.
     #[structopt(long)]
     module_versions: bool,
@@ -74,7 +207,32 @@ struct CmdlineArgs {
 fn main() {
     let cmdline_args = CmdlineArgs::from_args();
 
-    run_with_ghidra(&cmdline_args);
+    // Panics are reported as crashes of the analysis.
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| run_with_ghidra(&cmdline_args)))
+        .unwrap_or_else(|panic| {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            Err(CweCheckerError::new(
+                ErrorKind::Analysis,
+                format!("The cwe_checker panicked: {}", message),
+            )
+            .into())
+        });
+    if let Err(err) = result {
+        let error = CweCheckerError::from_error(&err);
+        if cmdline_args.json {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&error.to_json()).unwrap()
+            );
+        } else {
+            eprintln!("Error: {}", error);
+        }
+        std::process::exit(error.kind.exit_code());
+    }
 }
 
 /// Check the existence of a file
@@ -89,317 +247,251 @@ fn check_file_existence(file_path: String) -> Result<(), String> {
     }
 }
 
+/// Check the existence of a directory
+fn check_directory_existence(dir_path: String) -> Result<(), String> {
+    if std::fs::metadata(&dir_path)
+        .map_err(|err| format!("{}", err))?
+        .is_dir()
+    {
+        Ok(())
+    } else {
+        Err(format!("{} is not a directory.", dir_path))
+    }
+}
+
+/// Read and parse the JSON file at the given path.
+///
+/// Errors are reported as configuration errors.
+fn read_json_file<T: DeserializeOwned>(file_path: &str) -> Result<T, Error> {
+    let file = std::fs::File::open(file_path)
+        .map_err(|err| anyhow!("Could not open file {}: {}", file_path, err))
+        .error_kind(ErrorKind::Configuration)?;
+    serde_json::from_reader(std::io::BufReader::new(file))
+        .map_err(|err| anyhow!("Parsing of the file {} failed: {}", file_path, err))
+        .error_kind(ErrorKind::Configuration)
+}
+
 /// Run the cwe_checker with Ghidra as its backend.
-fn run_with_ghidra(args: &CmdlineArgs) {
-    let mut modules = cwe_checker_lib::get_modules();
+fn run_with_ghidra(args: &CmdlineArgs) -> Result<(), Error> {
     if args.module_versions {
         // Only print the module versions and then quit.
         println!("[cwe_checker] module_versions:");
-        for module in modules.iter() {
+        for module in cwe_checker_lib::get_modules().iter() {
             println!("{}", module);
         }
-        return;
+        return Ok(());
+    }
+    if args.prune_cache {
+        let num_removed_entries = PcodeCache::new()
+            .and_then(|cache| cache.prune())
+            .map_err(|err| anyhow!("Error while pruning the cache: {}", err))
+            .error_kind(ErrorKind::Io)?;
+        println!("Removed {} entries from the cache.", num_removed_entries);
+        if args.binary.is_none() && args.batch.is_none() {
+            return Ok(());
+        }
+    }
+    if let Some(ref batch_directory) = args.batch {
+        return run_batch_analysis_and_print_report(args, Path::new(batch_directory));
     }
 
     // Get the configuration file
     let config: serde_json::Value = if let Some(ref config_path) = args.config {
-        let file = std::io::BufReader::new(std::fs::File::open(config_path).unwrap());
-        serde_json::from_reader(file).expect("Parsing of the configuration file failed")
+        read_json_file(config_path)?
     } else {
-        read_config_file("config.json")
+        read_config_file("config.json")?
     };
 
     // Get the bare metal configuration file if it is provided
-    let bare_metal_config_opt: Option<BareMetalConfig> =
-        args.bare_metal_config.as_ref().map(|config_path| {
-            let file = std::io::BufReader::new(std::fs::File::open(config_path).unwrap());
-            serde_json::from_reader(file)
-                .expect("Parsing of the bare metal configuration file failed")
-        });
-
-    // Filter the modules to be executed if the `--partial` parameter is set.
-    if let Some(ref partial_module_list) = args.partial {
-        filter_modules_for_partial_run(&mut modules, partial_module_list);
-    } else {
-        // TODO: CWE78 is disabled on a standard run for now,
-        // because it uses up huge amounts of RAM and computation time on some binaries.
-        modules = modules
-            .into_iter()
-            .filter(|module| module.name != "CWE78")
-            .collect();
-    }
+    let bare_metal_config_opt: Option<BareMetalConfig> = match args.bare_metal_config {
+        Some(ref config_path) => Some(read_json_file(config_path)?),
+        None => None,
+    };
 
-    let binary_file_path = PathBuf::from(args.binary.clone().unwrap());
-    let binary: Vec<u8> = std::fs::read(&binary_file_path).unwrap_or_else(|_| {
-        panic!(
-            "Error: Could not read from file path {}",
-            binary_file_path.display()
-        )
-    });
-    let (mut project, mut all_logs) = get_project_from_ghidra(
-        &binary_file_path,
-        &binary[..],
-        bare_metal_config_opt.clone(),
-    );
-    // Normalize the project and gather log messages generated from it.
-    all_logs.append(&mut project.normalize());
-
-    // Generate the representation of the runtime memory image of the binary
-    let mut runtime_memory_image = if let Some(bare_metal_config) = bare_metal_config_opt.as_ref() {
-        RuntimeMemoryImage::new_from_bare_metal(&binary, bare_metal_config).unwrap_or_else(|err| {
-            panic!("Error while generating runtime memory image: {}", err);
-        })
-    } else {
-        RuntimeMemoryImage::new(&binary).unwrap_or_else(|err| {
-            panic!("Error while generating runtime memory image: {}", err);
-        })
+    // Get the CWE warnings of the baseline if it is provided
+    let baseline: Option<Vec<CweWarning>> = match args.baseline {
+        Some(ref baseline_path) => Some(read_json_file::<JsonOutput>(baseline_path)?.warnings),
+        None => None,
     };
-    if project.program.term.address_base_offset != 0 {
-        // We adjust the memory addresses once globally
-        // so that other analyses do not have to adjust their addresses.
-        runtime_memory_image.add_global_memory_offset(project.program.term.address_base_offset);
+
+    // Get the function prototypes for extern symbols
+    let mut prototypes = PrototypeDatabase::bundled();
+    let mut prototype_logs = Vec::new();
+    for prototype_path in args.prototypes.iter() {
+        prototype_logs.append(
+            &mut prototypes
+                .add_declarations_from_file(Path::new(prototype_path))
+                .error_kind(ErrorKind::Configuration)?,
+        );
     }
-    // Generate the control flow graph of the program
-    let extern_sub_tids = project
-        .program
-        .term
-        .extern_symbols
-        .iter()
-        .map(|symbol| symbol.tid.clone())
-        .collect();
-    let control_flow_graph = graph::get_program_cfg(&project.program, extern_sub_tids);
 
-    let analysis_results = AnalysisResults::new(
-        &binary,
-        &runtime_memory_image,
-        &control_flow_graph,
-        &project,
-    );
+    let options = AnalysisOptions {
+        config,
+        modules: args
+            .partial
+            .as_ref()
+            .map(|partial| partial.split(',').map(|name| name.to_string()).collect()),
+        statistics: args.statistics,
+        bare_metal_config: bare_metal_config_opt.clone(),
+        macho_architecture: args.macho_arch.clone(),
+        prototypes,
+    };
+    // Check the module names given by the `--partial` parameter before running Ghidra.
+    select_modules(options.modules.as_deref())?;
 
-    let modules_depending_on_pointer_inference = vec!["CWE78", "CWE134", "CWE476", "Memory"];
-    let pointer_inference_results = if modules
-        .iter()
-        .any(|module| modules_depending_on_pointer_inference.contains(&module.name))
-    {
-        Some(analysis_results.compute_pointer_inference(&config["Memory"], args.statistics))
+    let binary_path = args
+        .binary
+        .as_ref()
+        .ok_or_else(|| anyhow!("No binary given"))
+        .error_kind(ErrorKind::Configuration)?;
+    let binary_file = BinaryFile::read(
+        Path::new(binary_path),
+        options.macho_architecture.as_deref(),
+    )?;
+    let binary = binary_file.content();
+    let (mut project, mut all_logs) = if let Some(ref import_ir_path) = args.import_ir {
+        // Imported projects are already normalized.
+        let project = import_ir(Path::new(import_ir_path))?;
+        (project, Vec::new())
     } else {
-        None
+        let frontend: Box<dyn Frontend> = if let Some(ref pcode_raw_path) = args.pcode_raw {
+            Box::new(PcodeJsonFrontend {
+                pcode_json_path: PathBuf::from(pcode_raw_path),
+                bare_metal_config: bare_metal_config_opt.clone(),
+            })
+        } else {
+            Box::new(GhidraFrontend {
+                ghidra_path: None,
+                bare_metal_config: bare_metal_config_opt.clone(),
+                use_cache: !args.no_cache,
+                analysis_timeout: args.ghidra_timeout.unwrap_or(DEFAULT_ANALYSIS_TIMEOUT),
+            })
+        };
+        let (mut project, mut logs) = frontend
+            .get_project(binary_file.path(), binary)
+            .error_kind(ErrorKind::Frontend)?;
+        logs.append(&mut prototype_logs);
+        logs.append(&mut options.prototypes.add_missing_signatures(&mut project));
+        // Normalize the project and gather log messages generated from it.
+        logs.append(&mut project.normalize());
+        (project, logs)
     };
-    let analysis_results =
-        analysis_results.set_pointer_inference(pointer_inference_results.as_ref());
+    if let Some(ref export_ir_path) = args.export_ir {
+        export_ir(&project, Path::new(export_ir_path))?;
+    }
 
     // Print debug and then return.
     // Right now there is only one debug printing function.
     // When more debug printing modes exist, this behaviour will change!
     if args.debug {
-        cwe_checker_lib::analysis::pointer_inference::run(
-            &project,
-            &runtime_memory_image,
-            &control_flow_graph,
-            serde_json::from_value(config["Memory"].clone()).unwrap(),
-            true,
-            false,
-        );
-        return;
+        return print_pointer_inference_debug_output(binary, &project, &options);
     }
 
-    // Execute the modules and collect their logs and CWE-warnings.
-    let mut all_cwes = Vec::new();
-    for module in modules {
-        let (mut logs, mut cwes) = (module.run)(&analysis_results, &config[&module.name]);
-        all_logs.append(&mut logs);
-        all_cwes.append(&mut cwes);
+    let mut report = analyze(binary, &mut project, &options)?;
+    all_logs.append(&mut report.logs);
+    if let Some(baseline) = baseline {
+        report.warnings = compare_with_baseline(report.warnings, baseline)?;
     }
+    report.warnings.retain(|warning| {
+        warning.severity >= args.min_severity && warning.confidence >= args.min_confidence
+    });
 
     // Print the results of the modules.
     if args.quiet {
         all_logs = Vec::new(); // Suppress all log messages since the `--quiet` flag is set.
-    } else {
-        if args.statistics {
-            cwe_checker_lib::utils::log::add_debug_log_statistics(&mut all_logs);
-        }
-        if !args.verbose {
-            all_logs.retain(|log_msg| log_msg.level != LogLevel::Debug);
-        }
+    } else if !args.verbose {
+        all_logs.retain(|log_msg| log_msg.level != LogLevel::Debug);
     }
-    print_all_messages(all_logs, all_cwes, args.out.as_deref(), args.json);
-}
-
-/// Only keep the modules specified by the `--partial` parameter in the `modules` list.
-/// The parameter is a comma-separated list of module names, e.g. 'CWE332,CWE476,CWE782'.
-fn filter_modules_for_partial_run(
-    modules: &mut Vec<&cwe_checker_lib::CweModule>,
-    partial_param: &str,
-) {
-    let module_names: HashSet<&str> = partial_param.split(',').collect();
-    *modules = module_names
-        .into_iter()
-        .filter_map(|module_name| {
-            if let Some(module) = modules.iter().find(|module| module.name == module_name) {
-                Some(*module)
-            } else if module_name.is_empty() {
-                None
-            } else {
-                panic!("Error: {} is not a valid module name.", module_name)
-            }
-        })
-        .collect();
+    if args.html {
+        let html = generate_html_report(&report, &all_logs, &project, binary, binary_path);
+        return write_output(&html, args.out.as_deref());
+    }
+    let format = if args.sarif {
+        OutputFormat::Sarif {
+            binary_path: Some(binary_path.to_string()),
+        }
+    } else if args.json {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Text
+    };
+    print_all_messages(
+        all_logs,
+        report.warnings,
+        report.is_partial,
+        args.out.as_deref(),
+        format,
+    )
 }
 
-/// Execute the `p_code_extractor` plugin in ghidra and parse its output into the `Project` data structure.
-fn get_project_from_ghidra(
-    file_path: &Path,
-    binary: &[u8],
-    bare_metal_config_opt: Option<BareMetalConfig>,
-) -> (Project, Vec<LogMessage>) {
-    let bare_metal_base_address_opt = bare_metal_config_opt
-        .as_ref()
-        .map(|config| config.parse_binary_base_address());
-    let ghidra_path: std::path::PathBuf =
-        serde_json::from_value(read_config_file("ghidra.json")["ghidra_path"].clone())
-            .expect("Path to Ghidra not configured.");
-    let headless_path = ghidra_path.join("support/analyzeHeadless");
-
-    // Find the correct paths for temporary files.
-    let project_dirs = directories::ProjectDirs::from("", "", "cwe_checker")
-        .expect("Could not determine path for temporary files");
-    let tmp_folder = if let Some(folder) = project_dirs.runtime_dir() {
-        folder
+/// Analyze all binaries in the given directory in batch mode and print the aggregated report.
+fn run_batch_analysis_and_print_report(args: &CmdlineArgs, directory: &Path) -> Result<(), Error> {
+    // Forward the command line arguments relevant for the analysis to the worker processes.
+    let mut worker_args = Vec::new();
+    if let Some(ref config_path) = args.config {
+        worker_args.push(format!("--config={}", config_path));
+    }
+    if let Some(ref partial) = args.partial {
+        worker_args.push(format!("--partial={}", partial));
+    }
+    if args.no_cache {
+        worker_args.push("--no-cache".to_string());
+    }
+    if let Some(ref macho_arch) = args.macho_arch {
+        worker_args.push(format!("--macho-arch={}", macho_arch));
+    }
+    for prototype_path in args.prototypes.iter() {
+        worker_args.push(format!("--prototypes={}", prototype_path));
+    }
+    if args.statistics {
+        worker_args.push("--statistics".to_string());
     } else {
-        Path::new("/tmp/cwe_checker")
-    };
-    if !tmp_folder.exists() {
-        std::fs::create_dir(tmp_folder).expect("Unable to create temporary folder");
+        worker_args.push("--quiet".to_string());
     }
-    // We add a timestamp suffix to file names
-    // so that if two instances of the cwe_checker are running in parallel on the same file
-    // they do not interfere with each other.
-    let timestamp_suffix = format!(
-        "{:?}",
-        std::time::SystemTime::now()
-            .duration_since(std::time::SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_millis()
-    );
-    let filename = file_path
-        .file_name()
-        .expect("Invalid file name")
-        .to_string_lossy()
-        .to_string();
-    let ghidra_plugin_path = get_ghidra_plugin_path("p_code_extractor");
-
-    // Create a unique name for the pipe
-    let fifo_path = tmp_folder.join(format!("pcode_{}.pipe", timestamp_suffix));
-
-    // Create a new fifo and give read and write rights to the owner
-    if let Err(err) = unistd::mkfifo(&fifo_path, stat::Mode::from_bits(0o600).unwrap()) {
-        eprintln!("Error creating FIFO pipe: {}", err);
-        std::process::exit(101);
+    if let Some(ghidra_timeout) = args.ghidra_timeout {
+        worker_args.push(format!("--ghidra-timeout={}", ghidra_timeout));
     }
+    worker_args.push(format!("--min-severity={}", args.min_severity));
+    worker_args.push(format!("--min-confidence={}", args.min_confidence));
+    let report = batch::run_batch_analysis(directory, args.jobs, &worker_args)
+        .map_err(|err| anyhow!(err))
+        .error_kind(ErrorKind::Analysis)?;
+    let output = serde_json::to_string_pretty(&report)?;
+    if let Some(ref file_path) = args.out {
+        std::fs::write(file_path, output)
+            .map_err(|err| anyhow!("Writing to output path {} failed: {}", file_path, err))
+            .error_kind(ErrorKind::Io)?;
+    } else {
+        println!("{}", output);
+    }
+    Ok(())
+}
 
-    let thread_fifo_path = fifo_path.clone();
-    let thread_file_path = file_path.to_path_buf();
-    let thread_tmp_folder = tmp_folder.to_path_buf();
-    // Execute Ghidra in a new thread and return a Join Handle, so that the thread is only joined
-    // after the output has been read into the cwe_checker
-    let ghidra_subprocess = thread::spawn(move || {
-        let mut ghidra_command = Command::new(&headless_path);
-        ghidra_command
-            .arg(&thread_tmp_folder) // The folder where temporary files should be stored
-            .arg(format!("PcodeExtractor_{}_{}", filename, timestamp_suffix)) // The name of the temporary Ghidra Project.
-            .arg("-import") // Import a file into the Ghidra project
-            .arg(thread_file_path) // File import path
-            .arg("-postScript") // Execute a script after standard analysis by Ghidra finished
-            .arg(ghidra_plugin_path.join("PcodeExtractor.java")) // Path to the PcodeExtractor.java
-            .arg(thread_fifo_path) // The path to the named pipe (fifo)
-            .arg("-scriptPath") // Add a folder containing additional script files to the Ghidra script file search paths
-            .arg(ghidra_plugin_path) // Path to the folder containing the PcodeExtractor.java (so that the other java files can be found.)
-            .arg("-deleteProject") // Delete the temporary project after the script finished
-            .arg("-analysisTimeoutPerFile") // Set a timeout for how long the standard analysis can run before getting aborted
-            .arg("3600"); // Timeout of one hour (=3600 seconds) // TODO: The post-script can detect that the timeout fired and react accordingly.
-        if let Some(bare_metal_config) = bare_metal_config_opt {
-            let mut base_address: &str = &bare_metal_config.flash_base_address;
-            if let Some(stripped_address) = base_address.strip_prefix("0x") {
-                base_address = stripped_address;
-            }
-            ghidra_command
-                .arg("-loader") // Tell Ghidra to use a specific loader
-                .arg("BinaryLoader") // Use the BinaryLoader for bare metal binaries
-                .arg("-loader-baseAddr") // Provide the base address where the binary should be mapped in memory
-                .arg(base_address)
-                .arg("-processor") // Provide the processor type ID, for which the binary was compiled.
-                .arg(bare_metal_config.processor_id);
-        }
-        let output = match ghidra_command.output() // Execute the command and catch its output.
-        {
-            Ok(output) => output,
-            Err(err) => {
-                eprintln!("Error: Ghidra could not be executed:\n{}", err);
-                std::process::exit(101);
-            }
-        };
-
-        match String::from_utf8(output.stdout.clone()) {
-            Ok(standard_out) => {
-                if !standard_out.contains("Pcode was successfully extracted!") {
-                    eprintln!("Execution of Ghidra plugin failed: Process was terminated.");
-                    let error_message: String =
-                        standard_out.lines().rev().collect::<Vec<&str>>()[..2].join("\n");
-                    eprintln!("{}", error_message);
-                    std::process::exit(101);
-                }
-            }
-            Err(_) => {
-                eprintln!("Execution of Ghidra plugin failed: Process was terminated.");
-                std::process::exit(101);
-            }
-        }
-
-        if !output.status.success() {
-            match output.status.code() {
-                Some(code) => {
-                    eprintln!("{}", String::from_utf8(output.stdout).unwrap());
-                    eprintln!("{}", String::from_utf8(output.stderr).unwrap());
-                    eprintln!("Execution of Ghidra plugin failed with exit code {}", code);
-                    std::process::exit(101);
-                }
-                None => {
-                    eprintln!("Execution of Ghidra plugin failed: Process was terminated.");
-                    std::process::exit(101);
-                }
-            }
-        }
-    });
-
-    // Open the FIFO
-    let file = std::fs::File::open(fifo_path.clone()).expect("Could not open FIFO.");
-
-    let mut project_pcode: cwe_checker_lib::pcode::Project =
-        serde_json::from_reader(std::io::BufReader::new(file)).unwrap();
-    let mut log_messages = project_pcode.normalize();
-    let project: Project = match cwe_checker_lib::utils::get_binary_base_address(binary) {
-        Ok(binary_base_address) => project_pcode.into_ir_project(binary_base_address),
-        Err(_err) => {
-            if let Some(binary_base_address) = bare_metal_base_address_opt {
-                let mut project = project_pcode.into_ir_project(binary_base_address);
-                project.program.term.address_base_offset = 0;
-                project
-            } else {
-                log_messages.push(LogMessage::new_info("Could not determine binary base address. Using base address of Ghidra output as fallback."));
-                let mut project = project_pcode.into_ir_project(0);
-                // For PE files setting the address_base_offset to zero is a hack, which worked for the tested PE files.
-                // But this hack will probably not work in general!
-                project.program.term.address_base_offset = 0;
-                project
-            }
-        }
-    };
-
-    ghidra_subprocess
-        .join()
-        .expect("The Ghidra thread to be joined has panicked!");
-
-    std::fs::remove_file(fifo_path).unwrap();
-
-    (project, log_messages)
+/// Run the pointer inference analysis and print its results as JSON to stdout.
+fn print_pointer_inference_debug_output(
+    binary: &[u8],
+    project: &Project,
+    options: &AnalysisOptions,
+) -> Result<(), Error> {
+    let runtime_memory_image =
+        get_runtime_memory_image(binary, project, options.bare_metal_config.as_ref())?;
+    let extern_sub_tids = project
+        .program
+        .term
+        .extern_symbols
+        .iter()
+        .map(|symbol| symbol.tid.clone())
+        .collect();
+    let control_flow_graph = graph::get_program_cfg(&project.program, extern_sub_tids);
+    cwe_checker_lib::analysis::pointer_inference::run(
+        project,
+        &runtime_memory_image,
+        &control_flow_graph,
+        serde_json::from_value(options.config["Memory"].clone())
+            .map_err(|err| anyhow!("Invalid configuration of the Memory check: {}", err))
+            .error_kind(ErrorKind::Configuration)?,
+        true,
+        false,
+    );
+    Ok(())
 }
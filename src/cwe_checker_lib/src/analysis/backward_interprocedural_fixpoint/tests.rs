@@ -86,6 +86,7 @@ fn mock_program() -> Term<Program> {
         tid: Tid::new("sub1"),
         term: Sub {
             name: "sub1".to_string(),
+            signature: None,
             blocks: vec![sub1_blk1, sub1_blk2],
         },
     };
@@ -121,6 +122,7 @@ fn mock_program() -> Term<Program> {
         tid: Tid::new("sub2"),
         term: Sub {
             name: "sub2".to_string(),
+            signature: None,
             blocks: vec![sub2_blk1, sub2_blk2],
         },
     };
@@ -131,6 +133,7 @@ fn mock_program() -> Term<Program> {
             extern_symbols: Vec::new(),
             entry_points: Vec::new(),
             address_base_offset: 0,
+            section_addresses: Vec::new(),
         },
     };
     program
@@ -149,6 +152,9 @@ fn backward_fixpoint() {
         calling_conventions: Vec::new(),
         register_list: Vec::new(),
         datatype_properties: DatatypeProperties::mock(),
+        initial_stack_pointer: None,
+        vector_table_handlers: Vec::new(),
+        is_partial: false,
     };
 
     let mock_con = Context::new(&project);
@@ -159,6 +159,7 @@ mod tests {
             tid: Tid::new("sub"),
             term: Sub {
                 name: "sub".to_string(),
+                signature: None,
                 blocks: vec![block],
             },
         };
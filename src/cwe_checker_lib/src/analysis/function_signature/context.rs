@@ -0,0 +1,367 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::analysis::graph::Graph;
+use crate::intermediate_representation::*;
+
+/// A read access to memory at the address `base + offset`,
+/// where `base` is the value of a register at the corresponding point of the program.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub struct MemoryRead {
+    /// The base register of the address.
+    pub base: Variable,
+    /// The offset of the address relative to the base register.
+    pub offset: i64,
+    /// The number of bytes read.
+    pub size: ByteSize,
+}
+
+/// The inputs of a program that are read before they are overwritten,
+/// as seen from a specific point in the program.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct InputReads {
+    /// Registers that may be read before they are overwritten.
+    pub registers: BTreeSet<Variable>,
+    /// Memory accesses that may read values that are not written before the access.
+    pub memory: BTreeSet<MemoryRead>,
+}
+
+impl InputReads {
+    /// Add all input variables of the given expression to the register reads.
+    fn add_expression_inputs(&mut self, expression: &Expression) {
+        for input_var in expression.input_vars() {
+            self.registers.insert(input_var.clone());
+        }
+    }
+
+    /// Replace the base registers of the memory reads according to the assignment `var := value`.
+    ///
+    /// Memory reads based on `var` are rebased to the input register of `value`
+    /// if `value` is the sum of a register and a constant offset.
+    /// Otherwise they are removed, since their address cannot be tracked further.
+    /// Self-referential assignments are only tracked for the stack pointer,
+    /// since tracking them for other registers (e.g. loop counters) may not terminate.
+    fn rebase_memory_reads(
+        &mut self,
+        var: &Variable,
+        value: Option<&Expression>,
+        stack_pointer: &Variable,
+    ) {
+        let rebase_target = value
+            .and_then(get_base_and_offset)
+            .filter(|(base, _)| *base != var || var == stack_pointer);
+        self.memory = std::mem::take(&mut self.memory)
+            .into_iter()
+            .filter_map(|read| {
+                if read.base != *var {
+                    Some(read)
+                } else {
+                    rebase_target.map(|(base, offset)| MemoryRead {
+                        base: base.clone(),
+                        offset: read.offset.wrapping_add(offset),
+                        size: read.size,
+                    })
+                }
+            })
+            .collect();
+    }
+
+    /// Remove all registers in `clobbered_registers` and all memory reads based on them.
+    fn remove_clobbered_registers(&mut self, clobbered_registers: &HashSet<Variable>) {
+        self.registers
+            .retain(|register| !clobbered_registers.contains(register));
+        self.memory
+            .retain(|read| !clobbered_registers.contains(&read.base));
+    }
+
+    /// Shift the offsets of all memory reads based on the stack pointer by the given value.
+    fn shift_stack_reads(&mut self, stack_pointer: &Variable, shift: i64) {
+        if shift != 0 {
+            self.rebase_memory_reads(
+                stack_pointer,
+                Some(&Expression::Var(stack_pointer.clone()).plus_const(shift)),
+                stack_pointer,
+            );
+        }
+    }
+}
+
+/// If the expression is the sum of a register and a constant, return the register and the constant.
+pub fn get_base_and_offset(expression: &Expression) -> Option<(&Variable, i64)> {
+    match expression {
+        Expression::Var(var) => Some((var, 0)),
+        Expression::BinOp { op, lhs, rhs } => match (op, lhs.as_ref(), rhs.as_ref()) {
+            (BinOpType::IntAdd, Expression::Var(var), Expression::Const(constant))
+            | (BinOpType::IntAdd, Expression::Const(constant), Expression::Var(var)) => {
+                Some((var, constant.try_to_i64().ok()?))
+            }
+            (BinOpType::IntSub, Expression::Var(var), Expression::Const(constant)) => {
+                Some((var, constant.try_to_i64().ok()?.checked_neg()?))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The context struct for the function signature fixpoint computation.
+///
+/// The computation is a backwards fixpoint calculation on the interprocedural control flow graph
+/// that stores at each node the inputs that may be read before they are overwritten.
+/// The values do not flow through return instructions,
+/// so that the value at the start of a function only contains the inputs read by the function itself
+/// (and the functions called by it).
+pub struct Context<'a> {
+    /// The reversed control flow graph of the program.
+    graph: &'a Graph<'a>,
+    /// The stack pointer register.
+    stack_pointer: &'a Variable,
+    /// The registers that are assumed to be overwritten by calls, i.e. all non-callee-saved registers.
+    clobbered_registers: HashSet<Variable>,
+    /// The parameters of the standard calling convention.
+    /// They are assumed to be read by calls with unknown targets.
+    standard_parameter_registers: Vec<Variable>,
+    /// The offset of the stack pointer after a call returns relative to its value at the call instruction.
+    /// On x86 the return instruction pops the return address pushed by the call instruction.
+    stack_offset_after_return: i64,
+    /// Maps the TIDs of extern symbols to the extern symbols.
+    extern_symbol_map: HashMap<&'a Tid, &'a ExternSymbol>,
+}
+
+impl<'a> Context<'a> {
+    /// Create a new context object for the given project and reversed control flow graph.
+    pub fn new(project: &'a Project, graph: &'a Graph<'a>) -> Context<'a> {
+        let standard_cconv = project.get_standard_calling_convention();
+        let clobbered_registers = match standard_cconv {
+            Some(cconv) => project
+                .register_list
+                .iter()
+                .filter(|register| {
+                    *register != &project.stack_pointer_register
+                        && !cconv.callee_saved_register.contains(&register.name)
+                })
+                .cloned()
+                .collect(),
+            None => HashSet::new(),
+        };
+        let standard_parameter_registers = match standard_cconv {
+            Some(cconv) => project
+                .register_list
+                .iter()
+                .filter(|register| cconv.integer_parameter_register.contains(&register.name))
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        };
+        let stack_offset_after_return = if project.cpu_architecture.contains("x86") {
+            u64::from(project.get_pointer_bytesize()) as i64
+        } else {
+            0
+        };
+        let extern_symbol_map = project
+            .program
+            .term
+            .extern_symbols
+            .iter()
+            .map(|symbol| (&symbol.tid, symbol))
+            .collect();
+        Context {
+            graph,
+            stack_pointer: &project.stack_pointer_register,
+            clobbered_registers,
+            standard_parameter_registers,
+            stack_offset_after_return,
+            extern_symbol_map,
+        }
+    }
+
+    /// Compute the inputs read before the call instruction from the inputs read after the call returns.
+    ///
+    /// Registers that may be overwritten by the call are removed
+    /// and stack accesses are adjusted to the value of the stack pointer at the call instruction.
+    fn get_reads_before_call(&self, reads_after_call: &InputReads) -> InputReads {
+        let mut reads = reads_after_call.clone();
+        reads.remove_clobbered_registers(&self.clobbered_registers);
+        reads.shift_stack_reads(self.stack_pointer, self.stack_offset_after_return);
+        reads
+    }
+
+    /// Add the reads of the given parameters of a called function to the reads at the call instruction.
+    fn add_parameter_reads(&self, reads: &mut InputReads, parameters: &[Arg]) {
+        for parameter in parameters {
+            match parameter {
+                Arg::Register { var, .. } => {
+                    reads.registers.insert(var.clone());
+                }
+                Arg::Stack { offset, size, .. } => {
+                    reads.memory.insert(MemoryRead {
+                        base: self.stack_pointer.clone(),
+                        offset: *offset,
+                        size: *size,
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl<'a> crate::analysis::backward_interprocedural_fixpoint::Context<'a> for Context<'a> {
+    /// The value at each node is the set of inputs that may be read before they are overwritten.
+    type Value = InputReads;
+
+    /// Get the reversed control flow graph on which the fixpoint computation operates.
+    fn get_graph(&self) -> &Graph<'a> {
+        self.graph
+    }
+
+    /// Merge by taking the union of the register and memory reads.
+    fn merge(&self, value1: &Self::Value, value2: &Self::Value) -> Self::Value {
+        InputReads {
+            registers: value1.registers.union(&value2.registers).cloned().collect(),
+            memory: value1.memory.union(&value2.memory).cloned().collect(),
+        }
+    }
+
+    /// Update the reads according to the effect of the given `Def` term.
+    fn update_def(&self, value: &Self::Value, def: &Term<Def>) -> Option<Self::Value> {
+        let mut reads = value.clone();
+        match &def.term {
+            Def::Assign { var, value } => {
+                reads.rebase_memory_reads(var, Some(value), self.stack_pointer);
+                reads.registers.remove(var);
+                reads.add_expression_inputs(value);
+            }
+            Def::Load { var, address } => {
+                reads.rebase_memory_reads(var, None, self.stack_pointer);
+                reads.registers.remove(var);
+                reads.add_expression_inputs(address);
+                if let Some((base, offset)) = get_base_and_offset(address) {
+                    reads.memory.insert(MemoryRead {
+                        base: base.clone(),
+                        offset,
+                        size: var.size,
+                    });
+                }
+            }
+            Def::Store { address, value } => {
+                if let Some((base, offset)) = get_base_and_offset(address) {
+                    reads.memory.remove(&MemoryRead {
+                        base: base.clone(),
+                        offset,
+                        size: value.bytesize(),
+                    });
+                }
+                reads.add_expression_inputs(address);
+                reads.add_expression_inputs(value);
+            }
+        }
+        Some(reads)
+    }
+
+    /// Add the input variables of jump conditions and jump target computations to the register reads.
+    fn update_jumpsite(
+        &self,
+        value_after_jump: &Self::Value,
+        jump: &Term<Jmp>,
+        untaken_conditional: Option<&Term<Jmp>>,
+        _jumpsite: &Term<Blk>,
+    ) -> Option<Self::Value> {
+        let mut reads = value_after_jump.clone();
+        match &jump.term {
+            Jmp::CBranch {
+                condition: expression,
+                ..
+            }
+            | Jmp::BranchInd(expression) => reads.add_expression_inputs(expression),
+            _ => (),
+        }
+        if let Some(Term {
+            tid: _,
+            term: Jmp::CBranch { condition, .. },
+        }) = untaken_conditional
+        {
+            reads.add_expression_inputs(condition);
+        }
+        Some(reads)
+    }
+
+    /// Combine the reads of the called function with the reads after the call returns.
+    ///
+    /// Only the reads of the callee that can refer to parameters are added,
+    /// i.e. register reads and reads from the stack frame of the caller.
+    fn update_callsite(
+        &self,
+        target_value: Option<&Self::Value>,
+        return_value: Option<&Self::Value>,
+        _caller_sub: &Term<Sub>,
+        _call: &Term<Jmp>,
+        _return_: &Term<Jmp>,
+    ) -> Option<Self::Value> {
+        let mut reads = match return_value {
+            Some(return_value) => self.get_reads_before_call(return_value),
+            None => InputReads::default(),
+        };
+        if let Some(target_value) = target_value {
+            reads
+                .registers
+                .extend(target_value.registers.iter().cloned());
+            reads.memory.extend(
+                target_value
+                    .memory
+                    .iter()
+                    .filter(|read| read.base == *self.stack_pointer && read.offset >= 0)
+                    .cloned(),
+            );
+        }
+        Some(reads)
+    }
+
+    /// Pass the reads after the call returns to the callsite.
+    fn split_call_stub(&self, combined_value: &Self::Value) -> Option<Self::Value> {
+        Some(combined_value.clone())
+    }
+
+    /// Nothing flows into a function through its return instructions,
+    /// so that the value at the start of a function does not depend on its callers.
+    fn split_return_stub(
+        &self,
+        _combined_value: &Self::Value,
+        _returned_from_sub: &Term<Sub>,
+    ) -> Option<Self::Value> {
+        Some(InputReads::default())
+    }
+
+    /// Add the parameters of the called extern symbol to the reads.
+    /// For indirect calls all parameter registers of the standard calling convention are assumed to be read.
+    fn update_call_stub(
+        &self,
+        value_after_call: &Self::Value,
+        call: &Term<Jmp>,
+    ) -> Option<Self::Value> {
+        let mut reads = self.get_reads_before_call(value_after_call);
+        match &call.term {
+            Jmp::Call { target, .. } => {
+                if let Some(extern_symbol) = self.extern_symbol_map.get(target) {
+                    self.add_parameter_reads(&mut reads, &extern_symbol.parameters);
+                }
+            }
+            Jmp::CallInd { target, .. } => {
+                reads.add_expression_inputs(target);
+                reads
+                    .registers
+                    .extend(self.standard_parameter_registers.iter().cloned());
+            }
+            _ => (),
+        }
+        Some(reads)
+    }
+
+    /// This function just clones its input as it is not used by the fixpoint computation.
+    fn specialize_conditional(
+        &self,
+        value_after_jump: &Self::Value,
+        _condition: &Expression,
+        _is_true: bool,
+    ) -> Option<Self::Value> {
+        Some(value_after_jump.clone())
+    }
+}
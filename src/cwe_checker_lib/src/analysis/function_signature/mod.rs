@@ -0,0 +1,185 @@
+//! This module contains a fixpoint computation to recover the parameters and return values of internal functions.
+//!
+//! A register is considered a parameter of a function
+//! if it is a parameter register of the standard calling convention
+//! and it may be read by the function (or the functions called by it) before it is overwritten.
+//! Stack parameters are memory reads from the stack frame of the caller,
+//! i.e. from non-negative offsets relative to the stack pointer on function entry,
+//! that may happen before the corresponding stack position is overwritten.
+//! On x86 the return address at offset zero is not a parameter.
+//!
+//! A register is considered a return value of a function
+//! if it is a return register of the standard calling convention
+//! and at least one caller of the function may read it after the call returns before overwriting it.
+
+use crate::analysis::backward_interprocedural_fixpoint::create_computation;
+use crate::analysis::graph::Node;
+use crate::analysis::interprocedural_fixpoint_generic::NodeValue;
+use crate::intermediate_representation::*;
+use crate::utils::log::LogMessage;
+use std::collections::{BTreeSet, HashMap};
+
+mod context;
+use context::*;
+
+/// Compute the parameters and return values of all functions of the program
+/// and set the [`Sub::signature`] fields accordingly.
+///
+/// Nothing is computed if the project contains no standard calling convention,
+/// since parameter and return registers are determined by it.
+/// Returns a log message if the fixpoint computation did not stabilize,
+/// in which case the signatures may be incomplete.
+pub fn compute_function_signatures(project: &mut Project) -> Vec<LogMessage> {
+    let cconv = match project.get_standard_calling_convention() {
+        Some(cconv) => cconv.clone(),
+        None => return Vec::new(),
+    };
+    let (input_reads, return_site_reads, stabilized) = compute_input_reads(project);
+    let mut return_registers: HashMap<Tid, BTreeSet<Variable>> = HashMap::new();
+    for (callee_tid, reads) in return_site_reads {
+        let registers = return_registers.entry(callee_tid).or_default();
+        for register in reads.registers {
+            if cconv.return_register.contains(&register.name) {
+                registers.insert(register);
+            }
+        }
+    }
+    let return_address_size = if project.cpu_architecture.contains("x86") {
+        u64::from(project.get_pointer_bytesize()) as i64
+    } else {
+        0
+    };
+    let stack_pointer = project.stack_pointer_register.clone();
+    for sub in project.program.term.subs.iter_mut() {
+        let reads = match input_reads.get(&sub.tid) {
+            Some(reads) => reads,
+            None => continue,
+        };
+        let mut parameters = Vec::new();
+        for register_name in cconv
+            .integer_parameter_register
+            .iter()
+            .chain(cconv.float_parameter_register.iter())
+        {
+            if let Some(var) = reads
+                .registers
+                .iter()
+                .find(|var| var.name == *register_name)
+            {
+                parameters.push(Arg::Register {
+                    var: var.clone(),
+                    data_type: None,
+                });
+            }
+        }
+        for read in reads.memory.iter() {
+            if read.base == stack_pointer && read.offset >= return_address_size {
+                parameters.push(Arg::Stack {
+                    offset: read.offset,
+                    size: read.size,
+                    data_type: None,
+                });
+            }
+        }
+        let return_values = return_registers
+            .get(&sub.tid)
+            .map(|registers| {
+                registers
+                    .iter()
+                    .map(|var| Arg::Register {
+                        var: var.clone(),
+                        data_type: None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        sub.term.signature = Some(FunctionSignature {
+            parameters,
+            return_values,
+        });
+    }
+    if stabilized {
+        Vec::new()
+    } else {
+        vec![LogMessage::new_debug(
+            "Fixpoint for function signature computation did not stabilize. Function signatures may be incomplete.",
+        )
+        .source("Function Signature Analysis")]
+    }
+}
+
+/// Compute the inputs read by each function by means of a backward interprocedural fixpoint computation.
+///
+/// Returns a map from function TIDs to the inputs read by the function,
+/// a list of pairs of called function TIDs and the inputs read by the caller after the call returns
+/// and a flag indicating whether the fixpoint computation stabilized.
+fn compute_input_reads(
+    project: &Project,
+) -> (HashMap<Tid, InputReads>, Vec<(Tid, InputReads)>, bool) {
+    let extern_subs = project
+        .program
+        .term
+        .extern_symbols
+        .iter()
+        .map(|symbol| symbol.tid.clone())
+        .collect();
+    let mut graph = crate::analysis::graph::get_program_cfg(&project.program, extern_subs);
+    graph.reverse();
+    let context = Context::new(project, &graph);
+    let mut computation = create_computation(context, None);
+    for node in graph.node_indices() {
+        match graph[node] {
+            Node::BlkStart(_, _) => (),
+            Node::BlkEnd(_, _) | Node::CallReturn { .. } => {
+                computation.set_node_value(node, NodeValue::Value(InputReads::default()));
+            }
+            Node::CallSource { .. } => {
+                computation.set_node_value(
+                    node,
+                    NodeValue::CallFlowCombinator {
+                        call_stub: Some(InputReads::default()),
+                        interprocedural_flow: Some(InputReads::default()),
+                    },
+                );
+            }
+        }
+    }
+    computation.compute_with_max_steps(100);
+    let stabilized = computation.has_stabilized();
+
+    let mut input_reads = HashMap::new();
+    let mut block_start_nodes = HashMap::new();
+    for node in graph.node_indices() {
+        if let Node::BlkStart(blk, sub) = graph[node] {
+            block_start_nodes.insert((&blk.tid, &sub.tid), node);
+            if sub.term.blocks.first().map(|first_blk| &first_blk.tid) == Some(&blk.tid) {
+                if let Some(NodeValue::Value(reads)) = computation.get_node_value(node) {
+                    input_reads.insert(sub.tid.clone(), reads.clone());
+                }
+            }
+        }
+    }
+    let mut return_site_reads = Vec::new();
+    for sub in project.program.term.subs.iter() {
+        for blk in sub.term.blocks.iter() {
+            for jmp in blk.term.jmps.iter() {
+                if let Jmp::Call {
+                    target,
+                    return_: Some(return_tid),
+                } = &jmp.term
+                {
+                    if let Some(NodeValue::Value(reads)) = block_start_nodes
+                        .get(&(return_tid, &sub.tid))
+                        .and_then(|node| computation.get_node_value(*node))
+                    {
+                        return_site_reads.push((target.clone(), reads.clone()));
+                    }
+                }
+            }
+        }
+    }
+    (input_reads, return_site_reads, stabilized)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,167 @@
+use super::*;
+
+fn mock_block(tid: &str, defs: Vec<Term<Def>>, jmp: Jmp) -> Term<Blk> {
+    Term {
+        tid: Tid::new(tid),
+        term: Blk {
+            defs,
+            jmps: vec![Term {
+                tid: Tid::new(format!("{}_jmp", tid)),
+                term: jmp,
+            }],
+            indirect_jmp_targets: Vec::new(),
+        },
+    }
+}
+
+fn mock_sub(name: &str, blocks: Vec<Term<Blk>>) -> Term<Sub> {
+    Term {
+        tid: Tid::new(name),
+        term: Sub {
+            name: name.to_string(),
+            blocks,
+            signature: None,
+        },
+    }
+}
+
+fn reg(name: &str) -> Variable {
+    Variable::mock(name, 8)
+}
+
+/// A callee that saves a callee-saved register on the stack, reads a stack parameter and `RDI`
+/// and a caller that passes `RSI` to the callee and uses the return value of the callee.
+fn mock_project() -> Project {
+    let callee = mock_sub(
+        "callee",
+        vec![mock_block(
+            "callee_blk",
+            vec![
+                Def::assign(
+                    "callee_def_1",
+                    reg("RSP"),
+                    Expression::Var(reg("RSP")).plus_const(-8),
+                ),
+                Def::store(
+                    "callee_def_2",
+                    Expression::Var(reg("RSP")),
+                    Expression::Var(reg("RBP")),
+                ),
+                Def::load(
+                    "callee_def_3",
+                    reg("RAX"),
+                    Expression::Var(reg("RSP")).plus_const(16),
+                ),
+                Def::assign(
+                    "callee_def_4",
+                    reg("RAX"),
+                    Expression::Var(reg("RAX")).plus(Expression::Var(reg("RDI"))),
+                ),
+                Def::load("callee_def_5", reg("RBP"), Expression::Var(reg("RSP"))),
+            ],
+            Jmp::Return(Expression::Var(reg("RSP"))),
+        )],
+    );
+    let caller = mock_sub(
+        "caller",
+        vec![
+            mock_block(
+                "caller_blk_1",
+                vec![
+                    Def::assign("caller_def_1", reg("RDI"), Expression::Var(reg("RSI"))),
+                    Def::assign(
+                        "caller_def_2",
+                        reg("RSP"),
+                        Expression::Var(reg("RSP")).plus_const(-8),
+                    ),
+                ],
+                Jmp::Call {
+                    target: Tid::new("callee"),
+                    return_: Some(Tid::new("caller_blk_2")),
+                },
+            ),
+            mock_block(
+                "caller_blk_2",
+                vec![Def::assign(
+                    "caller_def_3",
+                    reg("RBX"),
+                    Expression::Var(reg("RAX")),
+                )],
+                Jmp::Return(Expression::Var(reg("RSP"))),
+            ),
+        ],
+    );
+    let mut project = Project::mock_empty();
+    project.calling_conventions = vec![CallingConvention::mock_with_parameter_registers(
+        vec!["RDI".to_string(), "RSI".to_string()],
+        Vec::new(),
+    )];
+    project.program.term.subs = vec![caller, callee];
+    project
+}
+
+#[test]
+fn function_signatures() {
+    let mut project = mock_project();
+    assert!(compute_function_signatures(&mut project).is_empty());
+    let caller_signature = project.program.term.subs[0]
+        .term
+        .signature
+        .as_ref()
+        .unwrap();
+    assert_eq!(
+        caller_signature.parameters,
+        vec![Arg::mock_register("RSI", 8)]
+    );
+    assert!(caller_signature.return_values.is_empty());
+    let callee_signature = project.program.term.subs[1]
+        .term
+        .signature
+        .as_ref()
+        .unwrap();
+    assert_eq!(
+        callee_signature.parameters,
+        vec![
+            Arg::mock_register("RDI", 8),
+            Arg::Stack {
+                offset: 8,
+                size: ByteSize::new(8),
+                data_type: None
+            }
+        ]
+    );
+    assert_eq!(
+        callee_signature.return_values,
+        vec![Arg::mock_register("RAX", 8)]
+    );
+}
+
+#[test]
+fn no_standard_calling_convention() {
+    let mut project = mock_project();
+    project.calling_conventions = Vec::new();
+    compute_function_signatures(&mut project);
+    assert!(project
+        .program
+        .term
+        .subs
+        .iter()
+        .all(|sub| sub.term.signature.is_none()));
+}
+
+#[test]
+fn base_and_offset() {
+    let rsp = reg("RSP");
+    assert_eq!(
+        get_base_and_offset(&Expression::Var(rsp.clone()).plus_const(-8)),
+        Some((&rsp, -8))
+    );
+    assert_eq!(
+        get_base_and_offset(&Expression::Var(rsp.clone())),
+        Some((&rsp, 0))
+    );
+    assert_eq!(
+        get_base_and_offset(&Expression::Var(rsp.clone()).plus(Expression::Var(reg("RAX")))),
+        None
+    );
+}
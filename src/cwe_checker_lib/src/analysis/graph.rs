@@ -535,6 +535,7 @@ mod tests {
             tid: Tid::new("sub1"),
             term: Sub {
                 name: "sub1".to_string(),
+                signature: None,
                 blocks: vec![sub1_blk1, sub1_blk2],
             },
         };
@@ -570,6 +571,7 @@ mod tests {
             tid: Tid::new("sub2"),
             term: Sub {
                 name: "sub2".to_string(),
+                signature: None,
                 blocks: vec![sub2_blk1, sub2_blk2],
             },
         };
@@ -580,6 +582,7 @@ mod tests {
                 extern_symbols: Vec::new(),
                 entry_points: Vec::new(),
                 address_base_offset: 0,
+                section_addresses: Vec::new(),
             },
         };
         program
@@ -614,6 +617,7 @@ mod tests {
             tid: Tid::new("sub"),
             term: Sub {
                 name: "sub".to_string(),
+                signature: None,
                 blocks: vec![blk_term],
             },
         };
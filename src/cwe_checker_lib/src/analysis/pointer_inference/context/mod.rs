@@ -8,7 +8,7 @@ use std::collections::{BTreeMap, BTreeSet};
 
 use super::state::State;
 use super::ValueDomain;
-use super::{Config, Data, VERSION};
+use super::{Config, Data, ExternFunctionModel, VERSION};
 
 // contains trait implementations for the `Context` struct,
 // especially the implementation of the `interprocedural_fixpoint::Context` trait.
@@ -33,10 +33,8 @@ pub struct Context<'a> {
     /// if the fixpoint computation does not instantly stabilize at the corresponding code point.
     /// These duplicates need to be filtered out.
     pub log_collector: crossbeam_channel::Sender<LogThreadMsg>,
-    /// Names of `malloc`-like extern functions.
-    pub allocation_symbols: Vec<String>,
-    /// Names of `free`-like extern functions.
-    pub deallocation_symbols: Vec<String>,
+    /// Models of the effects of extern functions, keyed by the names of the functions.
+    pub extern_function_models: BTreeMap<String, ExternFunctionModel>,
 }
 
 impl<'a> Context<'a> {
@@ -59,8 +57,7 @@ impl<'a> Context<'a> {
             runtime_memory_image,
             extern_symbol_map,
             log_collector,
-            allocation_symbols: config.allocation_symbols,
-            deallocation_symbols: config.deallocation_symbols,
+            extern_function_models: config.get_extern_function_models(),
         }
     }
 
@@ -84,6 +81,33 @@ impl<'a> Context<'a> {
         }
     }
 
+    /// If the stack pointer register `var` contains the initial stack pointer value of the state
+    /// (see [`State::initial_stack_pointer`]),
+    /// replace the absolute value with a pointer to the start of the current stack frame.
+    ///
+    /// Startup code of bare metal binaries often (re-)initializes the stack pointer with its initial value.
+    /// Without this replacement the analysis would lose track of the stack afterwards.
+    fn restore_stack_from_initial_stack_pointer(&self, state: &mut State, var: &Variable) {
+        if *var != self.project.stack_pointer_register {
+            return;
+        }
+        if let Some(initial_stack_pointer) = state.initial_stack_pointer {
+            let value = state.get_register(var);
+            let is_initial_stack_pointer = value
+                .get_if_absolute_value()
+                .and_then(|absolute_value| absolute_value.try_to_bitvec().ok())
+                .and_then(|bitvec| bitvec.try_to_u64().ok())
+                == Some(initial_stack_pointer);
+            if is_initial_stack_pointer {
+                let stack_pointer = Data::from_target(
+                    state.stack_id.clone(),
+                    Bitvector::zero(apint::BitWidth::from(var.size)).into(),
+                );
+                state.set_register(var, stack_pointer);
+            }
+        }
+    }
+
     /// If `result` is an `Err`, log the error message as a debug message through the `log_collector` channel.
     pub fn log_debug(&self, result: Result<(), Error>, location: Option<&Tid>) {
         if let Err(err) = result {
@@ -123,62 +147,35 @@ impl<'a> Context<'a> {
         }
     }
 
-    /// If the given `extern_symbol` is a call to a known allocation function
-    /// return the size of the memory object allocated by it.
+    /// Return the size of the memory object allocated by a call to the given `extern_symbol`.
+    /// The size is the product of the values of the parameters with the given indices.
     ///
     /// The function returns a `Top` element if the size could not be determined.
-    /// Known allocation functions: `malloc`, `realloc`, `calloc`.
     fn get_allocation_size_of_alloc_call(
         &self,
         state: &State,
         extern_symbol: &ExternSymbol,
+        size_parameters: &[usize],
     ) -> ValueDomain {
         let address_bytesize = self.project.get_pointer_bytesize();
-        let object_size = match extern_symbol.name.as_str() {
-            "malloc" => {
-                let size_parameter = extern_symbol.parameters.get(0).unwrap();
-                state
-                    .eval_parameter_arg(
-                        size_parameter,
-                        &self.project.stack_pointer_register,
-                        self.runtime_memory_image,
-                    )
-                    .unwrap_or_else(|_| Data::new_top(address_bytesize))
-            }
-            "realloc" => {
-                let size_parameter = extern_symbol.parameters.get(1).unwrap();
-                state
-                    .eval_parameter_arg(
-                        size_parameter,
-                        &self.project.stack_pointer_register,
-                        self.runtime_memory_image,
-                    )
-                    .unwrap_or_else(|_| Data::new_top(address_bytesize))
-            }
-            "calloc" => {
-                let size_param1 = extern_symbol.parameters.get(0).unwrap();
-                let size_param2 = extern_symbol.parameters.get(1).unwrap();
-                let param1_value = state
-                    .eval_parameter_arg(
-                        size_param1,
-                        &self.project.stack_pointer_register,
-                        self.runtime_memory_image,
-                    )
-                    .unwrap_or_else(|_| Data::new_top(address_bytesize));
-                let param2_value = state
-                    .eval_parameter_arg(
-                        size_param2,
+        let mut object_size: Option<Data> = None;
+        for index in size_parameters {
+            let parameter_value = get_parameter(extern_symbol, *index)
+                .and_then(|parameter| {
+                    state.eval_parameter_arg(
+                        parameter,
                         &self.project.stack_pointer_register,
                         self.runtime_memory_image,
                     )
-                    .unwrap_or_else(|_| Data::new_top(address_bytesize));
-                param1_value.bin_op(BinOpType::IntMult, &param2_value)
-            }
-            _ => DataDomain::new_top(address_bytesize),
-        };
+                })
+                .unwrap_or_else(|_| Data::new_top(address_bytesize));
+            object_size = Some(match object_size {
+                Some(size) => size.bin_op(BinOpType::IntMult, &parameter_value),
+                None => parameter_value,
+            });
+        }
         object_size
-            .get_if_absolute_value()
-            .cloned()
+            .and_then(|size| size.get_if_absolute_value().cloned())
             .unwrap_or_else(|| ValueDomain::new_top(address_bytesize))
     }
 
@@ -191,9 +188,11 @@ impl<'a> Context<'a> {
         mut new_state: State,
         call: &Term<Jmp>,
         extern_symbol: &ExternSymbol,
+        size_parameters: &[usize],
     ) -> State {
         let address_bytesize = self.project.get_pointer_bytesize();
-        let object_size = self.get_allocation_size_of_alloc_call(state, extern_symbol);
+        let object_size =
+            self.get_allocation_size_of_alloc_call(state, extern_symbol, size_parameters);
 
         match extern_symbol.get_unique_return_register() {
             Ok(return_register) => {
@@ -230,7 +229,7 @@ impl<'a> Context<'a> {
         }
     }
 
-    /// Mark the object that the parameter of a call is pointing to as freed.
+    /// Mark the object that the parameter with the given index of a call is pointing to as freed.
     /// If the object may have been already freed, generate a CWE warning.
     /// This models the behaviour of `free` and similar functions.
     fn mark_parameter_object_as_freed(
@@ -239,8 +238,9 @@ impl<'a> Context<'a> {
         mut new_state: State,
         call: &Term<Jmp>,
         extern_symbol: &ExternSymbol,
+        parameter_index: usize,
     ) -> State {
-        match extern_symbol.get_unique_parameter() {
+        match get_parameter(extern_symbol, parameter_index) {
             Ok(parameter) => {
                 let parameter_value = state.eval_parameter_arg(
                     parameter,
@@ -266,6 +266,13 @@ impl<'a> Context<'a> {
                                     "(Double Free) Object may have been freed before at {}",
                                     call.tid.address
                                 ),
+                                source_locations: Vec::new(),
+                                demangled_names: BTreeMap::new(),
+                                fingerprint: None,
+                                baseline_state: None,
+                                severity: Severity::High,
+                                confidence: state.get_pointer_confidence(&memory_object_pointer),
+                                trace: get_trace_source_and_sink(&memory_object_pointer, &call.tid),
                             };
                             let _ = self.log_collector.send(LogThreadMsg::Cwe(warning));
                         }
@@ -315,6 +322,13 @@ impl<'a> Context<'a> {
                                 "(Use After Free) Call to {} may access freed memory at {}",
                                 extern_symbol.name, call.tid.address
                             ),
+                            source_locations: Vec::new(),
+                            demangled_names: BTreeMap::new(),
+                            fingerprint: None,
+                            baseline_state: None,
+                            severity: Severity::High,
+                            confidence: state.get_pointer_confidence(&value),
+                            trace: get_trace_source_and_sink(&value, &call.tid),
                         };
                         let _ = self.log_collector.send(LogThreadMsg::Cwe(warning));
                     }
@@ -360,6 +374,13 @@ impl<'a> Context<'a> {
                                 extern_symbol.name,
                                 call.tid.address
                             ),
+                            source_locations: Vec::new(),
+                            demangled_names: BTreeMap::new(),
+                            fingerprint: None,
+                            baseline_state: None,
+                            severity: Severity::High,
+                            confidence: state.get_pointer_confidence(&data),
+                            trace: get_trace_source_and_sink(&data, &call.tid),
                         };
                         let _ = self.log_collector.send(LogThreadMsg::Cwe(warning));
                     }
@@ -411,14 +432,90 @@ impl<'a> Context<'a> {
         }
     }
 
+    /// Set the return register of a call to the given `extern_symbol`
+    /// to the value of the parameter with the given index.
+    /// This models functions like `memcpy` or `strcpy` that return one of their parameters.
+    fn set_return_value_to_parameter(
+        &self,
+        state: &State,
+        new_state: &mut State,
+        extern_symbol: &ExternSymbol,
+        parameter_index: usize,
+    ) -> Result<(), Error> {
+        let parameter = get_parameter(extern_symbol, parameter_index)?;
+        let parameter_value = state.eval_parameter_arg(
+            parameter,
+            &self.project.stack_pointer_register,
+            self.runtime_memory_image,
+        )?;
+        let return_register = extern_symbol.get_unique_return_register()?;
+        new_state.set_register(return_register, parameter_value);
+        Ok(())
+    }
+
+    /// Handle a call to an extern symbol according to the given model of its effects.
+    ///
+    /// The effects are applied in the following order:
+    /// Deallocation of the freed parameter object, writes to the memory objects reachable through parameters
+    /// and setting the return value (either to a newly allocated object or to the returned parameter).
+    fn handle_modelled_extern_call(
+        &self,
+        state: &State,
+        mut new_state: State,
+        call: &Term<Jmp>,
+        extern_symbol: &ExternSymbol,
+        model: &ExternFunctionModel,
+    ) -> State {
+        if let Some(parameter_index) = model.freed_parameter {
+            new_state = self.mark_parameter_object_as_freed(
+                state,
+                new_state,
+                call,
+                extern_symbol,
+                parameter_index,
+            );
+        }
+        new_state = self.handle_generic_extern_call(
+            state,
+            new_state,
+            call,
+            extern_symbol,
+            model.written_parameters.as_deref(),
+        );
+        if let Some(size_parameters) = &model.allocation_size_parameters {
+            new_state = self.add_new_object_in_call_return_register(
+                state,
+                new_state,
+                call,
+                extern_symbol,
+                size_parameters,
+            );
+        } else if let Some(parameter_index) = model.returned_parameter {
+            self.log_debug(
+                self.set_return_value_to_parameter(
+                    state,
+                    &mut new_state,
+                    extern_symbol,
+                    parameter_index,
+                ),
+                Some(&call.tid),
+            );
+        }
+        new_state
+    }
+
     /// Handle an extern symbol call, whose concrete effect on the state is unknown.
     /// Basically, we assume that the call may write to all memory objects and register that is has access to.
+    ///
+    /// If `written_parameters` is set, only the memory objects reachable through the parameters
+    /// with the given indices are assumed to be written to.
     fn handle_generic_extern_call(
         &self,
         state: &State,
         mut new_state: State,
         call: &Term<Jmp>,
         extern_symbol: &ExternSymbol,
+        written_parameters: Option<&[usize]>,
     ) -> State {
         self.log_debug(
             new_state.clear_stack_parameter(
@@ -430,34 +527,50 @@ impl<'a> Context<'a> {
         );
         let calling_conv = extern_symbol.get_calling_convention(self.project);
         let mut possible_referenced_ids = BTreeSet::new();
+        let mut written_ids = BTreeSet::new();
         if extern_symbol.parameters.is_empty() && extern_symbol.return_values.is_empty() {
             // We assume here that we do not know the parameters and approximate them by all possible parameter registers.
             // This approximation is wrong if the function is known but has neither parameters nor return values.
             // We cannot distinguish these two cases yet.
-            for parameter_register_name in calling_conv
-                .integer_parameter_register
-                .iter()
-                .chain(calling_conv.float_parameter_register.iter())
+            // The indices of written parameters are mapped to the integer parameter registers of the calling convention.
+            for (index, parameter_register_name) in
+                calling_conv.integer_parameter_register.iter().enumerate()
             {
+                if let Some(register_value) = state.get_register_by_name(parameter_register_name) {
+                    possible_referenced_ids.extend(register_value.referenced_ids().cloned());
+                    if is_written_parameter(written_parameters, index) {
+                        written_ids.extend(register_value.referenced_ids().cloned());
+                    }
+                }
+            }
+            for parameter_register_name in calling_conv.float_parameter_register.iter() {
                 if let Some(register_value) = state.get_register_by_name(parameter_register_name) {
                     possible_referenced_ids.extend(register_value.referenced_ids().cloned());
                 }
             }
         } else {
-            for parameter in extern_symbol.parameters.iter() {
+            for (index, parameter) in extern_symbol.parameters.iter().enumerate() {
                 if let Ok(data) = state.eval_parameter_arg(
                     parameter,
                     &self.project.stack_pointer_register,
                     self.runtime_memory_image,
                 ) {
                     possible_referenced_ids.extend(data.referenced_ids().cloned());
+                    if is_written_parameter(written_parameters, index) {
+                        written_ids.extend(data.referenced_ids().cloned());
+                    }
                 }
             }
         }
         possible_referenced_ids =
             state.add_recursively_referenced_ids_to_id_set(possible_referenced_ids);
-        // Delete content of all referenced objects, as the function may write to them.
-        for id in possible_referenced_ids.iter() {
+        if written_parameters.is_none() {
+            written_ids = possible_referenced_ids.clone();
+        } else {
+            written_ids = state.add_recursively_referenced_ids_to_id_set(written_ids);
+        }
+        // Delete content of all written objects, as the function may write to them.
+        for id in written_ids.iter() {
             new_state
                 .memory
                 .assume_arbitrary_writes_to_object(id, &possible_referenced_ids);
@@ -518,5 +631,41 @@ impl<'a> Context<'a> {
     }
 }
 
+/// Get the parameter with the given index of an extern symbol.
+fn get_parameter(extern_symbol: &ExternSymbol, index: usize) -> Result<&Arg, Error> {
+    extern_symbol.parameters.get(index).ok_or_else(|| {
+        anyhow!(
+            "{} has no parameter with index {}",
+            extern_symbol.name,
+            index
+        )
+    })
+}
+
+/// Returns `true` if the parameter with the given index may be written to according to `written_parameters`.
+///
+/// If `written_parameters` is not set, all parameters may be written to.
+fn is_written_parameter(written_parameters: Option<&[usize]>, index: usize) -> bool {
+    match written_parameters {
+        Some(indices) => indices.contains(&index),
+        None => true,
+    }
+}
+
+/// Get the source and the sink of the witness trace of a CWE warning about the memory object targeted by the given pointer.
+///
+/// The source is the origin of the memory object, i.e. the allocation site for heap objects
+/// and the start of the corresponding function for stack frames.
+/// If the pointer has several targets, the first one is used.
+fn get_trace_source_and_sink(pointer: &Data, sink: &Tid) -> Vec<TraceStep> {
+    match pointer.referenced_ids().next() {
+        Some(id) => vec![
+            TraceStep::new(TraceStepKind::Source, id.get_tid()),
+            TraceStep::new(TraceStepKind::Sink, sink),
+        ],
+        None => Vec::new(),
+    }
+}
+
 #[cfg(test)]
 mod tests;
@@ -80,6 +80,7 @@ fn mock_project() -> (Project, Config) {
         ],
         entry_points: Vec::new(),
         address_base_offset: 0,
+        section_addresses: Vec::new(),
     };
     let program_term = Term {
         tid: Tid::new("program"),
@@ -104,11 +105,11 @@ fn mock_project() -> (Project, Config) {
             calling_conventions: vec![cconv],
             register_list,
             datatype_properties: DatatypeProperties::mock(),
+            initial_stack_pointer: None,
+            vector_table_handlers: Vec::new(),
+            is_partial: false,
         },
-        Config {
-            allocation_symbols: vec!["malloc".into()],
-            deallocation_symbols: vec!["free".into()],
-        },
+        Config::mock(),
     )
 }
 
@@ -159,6 +160,7 @@ fn context_problem_implementation() {
         tid: Tid::new("caller_sub"),
         term: Sub {
             name: "caller_sub".into(),
+            signature: None,
             blocks: vec![target_block.clone()],
         },
     };
@@ -270,6 +272,99 @@ fn context_problem_implementation() {
         .is_top());
 }
 
+#[test]
+fn extern_function_models() {
+    let (project, config) = mock_project();
+    let runtime_memory_image = RuntimeMemoryImage::mock();
+    let graph = crate::analysis::graph::get_program_cfg(&project.program, HashSet::new());
+    let (log_sender, _log_receiver) = crossbeam_channel::unbounded();
+    let context = Context::new(&project, &runtime_memory_image, &graph, config, log_sender);
+    let mut state = State::new(&register("RSP"), Tid::new("main"));
+
+    // The allocation size is the product of the size parameters.
+    let mut calloc = mock_extern_symbol("calloc");
+    calloc.parameters = vec![Arg::mock_register("RDI", 8), Arg::mock_register("RSI", 8)];
+    state.set_register(&register("RDI"), bv(3).into());
+    state.set_register(&register("RSI"), bv(4).into());
+    assert_eq!(
+        context.get_allocation_size_of_alloc_call(&state, &calloc, &[0, 1]),
+        bv(12)
+    );
+    assert!(context
+        .get_allocation_size_of_alloc_call(&state, &calloc, &[0, 2])
+        .is_top());
+
+    // The return value aliases the returned parameter.
+    let memcpy_model = ExternFunctionModel {
+        returned_parameter: Some(0),
+        written_parameters: Some(vec![0]),
+        ..ExternFunctionModel::default()
+    };
+    let stack_pointer = Data::from_target(new_id("main", "RSP"), bv(-8));
+    state.set_register(&register("RDX"), stack_pointer.clone());
+    let mut new_state = state.clone();
+    new_state.set_register(&register("RDX"), Data::new_top(ByteSize::new(8)));
+    let new_state = context.handle_modelled_extern_call(
+        &state,
+        new_state,
+        &call_term("extern_memcpy"),
+        &mock_extern_symbol("memcpy"),
+        &memcpy_model,
+    );
+    assert_eq!(new_state.get_register(&register("RDX")), stack_pointer);
+}
+
+#[test]
+fn extern_call_without_signature() {
+    use crate::analysis::pointer_inference::object::ObjectType;
+    let (mut project, config) = mock_project();
+    project.calling_conventions[0].integer_parameter_register =
+        vec!["RDI".to_string(), "RSI".to_string()];
+    let runtime_memory_image = RuntimeMemoryImage::mock();
+    let graph = crate::analysis::graph::get_program_cfg(&project.program, HashSet::new());
+    let (log_sender, _log_receiver) = crossbeam_channel::unbounded();
+    let context = Context::new(&project, &runtime_memory_image, &graph, config, log_sender);
+    let mut state = State::new(&register("RSP"), Tid::new("main"));
+    for (register_name, object_name) in [("RDI", "destination"), ("RSI", "source")] {
+        let id = new_id(object_name, "RAX");
+        state.memory.add_abstract_object(
+            id.clone(),
+            bv(0).into(),
+            ObjectType::Heap,
+            ByteSize::new(8),
+        );
+        let pointer = Data::from_target(id, bv(0));
+        state
+            .store_value(&pointer, &bv(42).into(), &runtime_memory_image)
+            .unwrap();
+        state.set_register(&register(register_name), pointer);
+    }
+    // A memset-like function whose signature is unknown.
+    let mut memset = mock_extern_symbol("memset");
+    memset.parameters = Vec::new();
+    memset.return_values = Vec::new();
+
+    let new_state = context.handle_generic_extern_call(
+        &state,
+        state.clone(),
+        &call_term("extern_memset"),
+        &memset,
+        Some(&[0]),
+    );
+    let load = |register_name: &str| {
+        new_state
+            .load_value(
+                &Expression::Var(register(register_name)),
+                ByteSize::new(8),
+                &runtime_memory_image,
+            )
+            .unwrap()
+    };
+    // Only the object pointed to by the first parameter register may have been written to.
+    assert!(load("RDI").is_top());
+    assert_eq!(load("RSI"), bv(42).into());
+}
+
 #[test]
 fn update_return() {
     use crate::analysis::forward_interprocedural_fixpoint::Context as IpFpContext;
@@ -411,3 +506,77 @@ fn specialize_conditional() {
     let result = context.specialize_conditional(&state, &condition, &block, false);
     assert!(result.is_none());
 }
+
+#[test]
+fn initial_stack_pointer_assignment() {
+    use crate::analysis::forward_interprocedural_fixpoint::Context as IpFpContext;
+    let (mut project, config) = mock_project();
+    project.initial_stack_pointer = Some(0x20001000);
+    let graph = crate::analysis::graph::get_program_cfg(&project.program, HashSet::new());
+    let runtime_memory_image = RuntimeMemoryImage::mock();
+    let (log_sender, _log_receiver) = crossbeam_channel::unbounded();
+    let context = Context::new(&project, &runtime_memory_image, &graph, config, log_sender);
+    let mut handler_state = State::new(&register("RSP"), Tid::new("func"));
+    handler_state.initial_stack_pointer = Some(0x20001000);
+    let stack_pointer = Data::from_target(new_id("func", "RSP"), bv(0));
+
+    let mut assign_term = Def::assign(
+        "def",
+        register("RSP"),
+        Expression::Const(Bitvector::from_u64(0x20001000)),
+    );
+    let result = context.update_def(&handler_state, &assign_term).unwrap();
+    assert_eq!(result.get_register(&register("RSP")), stack_pointer);
+
+    // Functions that are not vector table handlers may use the address for other purposes.
+    let other_state = State::new(&register("RSP"), Tid::new("func"));
+    let result = context.update_def(&other_state, &assign_term).unwrap();
+    assert_eq!(
+        result.get_register(&register("RSP")),
+        Bitvector::from_u64(0x20001000).into()
+    );
+
+    // Other constants are not replaced.
+    assign_term.term = Def::Assign {
+        var: register("RSP"),
+        value: Expression::Const(Bitvector::from_u64(0x20000800)),
+    };
+    let result = context.update_def(&handler_state, &assign_term).unwrap();
+    assert_eq!(
+        result.get_register(&register("RSP")),
+        Bitvector::from_u64(0x20000800).into()
+    );
+
+    // The initial stack pointer is not passed on to callees, but restored after returning from them.
+    let target_block = Blk::mock_with_tid("callee_start");
+    let callee = Term {
+        tid: Tid::new("callee"),
+        term: Sub {
+            name: "callee".into(),
+            signature: None,
+            blocks: vec![target_block.clone()],
+        },
+    };
+    let target_node = crate::analysis::graph::Node::BlkStart(&target_block, &callee);
+    let call = call_term("callee");
+    let mut callee_state = context
+        .update_call(&handler_state, &call, &target_node)
+        .unwrap();
+    assert_eq!(callee_state.initial_stack_pointer, None);
+    // Emulate removing the return address from the stack for x64
+    callee_state = context
+        .update_def(
+            &callee_state,
+            &reg_add_term("RSP", 8, "stack_pointer_update_def"),
+        )
+        .unwrap();
+    let return_state = context
+        .update_return(
+            Some(&callee_state),
+            Some(&handler_state),
+            &call,
+            &return_term("return_target"),
+        )
+        .unwrap();
+    assert_eq!(return_state.initial_stack_pointer, Some(0x20001000));
+}
@@ -16,8 +16,20 @@ impl<'a> crate::analysis::forward_interprocedural_fixpoint::Context<'a> for Cont
     /// Update the state according to the effects of the given `Def` term.
     fn update_def(&self, state: &Self::Value, def: &Term<Def>) -> Option<Self::Value> {
         let mut new_state = state.clone();
+        // The confidence and the witness trace are only computed if a warning is generated.
+        let get_access_confidence_and_trace = || match &def.term {
+            Def::Load { address, .. } | Def::Store { address, .. } => {
+                let pointer = state.eval(address);
+                (
+                    state.get_pointer_confidence(&pointer),
+                    super::get_trace_source_and_sink(&pointer, &def.tid),
+                )
+            }
+            Def::Assign { .. } => (Confidence::default(), Vec::new()),
+        };
         // first check for use-after-frees
         if new_state.contains_access_of_dangling_memory(&def.term) {
+            let (access_confidence, access_trace) = get_access_confidence_and_trace();
             let warning = CweWarning {
                 name: "CWE416".to_string(),
                 version: VERSION.to_string(),
@@ -29,18 +41,26 @@ impl<'a> crate::analysis::forward_interprocedural_fixpoint::Context<'a> for Cont
                     "(Use After Free) Access through a dangling pointer at {}",
                     def.tid.address
                 ),
+                source_locations: Vec::new(),
+                demangled_names: BTreeMap::new(),
+                fingerprint: None,
+                baseline_state: None,
+                severity: Severity::High,
+                confidence: access_confidence,
+                trace: access_trace,
             };
             let _ = self.log_collector.send(LogThreadMsg::Cwe(warning));
         }
         // check for out-of-bounds memory access
         if state.contains_out_of_bounds_mem_access(&def.term, self.runtime_memory_image) {
-            let (warning_name, warning_description) = match def.term {
+            let (warning_name, warning_description, severity) = match def.term {
                 Def::Load { .. } => (
                     "CWE125",
                     format!(
                         "(Out-of-bounds Read) Memory load at {} may be out of bounds",
                         def.tid.address
                     ),
+                    Severity::Medium,
                 ),
                 Def::Store { .. } => (
                     "CWE787",
@@ -48,9 +68,11 @@ impl<'a> crate::analysis::forward_interprocedural_fixpoint::Context<'a> for Cont
                         "(Out-of-bounds Write) Memory write at {} may be out of bounds",
                         def.tid.address
                     ),
+                    Severity::High,
                 ),
                 Def::Assign { .. } => panic!(),
             };
+            let (access_confidence, access_trace) = get_access_confidence_and_trace();
             let warning = CweWarning {
                 name: warning_name.to_string(),
                 version: VERSION.to_string(),
@@ -59,6 +81,13 @@ impl<'a> crate::analysis::forward_interprocedural_fixpoint::Context<'a> for Cont
                 symbols: Vec::new(),
                 other: Vec::new(),
                 description: warning_description,
+                source_locations: Vec::new(),
+                demangled_names: BTreeMap::new(),
+                fingerprint: None,
+                baseline_state: None,
+                severity,
+                confidence: access_confidence,
+                trace: access_trace,
             };
             let _ = self.log_collector.send(LogThreadMsg::Cwe(warning));
         }
@@ -73,6 +102,7 @@ impl<'a> crate::analysis::forward_interprocedural_fixpoint::Context<'a> for Cont
             }
             Def::Assign { var, value } => {
                 new_state.handle_register_assign(var, value);
+                self.restore_stack_from_initial_stack_pointer(&mut new_state, var);
                 Some(new_state)
             }
             Def::Load { var, address } => {
@@ -86,6 +116,7 @@ impl<'a> crate::analysis::forward_interprocedural_fixpoint::Context<'a> for Cont
                 // This only works because gp is (incorrectly) marked as a callee-saved register.
                 // FIXME: If the rest of the analysis becomes good enough so that this case is not common anymore,
                 // we should log it.
+                self.restore_stack_from_initial_stack_pointer(&mut new_state, var);
                 Some(new_state)
             }
         }
@@ -160,6 +191,8 @@ impl<'a> crate::analysis::forward_interprocedural_fixpoint::Context<'a> for Cont
             );
             // set the new stack_id
             callee_state.stack_id = callee_stack_id.clone();
+            // The stack of the callee does not start at the initial stack pointer.
+            callee_state.initial_stack_pointer = None;
             // Set the stack pointer register to the callee stack id.
             // At the beginning of a function this is the only known pointer to the new stack frame.
             callee_state.set_register(
@@ -268,6 +301,7 @@ impl<'a> crate::analysis::forward_interprocedural_fixpoint::Context<'a> for Cont
         state_after_return.stack_id = original_caller_stack_id.clone();
         state_after_return.caller_stack_ids = state_before_call.caller_stack_ids.clone();
         state_after_return.ids_known_to_caller = state_before_call.ids_known_to_caller.clone();
+        state_after_return.initial_stack_pointer = state_before_call.initial_stack_pointer;
 
         state_after_return.readd_caller_objects(state_before_call);
 
@@ -315,12 +349,9 @@ impl<'a> crate::analysis::forward_interprocedural_fixpoint::Context<'a> for Cont
         if let Some(extern_symbol) = self.extern_symbol_map.get(call_target) {
             // Generate a CWE-message if some argument is an out-of-bounds pointer.
             self.check_parameter_register_for_out_of_bounds_pointer(state, call, extern_symbol);
+            let model = self.extern_function_models.get(&extern_symbol.name);
             // Check parameter for possible use-after-frees (except for possible double frees, which are handled later)
-            if !self
-                .deallocation_symbols
-                .iter()
-                .any(|free_like_fn| free_like_fn == extern_symbol.name.as_str())
-            {
+            if model.and_then(|model| model.freed_parameter).is_none() {
                 self.check_parameter_register_for_dangling_pointer(
                     &mut new_state,
                     call,
@@ -333,19 +364,21 @@ impl<'a> crate::analysis::forward_interprocedural_fixpoint::Context<'a> for Cont
             // Adjust stack register value (for x86 architecture).
             self.adjust_stack_register_on_extern_call(state, &mut new_state);
 
-            match extern_symbol.name.as_str() {
-                malloc_like_fn if self.allocation_symbols.iter().any(|x| x == malloc_like_fn) => {
-                    Some(self.add_new_object_in_call_return_register(
-                        state,
-                        new_state,
-                        call,
-                        extern_symbol,
-                    ))
-                }
-                free_like_fn if self.deallocation_symbols.iter().any(|x| x == free_like_fn) => {
-                    Some(self.mark_parameter_object_as_freed(state, new_state, call, extern_symbol))
-                }
-                _ => Some(self.handle_generic_extern_call(state, new_state, call, extern_symbol)),
+            match model {
+                Some(model) => Some(self.handle_modelled_extern_call(
+                    state,
+                    new_state,
+                    call,
+                    extern_symbol,
+                    model,
+                )),
+                None => Some(self.handle_generic_extern_call(
+                    state,
+                    new_state,
+                    call,
+                    extern_symbol,
+                    None,
+                )),
             }
         } else {
             panic!("Extern symbol not found.");
@@ -58,6 +58,7 @@ pub static CWE_MODULE: crate::CweModule = crate::CweModule {
     name: "Memory",
     version: VERSION,
     run: extract_pi_analysis_results,
+    check_config: crate::check_config::<Config>,
 };
 
 /// The abstract domain to use for absolute values.
@@ -69,16 +70,93 @@ pub type Data = DataDomain<ValueDomain>;
 /// Configurable parameters for the analysis.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Config {
-    /// Names of extern functions that are `malloc`-like,
-    /// i.e. the unique return value is a pointer to a newly allocated chunk of memory or a NULL pointer.
+    /// Models of the effects of extern functions on memory, keyed by the names of the functions.
+    ///
+    /// Calls to extern functions without a model are handled generically,
+    /// i.e. the called function may write to all memory objects reachable through its parameters.
+    #[serde(default)]
+    pub extern_function_models: BTreeMap<String, ExternFunctionModel>,
+    /// Names of extern functions that are `malloc`-like.
+    ///
+    /// Deprecated: Only supported for compatibility with older configuration files.
+    /// The symbols are translated to models in [`Config::get_extern_function_models`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub allocation_symbols: Vec<String>,
-    /// Names of extern functions that are `free`-like,
-    /// i.e. the memory chunk that the unique parameter of the function points to gets deallocated.
-    /// Note that the analysis currently does not detect mismatching allocation-deallocation pairs,
-    /// i.e. it cannot distinguish between memory allocated by `malloc` and memory allocated by `new`.
+    /// Names of extern functions that are `free`-like.
+    ///
+    /// Deprecated: Only supported for compatibility with older configuration files.
+    /// The symbols are translated to models in [`Config::get_extern_function_models`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub deallocation_symbols: Vec<String>,
 }
 
+impl Config {
+    /// Get the models of extern functions,
+    /// including models for the allocation and deallocation symbols of older configuration files.
+    ///
+    /// The allocation sizes of `malloc`, `calloc` and `realloc` are known,
+    /// for all other allocation symbols the size of the allocated memory is unknown.
+    /// Explicitly given models take precedence over models derived from the older configuration keys.
+    pub fn get_extern_function_models(&self) -> BTreeMap<String, ExternFunctionModel> {
+        let mut models = self.extern_function_models.clone();
+        for name in self.allocation_symbols.iter() {
+            let allocation_size_parameters = match name.as_str() {
+                "malloc" => vec![0],
+                "calloc" => vec![0, 1],
+                "realloc" => vec![1],
+                _ => Vec::new(),
+            };
+            models
+                .entry(name.clone())
+                .or_insert_with(|| ExternFunctionModel {
+                    allocation_size_parameters: Some(allocation_size_parameters),
+                    written_parameters: Some(Vec::new()),
+                    ..ExternFunctionModel::default()
+                });
+        }
+        for name in self.deallocation_symbols.iter() {
+            models
+                .entry(name.clone())
+                .or_insert_with(|| ExternFunctionModel {
+                    freed_parameter: Some(0),
+                    written_parameters: Some(Vec::new()),
+                    ..ExternFunctionModel::default()
+                });
+        }
+        models
+    }
+}
+
+/// A declarative model of the effects of an extern function on memory.
+///
+/// Parameters are referenced by their index in the parameter list of the extern symbol.
+/// Note that the analysis currently does not detect mismatching allocation-deallocation pairs,
+/// i.e. it cannot distinguish between memory allocated by `malloc` and memory allocated by `new`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Default)]
+pub struct ExternFunctionModel {
+    /// If set, the function is `malloc`-like,
+    /// i.e. the unique return value is a pointer to a newly allocated chunk of memory or a NULL pointer.
+    /// The size of the memory chunk is the product of the values of the parameters with the given indices,
+    /// e.g. `[0, 1]` for `calloc`.
+    /// If the list is empty, the size is unknown.
+    #[serde(default)]
+    pub allocation_size_parameters: Option<Vec<usize>>,
+    /// If set, the function is `free`-like,
+    /// i.e. the memory chunk that the parameter with the given index points to gets deallocated.
+    #[serde(default)]
+    pub freed_parameter: Option<usize>,
+    /// If set, the function returns the value of the parameter with the given index,
+    /// e.g. the destination parameter of `memcpy` or `strcpy`.
+    #[serde(default)]
+    pub returned_parameter: Option<usize>,
+    /// The indices of the parameters pointing to memory objects that the function may write to.
+    /// Memory objects reachable through pointers contained in these objects may also be written to.
+    ///
+    /// If not set, the function may write to all memory objects reachable through its parameters.
+    #[serde(default)]
+    pub written_parameters: Option<Vec<usize>>,
+}
+
 /// A wrapper struct for the pointer inference computation object.
 pub struct PointerInference<'a> {
     computation: Computation<GeneralizedContext<'a, Context<'a>>>,
@@ -165,6 +243,9 @@ impl<'a> PointerInference<'a> {
                 let _ = fn_entry_state
                     .set_mips_link_register(&sub_tid, project.stack_pointer_register.size);
             }
+            if project.vector_table_handlers.contains(&sub_tid) {
+                fn_entry_state.initial_stack_pointer = project.initial_stack_pointer;
+            }
             fixpoint_computation.set_node_value(
                 start_node_index,
                 super::interprocedural_fixpoint_generic::NodeValue::Value(fn_entry_state),
@@ -534,16 +615,37 @@ fn collect_all_logs(
 mod tests {
     use super::*;
 
+    impl Config {
+        /// A configuration modelling `malloc` and `free`.
+        pub fn mock() -> Config {
+            let malloc_model = ExternFunctionModel {
+                allocation_size_parameters: Some(vec![0]),
+                written_parameters: Some(Vec::new()),
+                ..ExternFunctionModel::default()
+            };
+            let free_model = ExternFunctionModel {
+                freed_parameter: Some(0),
+                written_parameters: Some(Vec::new()),
+                ..ExternFunctionModel::default()
+            };
+            Config {
+                extern_function_models: BTreeMap::from([
+                    ("malloc".to_string(), malloc_model),
+                    ("free".to_string(), free_model),
+                ]),
+                allocation_symbols: Vec::new(),
+                deallocation_symbols: Vec::new(),
+            }
+        }
+    }
+
     impl<'a> PointerInference<'a> {
         pub fn mock(
             project: &'a Project,
             mem_image: &'a RuntimeMemoryImage,
             graph: &'a Graph,
         ) -> PointerInference<'a> {
-            let config = Config {
-                allocation_symbols: vec!["malloc".to_string()],
-                deallocation_symbols: vec!["free".to_string()],
-            };
+            let config = Config::mock();
             let (log_sender, _) = crossbeam_channel::unbounded();
             PointerInference::new(project, mem_image, graph, config, log_sender, false)
         }
@@ -553,4 +655,68 @@ mod tests {
                 .set_node_value(node_index, NodeValue::Value(node_value));
         }
     }
+
+    #[test]
+    fn vector_table_handler_start_states() {
+        let mut project = Project::mock_empty();
+        for (sub_name, block_name) in [("Reset_Handler", "reset_start"), ("other", "other_start")] {
+            let mut sub = Sub::mock(sub_name);
+            sub.term.blocks.push(Blk::mock_with_tid(block_name));
+            project.program.term.entry_points.push(sub.tid.clone());
+            project.program.term.subs.push(sub);
+        }
+        project.initial_stack_pointer = Some(0x20001000);
+        project.vector_table_handlers = vec![Tid::new("Reset_Handler")];
+        let mem_image = RuntimeMemoryImage::mock();
+        let graph = crate::analysis::graph::get_program_cfg(
+            &project.program,
+            std::collections::HashSet::new(),
+        );
+        let pointer_inference = PointerInference::mock(&project, &mem_image, &graph);
+        let start_states: HashMap<&str, Option<u64>> = graph
+            .node_references()
+            .filter_map(
+                |(node_id, node)| match (node, pointer_inference.get_node_value(node_id)) {
+                    (Node::BlkStart(_, sub), Some(NodeValue::Value(state))) => {
+                        Some((sub.term.name.as_str(), state.initial_stack_pointer))
+                    }
+                    _ => None,
+                },
+            )
+            .collect();
+        assert_eq!(
+            start_states,
+            HashMap::from([("Reset_Handler", Some(0x20001000)), ("other", None)])
+        );
+    }
+
+    #[test]
+    fn config_with_allocation_symbols() {
+        let config: Config = serde_json::from_str(
+            r#"{ "allocation_symbols": ["malloc", "xmalloc"], "deallocation_symbols": ["free"] }"#,
+        )
+        .unwrap();
+        let models = config.get_extern_function_models();
+        assert_eq!(models.len(), 3);
+        assert_eq!(
+            models["malloc"],
+            Config::mock().extern_function_models["malloc"]
+        );
+        assert_eq!(
+            models["free"],
+            Config::mock().extern_function_models["free"]
+        );
+        assert_eq!(
+            models["xmalloc"].allocation_size_parameters,
+            Some(Vec::new())
+        );
+
+        // Explicit models take precedence over the deprecated keys.
+        let mut config = Config::mock();
+        config.allocation_symbols = vec!["free".to_string()];
+        assert_eq!(
+            config.get_extern_function_models(),
+            Config::mock().extern_function_models
+        );
+    }
 }
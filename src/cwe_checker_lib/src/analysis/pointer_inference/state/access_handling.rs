@@ -1,4 +1,5 @@
 use crate::utils::binary::RuntimeMemoryImage;
+use crate::utils::log::Confidence;
 
 use super::*;
 
@@ -301,6 +302,27 @@ impl State {
             .is_out_of_bounds_mem_access(&data, ByteSize::new(1), global_data)
     }
 
+    /// Get the confidence in a CWE warning about the memory objects targeted by the given pointer.
+    ///
+    /// The confidence is high if the pointer has exactly one target representing exactly one memory object.
+    /// It is low if the targets of the pointer are only partially tracked,
+    /// i.e. if the pointer may also contain `Top` values.
+    /// In all other cases, e.g. if the pointer has several targets, the confidence is medium.
+    pub fn get_pointer_confidence(&self, pointer: &Data) -> Confidence {
+        let pointer = self.adjust_pointer_for_read(pointer);
+        if pointer.contains_top() {
+            Confidence::Low
+        } else if let Some((id, _)) = pointer.get_if_unique_target() {
+            if self.memory.is_unique_object(id).unwrap_or(false) {
+                Confidence::High
+            } else {
+                Confidence::Medium
+            }
+        } else {
+            Confidence::Medium
+        }
+    }
+
     /// Return `true` if `data` is a pointer to the current stack frame with a constant positive address,
     /// i.e. if it accesses a stack parameter (or the return-to address for x86) of the current function.
     pub fn is_stack_pointer_with_nonnegative_offset(&self, data: &Data) -> bool {
@@ -35,6 +35,15 @@ pub struct State {
     /// Note that IDs that the callee should not have access to are not included here.
     /// For these IDs the caller can assume that the contents of the corresponding memory object were not accessed or modified by the call.
     pub ids_known_to_caller: BTreeSet<AbstractIdentifier>,
+    /// The initial stack pointer value of a bare metal binary,
+    /// if the current function is one of the handlers in the vector table of the binary
+    /// (see [`Project::vector_table_handlers`]).
+    ///
+    /// The handlers run on the stack starting at the initial stack pointer.
+    /// Thus if a handler sets the stack pointer register to this value, the stack is reset,
+    /// which is modelled as a reset to the start of the current stack frame.
+    /// The value is not passed on to callees.
+    pub initial_stack_pointer: Option<u64>,
 }
 
 impl State {
@@ -59,6 +68,7 @@ impl State {
             stack_id,
             caller_stack_ids: BTreeSet::new(),
             ids_known_to_caller: BTreeSet::new(),
+            initial_stack_pointer: None,
         }
     }
 
@@ -749,6 +759,11 @@ impl AbstractDomain for State {
                 .union(&other.ids_known_to_caller)
                 .cloned()
                 .collect(),
+            initial_stack_pointer: if self.initial_stack_pointer == other.initial_stack_pointer {
+                self.initial_stack_pointer
+            } else {
+                None
+            },
         }
     }
 
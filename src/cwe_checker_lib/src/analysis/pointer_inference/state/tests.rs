@@ -1,5 +1,6 @@
 use super::*;
 use crate::utils::binary::RuntimeMemoryImage;
+use crate::utils::log::Confidence;
 
 fn bv(value: i64) -> ValueDomain {
     ValueDomain::from(Bitvector::from_i64(value))
@@ -1053,6 +1054,42 @@ fn out_of_bounds_access_recognition() {
     assert!(!state.contains_out_of_bounds_mem_access(&load_def.term, &global_data));
 }
 
+#[test]
+fn pointer_confidence() {
+    let mut state = State::new(&register("RSP"), Tid::new("func_tid"));
+    let heap_obj_id = new_id("heap_malloc", "RAX");
+    let other_heap_obj_id = new_id("heap_calloc", "RAX");
+    for id in [&heap_obj_id, &other_heap_obj_id] {
+        state.memory.add_abstract_object(
+            id.clone(),
+            bv(0),
+            crate::analysis::pointer_inference::object::ObjectType::Heap,
+            ByteSize::new(8),
+        );
+    }
+    let pointer = Data::from_target(heap_obj_id.clone(), bv(0));
+    assert_eq!(state.get_pointer_confidence(&pointer), Confidence::High);
+    let other_pointer = Data::from_target(other_heap_obj_id.clone(), bv(0));
+    assert_eq!(
+        state.get_pointer_confidence(&pointer.merge(&other_pointer)),
+        Confidence::Medium
+    );
+    let mut partially_tracked_pointer = pointer.clone();
+    partially_tracked_pointer.set_contains_top_flag();
+    assert_eq!(
+        state.get_pointer_confidence(&partially_tracked_pointer),
+        Confidence::Low
+    );
+    // The object may represent more than one memory object after adding it a second time.
+    state.memory.add_abstract_object(
+        heap_obj_id,
+        bv(0),
+        crate::analysis::pointer_inference::object::ObjectType::Heap,
+        ByteSize::new(8),
+    );
+    assert_eq!(state.get_pointer_confidence(&pointer), Confidence::Medium);
+}
+
 #[test]
 fn specialize_pointer_comparison() {
     let mut state = State::new(&register("RSP"), Tid::new("func_tid"));
@@ -34,8 +34,8 @@ use crate::intermediate_representation::Jmp;
 use crate::intermediate_representation::Variable;
 use crate::prelude::*;
 use crate::utils::binary::RuntimeMemoryImage;
-use crate::utils::log::CweWarning;
 use crate::utils::log::LogMessage;
+use crate::utils::log::{Confidence, CweWarning, Severity};
 use crate::CweModule;
 
 /// The module name and version
@@ -43,6 +43,7 @@ pub static CWE_MODULE: CweModule = CweModule {
     name: "CWE134",
     version: "0.1",
     run: check_cwe,
+    check_config: crate::check_config::<Config>,
 };
 
 /// The configuration struct
@@ -63,6 +64,8 @@ pub enum StringLocation {
     GlobalWriteable,
     /// Non Global memory
     NonGlobal,
+    /// Untracked memory, e.g. if the string pointer may contain `Top` values
+    Untracked,
     /// Unknown memory
     Unknown,
 }
@@ -87,7 +90,7 @@ pub fn check_cwe(
         if let Edge::ExternCallStub(jmp) = edge.weight() {
             if let Jmp::Call { target, .. } = &jmp.term {
                 if let Some(symbol) = format_string_symbols.get(target) {
-                    let location = locate_format_string(
+                    let (location, string_origin) = locate_format_string(
                         &edge.source(),
                         symbol,
                         &format_string_index,
@@ -98,9 +101,15 @@ pub fn check_cwe(
 
                     if matches!(
                         location,
-                        StringLocation::GlobalWriteable | StringLocation::NonGlobal
+                        StringLocation::GlobalWriteable
+                            | StringLocation::NonGlobal
+                            | StringLocation::Untracked
                     ) {
-                        cwe_warnings.push(generate_cwe_warning(&jmp.tid, symbol, &location));
+                        let mut warning = generate_cwe_warning(&jmp.tid, symbol, &location);
+                        if let Some(origin) = string_origin {
+                            warning = warning.trace_source_and_sink(&origin, &jmp.tid);
+                        }
+                        cwe_warnings.push(warning);
                     }
                 }
             }
@@ -115,6 +124,10 @@ pub fn check_cwe(
 /// holding the string.
 /// If no assumption about the string location can be made,
 /// unknown is returned.
+///
+/// Also returns the origin of the memory object holding the string if known,
+/// i.e. the allocation site for heap objects or the start of the function for stack frames.
+/// It is used as the source of the witness trace of the CWE warning.
 fn locate_format_string(
     node: &NodeIndex,
     symbol: &ExternSymbol,
@@ -122,34 +135,44 @@ fn locate_format_string(
     pointer_inference_results: &PointerInference,
     runtime_memory_image: &RuntimeMemoryImage,
     stack_pointer: &Variable,
-) -> StringLocation {
+) -> (StringLocation, Option<Tid>) {
     if let Some(NodeValue::Value(pi_state)) = pointer_inference_results.get_node_value(*node) {
         let format_string_parameter = symbol
             .parameters
             .get(*format_string_index.get(&symbol.name).unwrap())
             .unwrap();
-        if let Ok(address) = pi_state.eval_parameter_arg(
+        match pi_state.eval_parameter_arg(
             format_string_parameter,
             stack_pointer,
             runtime_memory_image,
         ) {
-            if let Ok(address_vector) = address.try_to_bitvec() {
-                if runtime_memory_image.is_global_memory_address(&address_vector) {
-                    if runtime_memory_image
-                        .is_address_writeable(&address_vector)
-                        .unwrap()
-                    {
-                        return StringLocation::GlobalWriteable;
-                    }
+            Ok(address) => {
+                if let Ok(address_vector) = address.try_to_bitvec() {
+                    if runtime_memory_image.is_global_memory_address(&address_vector) {
+                        if runtime_memory_image
+                            .is_address_writeable(&address_vector)
+                            .unwrap()
+                        {
+                            return (StringLocation::GlobalWriteable, None);
+                        }
 
-                    return StringLocation::GlobalReadable;
+                        return (StringLocation::GlobalReadable, None);
+                    }
                 }
+                let origin = address
+                    .referenced_ids()
+                    .next()
+                    .map(|id| id.get_tid().clone());
+                if address.contains_top() {
+                    return (StringLocation::Untracked, origin);
+                }
+                return (StringLocation::NonGlobal, origin);
             }
+            Err(_) => return (StringLocation::Untracked, None),
         }
-        return StringLocation::NonGlobal;
     }
 
-    StringLocation::Unknown
+    (StringLocation::Unknown, None)
 }
 
 /// Generate the CWE warning for a detected instance of the CWE.
@@ -165,7 +188,7 @@ fn generate_cwe_warning(
             called_symbol.name, callsite.address
         )
         }
-        StringLocation::NonGlobal => {
+        StringLocation::NonGlobal | StringLocation::Untracked => {
             format!(
             "(Externally Controlled Format String) Potential externally controlled format string for call to {} at {}",
             called_symbol.name, callsite.address
@@ -173,10 +196,16 @@ fn generate_cwe_warning(
         }
         _ => panic!("Invalid String Location."),
     };
+    let confidence = match location {
+        StringLocation::Untracked => Confidence::Low,
+        _ => Confidence::Medium,
+    };
     CweWarning::new(CWE_MODULE.name, CWE_MODULE.version, description)
         .tids(vec![format!("{}", callsite)])
         .addresses(vec![callsite.address.clone()])
         .symbols(vec![called_symbol.name.clone()])
+        .severity(Severity::High)
+        .confidence(confidence)
 }
 
 #[cfg(test)]
@@ -248,7 +277,7 @@ pub mod tests {
                 &runtime_memory_image,
                 &stack_pointer
             ),
-            StringLocation::GlobalReadable
+            (StringLocation::GlobalReadable, None)
         );
     }
 }
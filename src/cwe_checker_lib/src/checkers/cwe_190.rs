@@ -30,7 +30,7 @@
 
 use crate::intermediate_representation::*;
 use crate::prelude::*;
-use crate::utils::log::{CweWarning, LogMessage};
+use crate::utils::log::{Confidence, CweWarning, LogMessage, Severity};
 use crate::utils::symbol_utils::{get_callsites, get_symbol_map};
 use crate::CweModule;
 
@@ -39,6 +39,7 @@ pub static CWE_MODULE: CweModule = CweModule {
     name: "CWE190",
     version: "0.1",
     run: check_cwe,
+    check_config: crate::check_config::<Config>,
 };
 
 /// The configuration struct.
@@ -95,6 +96,8 @@ fn generate_cwe_warning(callsite: &Tid, called_symbol: &ExternSymbol) -> CweWarn
         .tids(vec![format!("{}", callsite)])
         .addresses(vec![callsite.address.clone()])
         .symbols(vec![called_symbol.name.clone()])
+        .severity(Severity::High)
+        .confidence(Confidence::Low)
 }
 
 /// Run the CWE check.
@@ -19,7 +19,7 @@
 //! None known.
 
 use crate::prelude::*;
-use crate::utils::log::{CweWarning, LogMessage};
+use crate::utils::log::{Confidence, CweWarning, LogMessage, Severity};
 use crate::CweModule;
 
 /// The module name and version
@@ -27,6 +27,7 @@ pub static CWE_MODULE: CweModule = CweModule {
     name: "CWE215",
     version: "0.2",
     run: check_cwe,
+    check_config: crate::check_config::<serde::de::IgnoredAny>,
 };
 
 /// Run the check.
@@ -48,7 +49,9 @@ pub fn check_cwe(
                             CWE_MODULE.name,
                             CWE_MODULE.version,
                             "(Information Exposure Through Debug Information) The binary contains debug symbols."
-                        );
+                        )
+                        .severity(Severity::Low)
+                        .confidence(Confidence::High);
                         return (Vec::new(), vec![cwe_warning]);
                     }
                 }
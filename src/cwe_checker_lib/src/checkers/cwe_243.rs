@@ -29,7 +29,7 @@ use crate::analysis::graph::Node;
 use crate::intermediate_representation::*;
 use crate::prelude::*;
 use crate::utils::graph_utils::is_sink_call_reachable_from_source_call;
-use crate::utils::log::{CweWarning, LogMessage};
+use crate::utils::log::{Confidence, CweWarning, LogMessage, Severity};
 use crate::utils::symbol_utils::find_symbol;
 use crate::CweModule;
 
@@ -38,6 +38,7 @@ pub static CWE_MODULE: CweModule = CweModule {
     name: "CWE243",
     version: "0.2",
     run: check_cwe,
+    check_config: crate::check_config::<Config>,
 };
 
 /// The configuration struct contains the list of functions
@@ -102,6 +103,8 @@ fn generate_cwe_warning(sub: &Term<Sub>, callsite: &Tid) -> CweWarning {
         .tids(vec![format!("{}", callsite)])
         .addresses(vec![callsite.address.clone()])
         .symbols(vec![sub.term.name.clone()])
+        .severity(Severity::Medium)
+        .confidence(Confidence::High)
 }
 
 /// Run the check.
@@ -21,7 +21,7 @@
 //! - It is not checked whether the seeding function gets called before the random number generator function.
 
 use crate::prelude::*;
-use crate::utils::log::{CweWarning, LogMessage};
+use crate::utils::log::{Confidence, CweWarning, LogMessage, Severity};
 use crate::utils::symbol_utils::find_symbol;
 use crate::CweModule;
 
@@ -30,6 +30,7 @@ pub static CWE_MODULE: CweModule = CweModule {
     name: "CWE332",
     version: "0.1",
     run: check_cwe,
+    check_config: crate::check_config::<Config>,
 };
 
 /// The configuration struct contains pairs of symbol names,
@@ -52,6 +53,8 @@ fn generate_cwe_warning(secure_initializer_func: &str, rand_func: &str) -> CweWa
             rand_func, secure_initializer_func
         ),
     )
+    .severity(Severity::Low)
+    .confidence(Confidence::High)
 }
 
 /// Run the CWE check. See the module-level description for more information.
@@ -26,7 +26,7 @@ use crate::analysis::graph::{Edge, Node};
 use crate::intermediate_representation::Jmp;
 use crate::prelude::*;
 use crate::utils::graph_utils::is_sink_call_reachable_from_source_call;
-use crate::utils::log::{CweWarning, LogMessage};
+use crate::utils::log::{Confidence, CweWarning, LogMessage, Severity};
 use crate::CweModule;
 use petgraph::visit::EdgeRef;
 use std::collections::HashMap;
@@ -36,6 +36,7 @@ pub static CWE_MODULE: CweModule = CweModule {
     name: "CWE367",
     version: "0.1",
     run: check_cwe,
+    check_config: crate::check_config::<Config>,
 };
 
 /// The configuration struct contains pairs of the form `(source_symbol, sink_symbol)`.
@@ -64,6 +65,8 @@ fn generate_cwe_warning(
         .tids(vec![format!("{}", source_callsite), format!("{}", sink_callsite)])
         .addresses(vec![source_callsite.address, sink_callsite.address])
         .symbols(vec![source.into(), sink.into()])
+        .severity(Severity::Medium)
+        .confidence(Confidence::Low)
 }
 
 /// Run the check. See the module-level documentation for more information.
@@ -33,7 +33,7 @@
 
 use crate::intermediate_representation::*;
 use crate::prelude::*;
-use crate::utils::log::{CweWarning, LogMessage};
+use crate::utils::log::{Confidence, CweWarning, LogMessage, Severity};
 use crate::utils::symbol_utils::{find_symbol, get_calls_to_symbols};
 use crate::CweModule;
 use std::collections::HashMap;
@@ -43,6 +43,7 @@ pub static CWE_MODULE: CweModule = CweModule {
     name: "CWE426",
     version: "0.1",
     run: check_cwe,
+    check_config: crate::check_config::<Config>,
 };
 
 /// Function symbols read from *config.json*.
@@ -65,6 +66,8 @@ fn generate_cwe_warning(sub: &Term<Sub>) -> CweWarning {
     .tids(vec![format!("{}", sub.tid)])
     .addresses(vec![sub.tid.address.clone()])
     .symbols(vec![sub.term.name.clone()])
+    .severity(Severity::Medium)
+    .confidence(Confidence::Low)
 }
 
 /// Run the CWE check.
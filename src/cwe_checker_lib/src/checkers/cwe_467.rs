@@ -25,7 +25,7 @@ use crate::analysis::pointer_inference::State;
 use crate::intermediate_representation::*;
 use crate::prelude::*;
 use crate::utils::binary::RuntimeMemoryImage;
-use crate::utils::log::{CweWarning, LogMessage};
+use crate::utils::log::{Confidence, CweWarning, LogMessage, Severity};
 use crate::utils::symbol_utils::{get_callsites, get_symbol_map};
 use crate::CweModule;
 
@@ -34,6 +34,7 @@ pub static CWE_MODULE: CweModule = CweModule {
     name: "CWE467",
     version: "0.2",
     run: check_cwe,
+    check_config: crate::check_config::<Config>,
 };
 
 /// Function symbols read from *config.json*.
@@ -104,6 +105,8 @@ fn generate_cwe_warning(jmp: &Term<Jmp>, extern_symbol: &ExternSymbol) -> CweWar
     )
     .tids(vec![format!("{}", jmp.tid)])
     .addresses(vec![jmp.tid.address.clone()])
+    .severity(Severity::Medium)
+    .confidence(Confidence::Low)
 }
 
 /// Execute the CWE check.
@@ -61,6 +61,7 @@ pub static CWE_MODULE: CweModule = CweModule {
     name: "CWE476",
     version: "0.3",
     run: check_cwe,
+    check_config: crate::check_config::<Config>,
 };
 
 /// The configuration struct
@@ -9,7 +9,7 @@ use crate::analysis::pointer_inference::PointerInference as PointerInferenceComp
 use crate::analysis::pointer_inference::State as PointerInferenceState;
 use crate::intermediate_representation::*;
 use crate::utils::binary::RuntimeMemoryImage;
-use crate::utils::log::CweWarning;
+use crate::utils::log::{Confidence, CweWarning, Severity};
 use petgraph::graph::NodeIndex;
 use petgraph::visit::IntoNodeReferences;
 use std::collections::HashMap;
@@ -160,7 +160,12 @@ impl<'a> Context<'a> {
     }
 
     /// Generate a CWE warning for the taint source of the context object.
-    fn generate_cwe_warning(&self, taint_access_location: &Tid) {
+    ///
+    /// The confidence of the warning depends on how the taint was accessed:
+    /// It is high for memory accesses through a tainted pointer,
+    /// medium if the taint is passed to a function with known parameters
+    /// and low if it is passed to a function with unknown parameters or returned to the caller.
+    fn generate_cwe_warning(&self, taint_access_location: &Tid, confidence: Confidence) {
         let taint_source = self.taint_source.unwrap();
         let taint_source_name = self.taint_source_name.clone().unwrap();
         let cwe_warning = CweWarning::new(CWE_MODULE.name, CWE_MODULE.version,
@@ -168,22 +173,25 @@ impl<'a> Context<'a> {
             taint_source.tid.address, taint_source_name))
             .addresses(vec![taint_source.tid.address.clone(), taint_access_location.address.clone()])
             .tids(vec![format!("{}", taint_source.tid), format!("{}", taint_access_location)])
-            .symbols(vec![taint_source_name]);
+            .symbols(vec![taint_source_name])
+            .severity(Severity::Medium)
+            .confidence(confidence)
+            .trace_source_and_sink(&taint_source.tid, taint_access_location);
         let _ = self.cwe_collector.send(cwe_warning);
     }
 
-    /// Check parameters of an extern symbol for taint.
+    /// Check the given parameters of a called function (e.g. of an extern symbol) for taint.
     /// For pointers as parameters we also check
     /// whether the pointer points directly to taint if it points to some stack address.
     /// or whether the pointed to object contains any taint at all if it is not a stack object.
     pub fn check_parameters_for_taint(
         &self,
         state: &State,
-        extern_symbol: &ExternSymbol,
+        parameters: &[Arg],
         node_id: NodeIndex,
     ) -> bool {
         // First check for taint directly in parameter registers (we don't need a pointer inference state for that)
-        for parameter in extern_symbol.parameters.iter() {
+        for parameter in parameters.iter() {
             if let Arg::Register { var, .. } = parameter {
                 if state.eval(&Expression::Var(var.clone())).is_tainted() {
                     return true;
@@ -194,7 +202,7 @@ impl<'a> Context<'a> {
             self.pointer_inference_results.get_node_value(node_id)
         {
             // Check stack parameters and collect referenced memory object that need to be checked for taint.
-            for parameter in extern_symbol.parameters.iter() {
+            for parameter in parameters.iter() {
                 match parameter {
                     Arg::Register { var, .. } => {
                         let data = pi_state.eval(&Expression::Var(var.clone()));
@@ -235,7 +243,7 @@ impl<'a> Context<'a> {
     fn handle_generic_call(&self, state: &State, call_tid: &Tid) -> Option<State> {
         let pi_state_option = self.get_current_pointer_inference_state(state, call_tid);
         if state.check_generic_function_params_for_taint(self.project, pi_state_option.as_ref()) {
-            self.generate_cwe_warning(call_tid);
+            self.generate_cwe_warning(call_tid, Confidence::Low);
             return None;
         }
         let mut new_state = state.clone();
@@ -271,11 +279,35 @@ impl<'a> crate::analysis::forward_interprocedural_fixpoint::Context<'a> for Cont
     }
 
     /// Generate a CWE warning if taint may be contained in the function parameters.
+    /// If the signature of the called function is known, only its parameters are checked.
+    /// Otherwise (or if the node of the call site is not found)
+    /// all parameter registers of the standard calling convention are checked.
     /// Always returns `None` so that the analysis stays intraprocedural.
-    fn update_call(&self, state: &State, call: &Term<Jmp>, _target: &Node) -> Option<Self::Value> {
-        let pi_state_option = self.get_current_pointer_inference_state(state, &call.tid);
-        if state.check_generic_function_params_for_taint(self.project, pi_state_option.as_ref()) {
-            self.generate_cwe_warning(&call.tid);
+    fn update_call(&self, state: &State, call: &Term<Jmp>, target: &Node) -> Option<Self::Value> {
+        let signature_taint = match (&target.get_sub().term.signature, self.current_sub) {
+            (Some(signature), Some(current_sub)) => self
+                .jmp_to_blk_end_node_map
+                .get(&(call.tid.clone(), current_sub.tid.clone()))
+                .map(|blk_end_node_id| {
+                    self.check_parameters_for_taint(state, &signature.parameters, *blk_end_node_id)
+                }),
+            _ => None,
+        };
+        let (is_tainted, confidence) = match signature_taint {
+            Some(is_tainted) => (is_tainted, Confidence::Medium),
+            None => {
+                let pi_state_option = self.get_current_pointer_inference_state(state, &call.tid);
+                (
+                    state.check_generic_function_params_for_taint(
+                        self.project,
+                        pi_state_option.as_ref(),
+                    ),
+                    Confidence::Low,
+                )
+            }
+        };
+        if is_tainted {
+            self.generate_cwe_warning(&call.tid, confidence);
         }
         None
     }
@@ -293,8 +325,12 @@ impl<'a> crate::analysis::forward_interprocedural_fixpoint::Context<'a> for Cont
                         .jmp_to_blk_end_node_map
                         .get(&(call.tid.clone(), self.current_sub.unwrap().tid.clone()))
                         .unwrap();
-                    if self.check_parameters_for_taint(state, extern_symbol, *blk_end_node_id) {
-                        self.generate_cwe_warning(&call.tid);
+                    if self.check_parameters_for_taint(
+                        state,
+                        &extern_symbol.parameters,
+                        *blk_end_node_id,
+                    ) {
+                        self.generate_cwe_warning(&call.tid, Confidence::Medium);
                         return None;
                     }
                     let mut new_state = state.clone();
@@ -326,7 +362,7 @@ impl<'a> crate::analysis::forward_interprocedural_fixpoint::Context<'a> for Cont
             }
             Def::Load { var, address } => {
                 if state.eval(address).is_tainted() {
-                    self.generate_cwe_warning(&def.tid);
+                    self.generate_cwe_warning(&def.tid, Confidence::High);
                     return None;
                 } else if let Some(pi_state) =
                     self.get_current_pointer_inference_state(state, &def.tid)
@@ -340,7 +376,7 @@ impl<'a> crate::analysis::forward_interprocedural_fixpoint::Context<'a> for Cont
             }
             Def::Store { address, value } => {
                 if state.eval(address).is_tainted() {
-                    self.generate_cwe_warning(&def.tid);
+                    self.generate_cwe_warning(&def.tid, Confidence::High);
                     return None;
                 } else if let Some(pi_state) =
                     self.get_current_pointer_inference_state(state, &def.tid)
@@ -407,7 +443,7 @@ impl<'a> crate::analysis::forward_interprocedural_fixpoint::Context<'a> for Cont
             // If taint is returned, generate a CWE warning
             let pi_state_option = self.get_current_pointer_inference_state(state, &return_term.tid);
             if state.check_return_values_for_taint(self.project, pi_state_option.as_ref()) {
-                self.generate_cwe_warning(&return_term.tid);
+                self.generate_cwe_warning(&return_term.tid, Confidence::Low);
             }
             // Do not return early in case `state_before_call` is also set (possible for recursive functions).
         }
@@ -458,7 +494,11 @@ mod tests {
         let (mut state, _pi_state) = State::mock_with_pi_state();
 
         assert_eq!(
-            context.check_parameters_for_taint(&state, &ExternSymbol::mock(), NodeIndex::new(0)),
+            context.check_parameters_for_taint(
+                &state,
+                &ExternSymbol::mock().parameters,
+                NodeIndex::new(0)
+            ),
             false
         );
 
@@ -467,7 +507,11 @@ mod tests {
             Taint::Tainted(ByteSize::new(8)),
         );
         assert_eq!(
-            context.check_parameters_for_taint(&state, &ExternSymbol::mock(), NodeIndex::new(0)),
+            context.check_parameters_for_taint(
+                &state,
+                &ExternSymbol::mock().parameters,
+                NodeIndex::new(0)
+            ),
             true
         );
     }
@@ -494,6 +538,51 @@ mod tests {
             .is_none());
     }
 
+    #[test]
+    fn update_call_with_unknown_call_site() {
+        let project = Project::mock_empty();
+        let runtime_memory_image = RuntimeMemoryImage::mock();
+        let graph = crate::analysis::graph::get_program_cfg(&project.program, HashSet::new());
+        let pi_results = PointerInferenceComputation::mock(&project, &runtime_memory_image, &graph);
+        let (cwe_sender, cwe_receiver) = crossbeam_channel::unbounded();
+        let mut context = Context::new(&project, &runtime_memory_image, &pi_results, cwe_sender);
+        let taint_source = Term {
+            tid: Tid::new("taint_source"),
+            term: Jmp::Call {
+                target: Tid::new("malloc"),
+                return_: None,
+            },
+        };
+        let current_sub = Sub::mock("current_sub");
+        context.set_taint_source(&taint_source, &current_sub);
+        let (mut state, pi_state) = State::mock_with_pi_state();
+        state.set_pointer_inference_state(Some(pi_state));
+        state.set_register_taint(
+            &Variable::mock("RDX", 8u64),
+            Taint::Tainted(ByteSize::new(8)),
+        );
+        // The callee has a known signature, but the call site is not contained in the graph.
+        let mut callee = Sub::mock("callee");
+        callee.term.signature = Some(FunctionSignature {
+            parameters: vec![Arg::mock_register("RDX", 8)],
+            return_values: Vec::new(),
+        });
+        let block = Blk::mock();
+        let call = Term {
+            tid: Tid::new("call"),
+            term: Jmp::Call {
+                target: Tid::new("callee"),
+                return_: None,
+            },
+        };
+
+        assert!(context
+            .update_call(&state, &call, &Node::BlkStart(&block, &callee))
+            .is_none());
+        let warning = cwe_receiver.try_recv().unwrap();
+        assert_eq!(warning.confidence, Confidence::Low);
+    }
+
     #[test]
     fn update_def() {
         let project = Project::mock_empty();
@@ -27,7 +27,7 @@ use crate::analysis::pointer_inference::State;
 use crate::intermediate_representation::*;
 use crate::prelude::*;
 use crate::utils::binary::RuntimeMemoryImage;
-use crate::utils::log::{CweWarning, LogMessage};
+use crate::utils::log::{Confidence, CweWarning, LogMessage, Severity};
 use crate::utils::symbol_utils::{get_callsites, get_symbol_map};
 use crate::CweModule;
 
@@ -36,6 +36,7 @@ pub static CWE_MODULE: CweModule = CweModule {
     name: "CWE560",
     version: "0.2",
     run: check_cwe,
+    check_config: crate::check_config::<serde::de::IgnoredAny>,
 };
 
 /// An upper bound for the value of a presumably correct umask argument.
@@ -96,6 +97,8 @@ fn generate_cwe_warning(sub: &Term<Sub>, jmp: &Term<Jmp>, permission_const: u64)
             "umask_arg".to_string(),
             format!("{:#o}", permission_const),
         ]])
+        .severity(Severity::Low)
+        .confidence(Confidence::High)
 }
 
 /// Execute the CWE check.
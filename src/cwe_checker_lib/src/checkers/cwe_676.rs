@@ -24,7 +24,7 @@ use std::collections::HashMap;
 use crate::{
     intermediate_representation::{ExternSymbol, Program, Sub, Term, Tid},
     utils::{
-        log::{CweWarning, LogMessage},
+        log::{Confidence, CweWarning, LogMessage, Severity},
         symbol_utils::get_calls_to_symbols,
     },
 };
@@ -37,6 +37,7 @@ pub static CWE_MODULE: crate::CweModule = crate::CweModule {
     name: "CWE676",
     version: VERSION,
     run: check_cwe,
+    check_config: crate::check_config::<Config>,
 };
 
 /// struct containing dangerous symbols from config.json
@@ -84,7 +85,9 @@ pub fn generate_cwe_warnings<'a>(
         .other(vec![vec![
             String::from("dangerous_function"),
             String::from(*target_name),
-        ]]);
+        ]])
+        .severity(Severity::Low)
+        .confidence(Confidence::Low);
 
         cwe_warnings.push(cwe_warning);
     }
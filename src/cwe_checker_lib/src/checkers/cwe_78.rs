@@ -49,9 +49,9 @@ use crate::{
         graph::{self, Edge, Node},
         interprocedural_fixpoint_generic::NodeValue,
     },
-    intermediate_representation::{ExternSymbol, Jmp, Project, Sub},
+    intermediate_representation::{ExternSymbol, FunctionSignature, Jmp, Project, Sub},
     prelude::*,
-    utils::log::{CweWarning, LogMessage},
+    utils::log::{Confidence, CweWarning, LogMessage},
     AnalysisResults, CweModule,
 };
 
@@ -70,6 +70,7 @@ pub static CWE_MODULE: CweModule = CweModule {
     name: "CWE78",
     version: "0.1",
     run: check_cwe,
+    check_config: crate::check_config::<Config>,
 };
 
 /// The configuration struct
@@ -152,7 +153,15 @@ pub fn check_cwe(
                         if let Some(node_weight) = computation.get_node_value(*node_index) {
                             let state = node_weight.unwrap_value();
                             if !state.is_empty() {
-                                context.generate_cwe_warning(sub_name);
+                                // The taint may only have reached the program entry
+                                // because the pointer inference lost track of the tainted values.
+                                let entry_block =
+                                    general_context.get_pi_graph()[*node_index].get_block();
+                                context.generate_cwe_warning(
+                                    sub_name,
+                                    &entry_block.tid,
+                                    Confidence::Low,
+                                );
                             }
                         }
                     }
@@ -221,11 +230,14 @@ fn get_entry_sub_to_entry_node_map(
 ///     - Maps the TID of an extern symbol to the extern symbol struct.
 /// - format_string_index:
 ///     - Maps a symbol name to the index of its format string parameter.
+/// - sub_signature_map:
+///     - Maps the TID of a function with known signature to its signature.
 pub struct SymbolMaps<'a> {
     string_symbol_map: HashMap<Tid, &'a ExternSymbol>,
     user_input_symbol_map: HashMap<Tid, &'a ExternSymbol>,
     extern_symbol_map: HashMap<Tid, &'a ExternSymbol>,
     format_string_index: HashMap<String, usize>,
+    sub_signature_map: HashMap<Tid, &'a FunctionSignature>,
 }
 
 impl<'a> SymbolMaps<'a> {
@@ -246,6 +258,13 @@ impl<'a> SymbolMaps<'a> {
             ),
             extern_symbol_map,
             format_string_index: config.format_string_index.clone(),
+            sub_signature_map: project
+                .program
+                .term
+                .subs
+                .iter()
+                .filter_map(|sub| Some((sub.tid.clone(), sub.term.signature.as_ref()?)))
+                .collect(),
         }
     }
 }
@@ -15,7 +15,10 @@ use crate::{
     },
     checkers::cwe_476::Taint,
     intermediate_representation::*,
-    utils::{binary::RuntimeMemoryImage, log::CweWarning},
+    utils::{
+        binary::RuntimeMemoryImage,
+        log::{Confidence, CweWarning, Severity},
+    },
 };
 
 pub mod parameter_detection;
@@ -88,7 +91,18 @@ impl<'a> Context<'a> {
     }
 
     /// Generates the CWE Warning for the CWE 78 check
-    pub fn generate_cwe_warning(&self, sub_name: &str) {
+    ///
+    /// The confidence of the warning is given by the confidence
+    /// in the pointer to the tainted memory (see [`PointerInferenceState::get_pointer_confidence`]).
+    /// The witness trace of the warning leads from the `input_location`,
+    /// i.e. the call to the user input function or the start of the program entry function,
+    /// to the call to the system function.
+    pub fn generate_cwe_warning(
+        &self,
+        sub_name: &str,
+        input_location: &Tid,
+        confidence: Confidence,
+    ) {
         let source = self.taint_source.unwrap();
         let name = self.taint_source_name.clone().unwrap();
         let description: String = format!(
@@ -103,7 +117,10 @@ impl<'a> Context<'a> {
         .addresses(vec![source.tid.address.clone()])
         .tids(vec![format!("{}", source.tid)])
         .symbols(vec![String::from(sub_name)])
-        .other(vec![vec![String::from("OS Command Injection"), name]]);
+        .other(vec![vec![String::from("OS Command Injection"), name]])
+        .severity(Severity::High)
+        .confidence(confidence)
+        .trace_source_and_sink(input_location, &source.tid);
         let _ = self.cwe_collector.send(cwe_warning);
     }
 
@@ -284,16 +301,22 @@ impl<'a> Context<'a> {
     }
 
     /// Updates the target state at the callsite by removing non parameter register taints
-    /// and by merging callee saved register taints from the return state if available
+    /// and by merging callee saved register taints from the return state if available.
+    /// If the signature of the callee is known, its parameters are used to determine the parameter registers.
+    /// Otherwise all parameter registers of the standard calling convention are assumed to be parameters.
     pub fn update_target_state_for_callsite(
         &self,
         return_state: Option<&State>,
         target_state: Option<&State>,
         caller_sub: &Term<Sub>,
+        callee_signature: Option<&FunctionSignature>,
     ) -> Option<State> {
         if let Some(target) = target_state {
             let mut new_state = target.clone();
-            new_state.remove_non_parameter_taints_for_generic_function(self.project);
+            match callee_signature {
+                Some(signature) => new_state.remove_non_parameter_taints(&signature.parameters),
+                None => new_state.remove_non_parameter_taints_for_generic_function(self.project),
+            }
             new_state.set_current_sub(caller_sub);
             if let Some(return_) = return_state {
                 new_state.merge_callee_saved_taints_from_return_state(
@@ -391,15 +414,23 @@ impl<'a> crate::analysis::backward_interprocedural_fixpoint::Context<'a> for Con
         target_state: Option<&State>,
         return_state: Option<&State>,
         caller_sub: &Term<Sub>,
-        _call: &Term<Jmp>,
+        call: &Term<Jmp>,
         _return_: &Term<Jmp>,
     ) -> Option<State> {
+        let callee_signature = match &call.term {
+            Jmp::Call { target, .. } => self.symbol_maps.sub_signature_map.get(target).copied(),
+            _ => None,
+        };
         // Return state is present
         if let Some(return_) = return_state {
             // Update the target state if there is one. Otherwise clone the return state and
             // remove all non callee saved register taints
-            let new_state =
-                self.update_target_state_for_callsite(return_state, target_state, caller_sub);
+            let new_state = self.update_target_state_for_callsite(
+                return_state,
+                target_state,
+                caller_sub,
+                callee_signature,
+            );
             if new_state.is_none() {
                 let mut new_state = return_.clone();
                 if let Some(calling_conv) = self.project.get_standard_calling_convention() {
@@ -420,6 +451,7 @@ impl<'a> crate::analysis::backward_interprocedural_fixpoint::Context<'a> for Con
                         return_state,
                         target_state,
                         caller_sub,
+                        callee_signature,
                     );
                 }
             }
@@ -434,14 +466,25 @@ impl<'a> crate::analysis::backward_interprocedural_fixpoint::Context<'a> for Con
         Some(combined_state.clone())
     }
 
-    /// Removes all register taints except for possible return register taints
+    /// Removes all register taints except for possible return register taints.
+    /// If the signature of the function is known, only its return registers are kept.
     fn split_return_stub(
         &self,
         combined_state: &State,
         returned_from_sub: &Term<Sub>,
     ) -> Option<State> {
         let mut new_state = combined_state.clone();
-        if let Some(calling_conv) = self.project.get_standard_calling_convention() {
+        if let Some(signature) = &returned_from_sub.term.signature {
+            let return_registers: HashSet<String> = signature
+                .return_values
+                .iter()
+                .filter_map(|arg| match arg {
+                    Arg::Register { var, .. } => Some(var.name.clone()),
+                    Arg::Stack { .. } => None,
+                })
+                .collect();
+            new_state.remove_all_except_return_register_taints(return_registers);
+        } else if let Some(calling_conv) = self.project.get_standard_calling_convention() {
             let return_registers: HashSet<String> =
                 calling_conv.return_register.iter().cloned().collect();
             new_state.remove_all_except_return_register_taints(return_registers);
@@ -126,14 +126,18 @@ impl<'a> Context<'a> {
                 self.runtime_memory_image,
             ) {
                 if new_state.address_points_to_taint(address.clone(), pi_state) {
+                    let call_source = self.get_graph().node_weight(call_source_node).unwrap();
+                    let input_location = call_source
+                        .get_block()
+                        .term
+                        .jmps
+                        .first()
+                        .map(|jmp| &jmp.tid)
+                        .unwrap_or(&call_source.get_block().tid);
                     self.generate_cwe_warning(
-                        &self
-                            .get_graph()
-                            .node_weight(call_source_node)
-                            .unwrap()
-                            .get_sub()
-                            .term
-                            .name,
+                        &call_source.get_sub().term.name,
+                        input_location,
+                        pi_state.get_pointer_confidence(&address),
                     );
                     new_state.remove_all_register_taints();
                     new_state.remove_all_memory_taints();
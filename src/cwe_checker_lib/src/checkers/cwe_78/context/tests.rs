@@ -177,6 +177,7 @@ impl<'a> Context<'a> {
             user_input_symbol_map: user_input_symbols,
             extern_symbol_map,
             format_string_index,
+            sub_signature_map: HashMap::new(),
         };
 
         Context::new(
@@ -460,7 +461,7 @@ fn updating_target_state_for_callsite() {
 
     // Test Case 1: No target state
     assert_eq!(
-        context.update_target_state_for_callsite(None, None, &caller_sub),
+        context.update_target_state_for_callsite(None, None, &caller_sub, None),
         None
     );
 
@@ -474,7 +475,7 @@ fn updating_target_state_for_callsite() {
         .set_register_taint(&rdi_reg, Taint::Tainted(rdi_reg.size));
 
     let new_state = context
-        .update_target_state_for_callsite(None, Some(&setup.state), &caller_sub)
+        .update_target_state_for_callsite(None, Some(&setup.state), &caller_sub, None)
         .unwrap();
     assert_eq!(new_state.get_register_taint(&r9_reg), None);
     assert_eq!(
@@ -487,7 +488,12 @@ fn updating_target_state_for_callsite() {
     // Test Case 3: Target state and return state
     return_state.set_register_taint(&rbp_reg, Taint::Tainted(rbp_reg.size));
     let new_state = context
-        .update_target_state_for_callsite(Some(&return_state), Some(&setup.state), &caller_sub)
+        .update_target_state_for_callsite(
+            Some(&return_state),
+            Some(&setup.state),
+            &caller_sub,
+            None,
+        )
         .unwrap();
     assert_eq!(new_state.get_register_taint(&r9_reg), None);
     assert_eq!(
@@ -422,6 +422,16 @@ impl State {
         false
     }
 
+    /// Removes all taints of registers that are not contained in the given parameters of a function.
+    pub fn remove_non_parameter_taints(&mut self, parameters: &[Arg]) {
+        self.register_taint.retain(|register, _| {
+            parameters.iter().any(|parameter| match parameter {
+                Arg::Register { var, .. } => var.name == register.name,
+                Arg::Stack { .. } => false,
+            })
+        });
+    }
+
     /// Removes all taints of registers that are not generic function parameters.
     /// Since we don't know the actual calling convention of the call,
     /// we approximate the parameters with all parameter registers of the standard calling convention of the project.
@@ -21,7 +21,7 @@ use std::collections::HashMap;
 use crate::{
     intermediate_representation::{Program, Sub, Term, Tid},
     utils::{
-        log::{CweWarning, LogMessage},
+        log::{Confidence, CweWarning, LogMessage, Severity},
         symbol_utils::{find_symbol, get_calls_to_symbols},
     },
 };
@@ -33,6 +33,7 @@ pub static CWE_MODULE: crate::CweModule = crate::CweModule {
     name: "CWE782",
     version: VERSION,
     run: check_cwe,
+    check_config: crate::check_config::<serde::de::IgnoredAny>,
 };
 
 /// check whether the ioctl symbol is called by any subroutine. If so, generate the cwe warning.
@@ -60,7 +61,9 @@ pub fn generate_cwe_warning(calls: &[(&str, &Tid, &str)]) -> Vec<CweWarning> {
         )
         .addresses(vec![address.clone()])
         .tids(vec![format!("{}", jmp_tid)])
-        .symbols(vec![String::from(*sub_name)]);
+        .symbols(vec![String::from(*sub_name)])
+        .severity(Severity::Medium)
+        .confidence(Confidence::Low);
 
         cwe_warnings.push(cwe_warning);
     }
@@ -0,0 +1,235 @@
+//! A content-addressed on-disk cache for the output of the P-Code Extractor Ghidra plugin.
+//!
+//! Cache entries are keyed by the SHA-256 hash of the binary,
+//! the bare metal configuration (if one is provided)
+//! and the version of the P-Code Extractor plugin.
+//! Thus a cache entry can only be reused if all of these inputs match.
+
+use crate::pcode;
+use crate::prelude::*;
+use crate::utils::binary::BareMetalConfig;
+use crate::utils::get_ghidra_plugin_path;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+/// The cache folder containing one JSON file for each cached `pcode::Project`.
+pub struct PcodeCache {
+    folder: PathBuf,
+}
+
+impl PcodeCache {
+    /// Get the cache located in the data directory of the cwe_checker.
+    ///
+    /// Returns an error if the location of the data directory cannot be determined,
+    /// e.g. because the home directory of the user is unknown.
+    pub fn new() -> Result<PcodeCache, Error> {
+        let project_dirs = directories::ProjectDirs::from("", "", "cwe_checker")
+            .ok_or_else(|| anyhow!("Could not discern location of data directory."))?;
+        Ok(PcodeCache::with_folder(
+            project_dirs.data_dir().join("pcode_cache"),
+        ))
+    }
+
+    /// Get the cache located in the given folder.
+    ///
+    /// The folder is created when the first entry is stored.
+    pub fn with_folder(folder: PathBuf) -> PcodeCache {
+        PcodeCache { folder }
+    }
+
+    /// Compute the cache key for the given binary and bare metal configuration.
+    ///
+    /// For bare metal binaries the key also depends on the contents of the files backing memory regions.
+    /// The key also depends on the version of the P-Code Extractor plugin,
+    /// so that updating the plugin invalidates all old cache entries.
+    pub fn compute_key(binary: &[u8], bare_metal_config: Option<&BareMetalConfig>) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(binary);
+        if let Some(config) = bare_metal_config {
+            hasher.update(serde_json::to_vec(config).unwrap());
+            // The contents of additional files backing memory regions are also imported into Ghidra.
+            for region in config.memory_regions.iter() {
+                if let Some(content) = region
+                    .file
+                    .as_ref()
+                    .and_then(|path| std::fs::read(path).ok())
+                {
+                    hasher.update(content);
+                }
+            }
+        }
+        hasher.update(get_pcode_extractor_version());
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Load the cached `pcode::Project` for the given key.
+    ///
+    /// Returns `None` if no cache entry exists or if the cache entry could not be parsed.
+    pub fn load(&self, key: &str) -> Option<pcode::Project> {
+        let file = std::fs::File::open(self.get_entry_path(key)).ok()?;
+        serde_json::from_reader(std::io::BufReader::new(file)).ok()
+    }
+
+    /// Store the given `pcode::Project` under the given key.
+    ///
+    /// The entry is first written to a temporary file and then moved to its final location,
+    /// so that parallel instances of the cwe_checker never read partially written entries.
+    pub fn store(&self, key: &str, project: &pcode::Project) -> Result<(), Error> {
+        std::fs::create_dir_all(&self.folder)?;
+        let tmp_path = self
+            .folder
+            .join(format!("{}.{}.tmp", key, std::process::id()));
+        let file = std::fs::File::create(&tmp_path)?;
+        serde_json::to_writer(std::io::BufWriter::new(file), project)?;
+        std::fs::rename(&tmp_path, self.get_entry_path(key))?;
+        Ok(())
+    }
+
+    /// Remove all entries from the cache.
+    /// Returns the number of removed entries.
+    pub fn prune(&self) -> Result<usize, Error> {
+        if !self.folder.exists() {
+            return Ok(0);
+        }
+        let mut num_removed_entries = 0;
+        for entry in std::fs::read_dir(&self.folder)? {
+            let path = entry?.path();
+            if path.is_file() {
+                std::fs::remove_file(&path)?;
+                if path.extension() == Some(std::ffi::OsStr::new("json")) {
+                    num_removed_entries += 1;
+                }
+            }
+        }
+        Ok(num_removed_entries)
+    }
+
+    /// Get the file path of the cache entry for the given key.
+    fn get_entry_path(&self, key: &str) -> PathBuf {
+        self.folder.join(format!("{}.json", key))
+    }
+}
+
+/// Get the version of the installed P-Code Extractor plugin.
+///
+/// Since the plugin has no explicit version number,
+/// the version is given by the SHA-256 hash over the contents of all of its source files.
+fn get_pcode_extractor_version() -> String {
+    let plugin_path = get_ghidra_plugin_path("p_code_extractor");
+    let mut source_files = Vec::new();
+    collect_files(&plugin_path, &mut source_files);
+    source_files.sort();
+    let mut hasher = Sha256::new();
+    for file_path in source_files {
+        if let Ok(relative_path) = file_path.strip_prefix(&plugin_path) {
+            hasher.update(relative_path.to_string_lossy().as_bytes());
+        }
+        if let Ok(content) = std::fs::read(&file_path) {
+            hasher.update(content);
+        }
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Recursively collect the paths of all files contained in the given folder.
+fn collect_files(folder: &Path, files: &mut Vec<PathBuf>) {
+    if let Ok(entries) = std::fs::read_dir(folder) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                collect_files(&path, files);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_pcode_project() -> pcode::Project {
+        serde_json::from_value(serde_json::json!({
+            "program": {
+                "tid": { "id": "prog_08048000", "address": "08048000" },
+                "term": { "subs": [], "extern_symbols": [], "entry_points": [], "image_base": "10000" }
+            },
+            "stack_pointer_register": { "name": "RSP", "size": 8, "is_virtual": false },
+            "cpu_architecture": "x86_64",
+            "register_properties": [],
+            "register_calling_convention": [],
+            "datatype_properties": {
+                "char_size": 1,
+                "double_size": 8,
+                "float_size": 4,
+                "integer_size": 4,
+                "long_double_size": 8,
+                "long_long_size": 8,
+                "long_size": 8,
+                "pointer_size": 8,
+                "short_size": 2
+            }
+        }))
+        .unwrap()
+    }
+
+    fn mock_bare_metal_config(region_file: Option<&Path>) -> BareMetalConfig {
+        serde_json::from_value(serde_json::json!({
+            "processor_id": "ARM:LE:32:Cortex",
+            "memory_regions": [
+                { "name": "flash", "base_address": "0x08000000", "permissions": "rx", "file_offset": "0x0" },
+                { "name": "sram", "base_address": "0x20000000", "permissions": "rw", "file": region_file },
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn cache_key() {
+        let region_file =
+            std::env::temp_dir().join(format!("cwe_checker_cache_region_{}", std::process::id()));
+        std::fs::write(&region_file, [1, 2, 3]).unwrap();
+        let config = mock_bare_metal_config(Some(&region_file));
+
+        let key = PcodeCache::compute_key(&[0, 1], None);
+        assert_eq!(key, PcodeCache::compute_key(&[0, 1], None));
+        assert_ne!(key, PcodeCache::compute_key(&[0, 2], None));
+        let bare_metal_key = PcodeCache::compute_key(&[0, 1], Some(&config));
+        assert_ne!(key, bare_metal_key);
+        assert_ne!(
+            bare_metal_key,
+            PcodeCache::compute_key(&[0, 1], Some(&mock_bare_metal_config(None)))
+        );
+        // The key depends on the contents of files backing memory regions.
+        std::fs::write(&region_file, [1, 2, 4]).unwrap();
+        assert_ne!(
+            bare_metal_key,
+            PcodeCache::compute_key(&[0, 1], Some(&config))
+        );
+
+        std::fs::remove_file(&region_file).unwrap();
+    }
+
+    #[test]
+    fn store_load_and_prune() {
+        let folder =
+            std::env::temp_dir().join(format!("cwe_checker_cache_test_{}", std::process::id()));
+        let cache = PcodeCache::with_folder(folder.clone());
+        assert_eq!(cache.prune().unwrap(), 0);
+        assert!(cache.load("key").is_none());
+
+        let project = mock_pcode_project();
+        cache.store("key", &project).unwrap();
+        assert_eq!(cache.load("key"), Some(project));
+        assert!(cache.load("other_key").is_none());
+        // Unparseable entries are ignored.
+        std::fs::write(folder.join("broken.json"), "{").unwrap();
+        assert!(cache.load("broken").is_none());
+
+        assert_eq!(cache.prune().unwrap(), 2);
+        assert!(cache.load("key").is_none());
+
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -0,0 +1,456 @@
+use super::{parse_pcode_project_to_ir_project, Frontend, PcodeCache};
+use crate::intermediate_representation::Project;
+use crate::prelude::*;
+use crate::utils::binary::firmware::FirmwareFormat;
+use crate::utils::binary::vector_table::CortexMVectorTable;
+use crate::utils::binary::BareMetalConfig;
+use crate::utils::error::{ErrorKind, WithErrorKind};
+use crate::utils::log::LogMessage;
+use crate::utils::{get_ghidra_plugin_path, read_config_file};
+use nix::{sys::stat, unistd};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+/// The default timeout in seconds for the analysis of a binary by Ghidra.
+pub const DEFAULT_ANALYSIS_TIMEOUT: u64 = 3600;
+
+/// The frontend that uses the P-Code Extractor plugin of Ghidra to disassemble a binary.
+pub struct GhidraFrontend {
+    /// The path to the local Ghidra installation.
+    ///
+    /// If not set, the path is read from the `ghidra.json` configuration file.
+    pub ghidra_path: Option<PathBuf>,
+    /// The bare metal configuration, if the binary is a bare metal binary.
+    pub bare_metal_config: Option<BareMetalConfig>,
+    /// If set, outputs of Ghidra are loaded from and stored in the [`PcodeCache`].
+    pub use_cache: bool,
+    /// The timeout in seconds for the analysis of the binary by Ghidra.
+    ///
+    /// If the timeout fires, the P-Code of the binary is still extracted,
+    /// but the disassembly may be incomplete and the generated project is marked as partial.
+    pub analysis_timeout: u64,
+}
+
+impl GhidraFrontend {
+    /// Create a new Ghidra frontend using the Ghidra installation configured in `ghidra.json`,
+    /// using the cache for Ghidra outputs and using the default analysis timeout.
+    pub fn new(bare_metal_config: Option<BareMetalConfig>) -> GhidraFrontend {
+        GhidraFrontend {
+            ghidra_path: None,
+            bare_metal_config,
+            use_cache: true,
+            analysis_timeout: DEFAULT_ANALYSIS_TIMEOUT,
+        }
+    }
+
+    /// Load the `pcode::Project` for the given binary from the cache.
+    /// If no cache entry exists, execute Ghidra instead and store its output in the cache.
+    fn get_pcode_project_from_cache_or_ghidra(
+        &self,
+        file_path: &Path,
+        binary: &[u8],
+    ) -> Result<(crate::pcode::Project, Vec<LogMessage>), Error> {
+        let cache = match PcodeCache::new() {
+            Ok(cache) => cache,
+            Err(err) => {
+                let project_pcode = self.get_pcode_project_from_ghidra(file_path)?;
+                let log_msg = LogMessage::new_info(format!(
+                    "Could not use the cache for Ghidra outputs: {}",
+                    err
+                ));
+                return Ok((project_pcode, vec![log_msg]));
+            }
+        };
+        let cache_key = PcodeCache::compute_key(binary, self.bare_metal_config.as_ref());
+        if let Some(project_pcode) = cache.load(&cache_key) {
+            let log_msg =
+                LogMessage::new_debug(format!("Using cached Ghidra output {}", cache_key));
+            return Ok((project_pcode, vec![log_msg]));
+        }
+        let project_pcode = self.get_pcode_project_from_ghidra(file_path)?;
+        if project_pcode.analysis_timeout_occurred {
+            // Partial results are not cached, so that a later run with a larger timeout is not affected by them.
+            return Ok((project_pcode, Vec::new()));
+        }
+        let logs = match cache.store(&cache_key, &project_pcode) {
+            Ok(()) => Vec::new(),
+            Err(err) => vec![LogMessage::new_info(format!(
+                "Could not store Ghidra output in the cache: {}",
+                err
+            ))],
+        };
+        Ok((project_pcode, logs))
+    }
+
+    /// Execute the `p_code_extractor` plugin in ghidra and parse its output into the `pcode::Project` data structure.
+    pub fn get_pcode_project_from_ghidra(
+        &self,
+        file_path: &Path,
+    ) -> Result<crate::pcode::Project, Error> {
+        let ghidra_path: PathBuf = match &self.ghidra_path {
+            Some(path) => path.clone(),
+            None => serde_json::from_value(read_config_file("ghidra.json")?["ghidra_path"].clone())
+                .map_err(|_| anyhow!("Path to Ghidra not configured."))
+                .error_kind(ErrorKind::Configuration)?,
+        };
+        let headless_path = ghidra_path.join("support/analyzeHeadless");
+        if !headless_path.is_file() {
+            return Err(anyhow!(
+                "No Ghidra installation found at {}",
+                ghidra_path.display()
+            ))
+            .error_kind(ErrorKind::Configuration);
+        }
+
+        // Find the correct paths for temporary files.
+        let project_dirs = directories::ProjectDirs::from("", "", "cwe_checker")
+            .ok_or_else(|| anyhow!("Could not determine path for temporary files"))
+            .error_kind(ErrorKind::Io)?;
+        let tmp_folder = if let Some(folder) = project_dirs.runtime_dir() {
+            folder
+        } else {
+            Path::new("/tmp/cwe_checker")
+        };
+        if !tmp_folder.exists() {
+            std::fs::create_dir(tmp_folder)
+                .map_err(|err| anyhow!("Unable to create temporary folder: {}", err))
+                .error_kind(ErrorKind::Io)?;
+        }
+        // We add a timestamp suffix to file names
+        // so that if two instances of the cwe_checker are running in parallel on the same file
+        // they do not interfere with each other.
+        let timestamp_suffix = format!(
+            "{:?}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::SystemTime::UNIX_EPOCH)
+                .unwrap()
+                .as_millis()
+        );
+        let filename = file_path
+            .file_name()
+            .ok_or_else(|| anyhow!("Invalid file name"))?
+            .to_string_lossy()
+            .to_string();
+        let ghidra_plugin_path = get_ghidra_plugin_path("p_code_extractor");
+        if !ghidra_plugin_path.join("PcodeExtractor.java").is_file() {
+            return Err(anyhow!(
+                "The P-Code Extractor plugin is not installed at {}",
+                ghidra_plugin_path.display()
+            ))
+            .error_kind(ErrorKind::Configuration);
+        }
+
+        // For bare metal binaries, the memory map of the chip is written to a file
+        // that a script reads to create the memory blocks in Ghidra.
+        let memory_map_path = tmp_folder.join(format!("memory_map_{}.json", timestamp_suffix));
+        let bare_metal_args = match &self.bare_metal_config {
+            Some(bare_metal_config) => get_bare_metal_ghidra_args(
+                bare_metal_config,
+                file_path,
+                &memory_map_path,
+                &ghidra_plugin_path,
+            )
+            .error_kind(ErrorKind::Configuration)?,
+            None => Vec::new(),
+        };
+
+        // Create a unique name for the pipe
+        let fifo_path = tmp_folder.join(format!("pcode_{}.pipe", timestamp_suffix));
+
+        // Create a new fifo and give read and write rights to the owner
+        unistd::mkfifo(&fifo_path, stat::Mode::from_bits(0o600).unwrap())
+            .map_err(|err| anyhow!("Error creating FIFO pipe: {}", err))
+            .error_kind(ErrorKind::Io)?;
+
+        // Read the output of Ghidra from the FIFO in a new thread
+        // while Ghidra is executed in the current thread.
+        let thread_fifo_path = fifo_path.clone();
+        let reader_finished = Arc::new(AtomicBool::new(false));
+        let thread_reader_finished = reader_finished.clone();
+        let reader_thread = thread::spawn(move || -> Result<crate::pcode::Project, Error> {
+            let result = std::fs::File::open(thread_fifo_path)
+                .map_err(|err| anyhow!("Could not open FIFO: {}", err))
+                .and_then(|file| {
+                    serde_json::from_reader(std::io::BufReader::new(file))
+                        .map_err(|err| anyhow!("Parsing of the Ghidra output failed: {}", err))
+                });
+            thread_reader_finished.store(true, Ordering::SeqCst);
+            result
+        });
+
+        let mut ghidra_command = Command::new(&headless_path);
+        ghidra_command
+            .arg(tmp_folder) // The folder where temporary files should be stored
+            .arg(format!("PcodeExtractor_{}_{}", filename, timestamp_suffix)) // The name of the temporary Ghidra Project.
+            .arg("-import") // Import a file into the Ghidra project
+            .arg(file_path) // File import path
+            .arg("-postScript") // Execute a script after standard analysis by Ghidra finished
+            .arg(ghidra_plugin_path.join("PcodeExtractor.java")) // Path to the PcodeExtractor.java
+            .arg(&fifo_path) // The path to the named pipe (fifo)
+            .arg("-scriptPath") // Add a folder containing additional script files to the Ghidra script file search paths
+            .arg(ghidra_plugin_path) // Path to the folder containing the PcodeExtractor.java (so that the other java files can be found.)
+            .arg("-deleteProject") // Delete the temporary project after the script finished
+            .arg("-analysisTimeoutPerFile") // Set a timeout for how long the standard analysis can run before getting aborted
+            .arg(self.analysis_timeout.to_string()); // The post-script detects whether the timeout fired.
+        ghidra_command.args(bare_metal_args);
+        let ghidra_result = execute_ghidra(ghidra_command);
+        if ghidra_result.is_err() {
+            unblock_fifo_reader(&fifo_path, &reader_finished);
+        }
+        let pcode_result = reader_thread
+            .join()
+            .map_err(|_| anyhow!("The thread reading the Ghidra output has panicked!"))
+            .error_kind(ErrorKind::Frontend)?;
+        std::fs::remove_file(fifo_path).error_kind(ErrorKind::Io)?;
+        if self.bare_metal_config.is_some() {
+            let _ = std::fs::remove_file(memory_map_path);
+        }
+
+        ghidra_result.error_kind(ErrorKind::Frontend)?;
+        pcode_result.error_kind(ErrorKind::Frontend)
+    }
+}
+
+impl Frontend for GhidraFrontend {
+    fn get_project(
+        &self,
+        binary_path: &Path,
+        binary: &[u8],
+    ) -> Result<(Project, Vec<LogMessage>), Error> {
+        let (project_pcode, mut cache_logs) = if self.use_cache {
+            self.get_pcode_project_from_cache_or_ghidra(binary_path, binary)?
+        } else {
+            (self.get_pcode_project_from_ghidra(binary_path)?, Vec::new())
+        };
+        let (project, mut logs) = parse_pcode_project_to_ir_project(
+            project_pcode,
+            binary,
+            self.bare_metal_config.as_ref(),
+        );
+        logs.append(&mut cache_logs);
+        Ok((project, logs))
+    }
+}
+
+/// The memory map and the known functions of a bare metal binary
+/// that are passed to the `BareMetalMemoryMap.java` script.
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+struct GhidraMemoryMap {
+    /// The memory blocks to create.
+    memory_blocks: Vec<GhidraMemoryBlock>,
+    /// The functions to create, e.g. the exception and interrupt handlers from the vector table.
+    functions: Vec<GhidraFunction>,
+}
+
+/// A function that the `BareMetalMemoryMap.java` script creates in the Ghidra project.
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+struct GhidraFunction {
+    /// The name of the function.
+    name: String,
+    /// The entry address as a hexadecimal number without `0x` prefix.
+    address: String,
+    /// Whether the function contains Thumb code.
+    thumb: bool,
+}
+
+/// A memory block that the `BareMetalMemoryMap.java` script creates in the Ghidra project
+/// before the analysis of a bare metal binary starts.
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+struct GhidraMemoryBlock {
+    /// The name of the memory block.
+    name: String,
+    /// The base address as a hexadecimal number without `0x` prefix.
+    base_address: String,
+    /// The size of the memory block in bytes.
+    size: u64,
+    /// The absolute path to the file containing the initial content of the block, if the block is initialized.
+    file: Option<PathBuf>,
+    /// The offset of the initial content of the block in the file.
+    file_offset: u64,
+    /// Whether the block is readable.
+    read: bool,
+    /// Whether the block is writeable.
+    write: bool,
+    /// Whether the block is executable.
+    execute: bool,
+    /// Whether the block contains memory-mapped peripherals.
+    volatile: bool,
+}
+
+/// Get the command line arguments for Ghidra to import a bare metal binary.
+///
+/// For raw binaries the first memory region backed by the input binary is imported via the `BinaryLoader` of Ghidra.
+/// Firmware images in Intel HEX, S-record or ELF format are imported with the corresponding Ghidra loader instead,
+/// which maps the content to the addresses contained in the image.
+/// In this case memory regions backed by the input binary are ignored.
+/// All (other) memory regions are written to a JSON file at `memory_map_path`,
+/// so that the `BareMetalMemoryMap.java` script can create the other memory regions
+/// and set the access permissions of all regions before the analysis starts.
+/// If the binary contains a Cortex-M vector table,
+/// the script also creates functions for the exception and interrupt handlers contained in it.
+fn get_bare_metal_ghidra_args(
+    bare_metal_config: &BareMetalConfig,
+    file_path: &Path,
+    memory_map_path: &Path,
+    ghidra_plugin_path: &Path,
+) -> Result<Vec<OsString>, Error> {
+    let binary_path = std::fs::canonicalize(file_path)?;
+    let binary = std::fs::read(file_path)?;
+    let format = FirmwareFormat::detect(&binary);
+    let mut memory_blocks = Vec::new();
+    for region in bare_metal_config.get_memory_regions() {
+        if format != FirmwareFormat::Raw && region.is_backed_by_input_binary() {
+            continue;
+        }
+        let file = match &region.file {
+            Some(path) => Some(
+                std::fs::canonicalize(path)
+                    .map_err(|err| anyhow!("Could not find file {}: {}", path, err))?,
+            ),
+            None if region.is_backed_by_input_binary() => Some(binary_path.clone()),
+            None => None,
+        };
+        let file_length = match &file {
+            Some(path) => Some(std::fs::metadata(path)?.len()),
+            None => None,
+        };
+        memory_blocks.push(GhidraMemoryBlock {
+            name: region.name.clone(),
+            base_address: format!("{:x}", region.parse_base_address()?),
+            size: region.parse_size(file_length)?,
+            file,
+            file_offset: region.parse_file_offset()?,
+            read: region.has_permission('r'),
+            write: region.has_permission('w'),
+            execute: region.has_permission('x'),
+            volatile: region.volatile,
+        });
+    }
+    let functions = match CortexMVectorTable::from_bare_metal_binary(&binary, bare_metal_config) {
+        Ok(vector_table) => vector_table
+            .handlers
+            .into_iter()
+            .map(|(name, address)| GhidraFunction {
+                name,
+                address: format!("{:x}", address),
+                thumb: true,
+            })
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    let memory_map = GhidraMemoryMap {
+        memory_blocks,
+        functions,
+    };
+    std::fs::write(memory_map_path, serde_json::to_vec(&memory_map)?)
+        .map_err(|err| anyhow!("Could not write memory map file: {}", err))?;
+
+    let mut args: Vec<OsString> = match format {
+        FirmwareFormat::Raw => {
+            let primary_region = bare_metal_config.get_primary_memory_region()?;
+            let binary_length = std::fs::metadata(file_path)?.len();
+            let file_offset = primary_region.parse_file_offset()?;
+            let length = std::cmp::min(
+                primary_region.parse_size(Some(binary_length))?,
+                binary_length.saturating_sub(file_offset),
+            );
+            vec![
+                "-loader".into(),          // Tell Ghidra to use a specific loader
+                "BinaryLoader".into(),     // Use the BinaryLoader for bare metal binaries
+                "-loader-baseAddr".into(), // Provide the base address where the binary should be mapped in memory
+                format!("{:x}", primary_region.parse_base_address()?).into(),
+                "-loader-fileOffset".into(), // The part of the binary to map at the base address
+                file_offset.to_string().into(),
+                "-loader-length".into(),
+                length.to_string().into(),
+                "-loader-blockName".into(),
+                primary_region.name.into(),
+            ]
+        }
+        FirmwareFormat::IntelHex => vec!["-loader".into(), "IntelHexLoader".into()],
+        FirmwareFormat::SRecord => vec!["-loader".into(), "MotorolaHexLoader".into()],
+        FirmwareFormat::Elf => vec!["-loader".into(), "ElfLoader".into()],
+    };
+    args.extend(vec![
+        "-processor".into(), // Provide the processor type ID, for which the binary was compiled.
+        bare_metal_config.processor_id.clone().into(),
+        "-preScript".into(), // Execute a script after import, but before the analysis by Ghidra starts
+        ghidra_plugin_path.join("BareMetalMemoryMap.java").into(), // Creates the other memory regions
+        memory_map_path.into(),
+    ]);
+    Ok(args)
+}
+
+/// Execute the given Ghidra command and check whether the P-Code Extractor plugin ran successfully.
+fn execute_ghidra(mut ghidra_command: Command) -> Result<(), Error> {
+    let output = ghidra_command
+        .output() // Execute the command and catch its output.
+        .map_err(|err| anyhow!("Ghidra could not be executed:\n{}", err))?;
+
+    match String::from_utf8(output.stdout.clone()) {
+        Ok(standard_out) => {
+            if !standard_out.contains("Pcode was successfully extracted!") {
+                let error_message: String = standard_out
+                    .lines()
+                    .rev()
+                    .take(2)
+                    .collect::<Vec<&str>>()
+                    .join("\n");
+                return Err(anyhow!(
+                    "Execution of Ghidra plugin failed: Process was terminated.\n{}",
+                    error_message
+                ));
+            }
+        }
+        Err(_) => {
+            return Err(anyhow!(
+                "Execution of Ghidra plugin failed: Process was terminated."
+            ))
+        }
+    }
+
+    if !output.status.success() {
+        match output.status.code() {
+            Some(code) => {
+                return Err(anyhow!(
+                    "{}\n{}\nExecution of Ghidra plugin failed with exit code {}",
+                    String::from_utf8_lossy(&output.stdout),
+                    String::from_utf8_lossy(&output.stderr),
+                    code
+                ))
+            }
+            None => {
+                return Err(anyhow!(
+                    "Execution of Ghidra plugin failed: Process was terminated."
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+/// If Ghidra failed without ever opening the FIFO for writing,
+/// the reader thread is still blocked on opening the FIFO.
+/// Unblock it by briefly opening the FIFO for writing,
+/// so that the reader thread sees an empty input and terminates.
+fn unblock_fifo_reader(fifo_path: &Path, reader_finished: &AtomicBool) {
+    use nix::fcntl::{open, OFlag};
+    while !reader_finished.load(Ordering::SeqCst) {
+        // Opening a FIFO for writing in non-blocking mode fails as long as no reader has opened it.
+        match open(
+            fifo_path,
+            OFlag::O_WRONLY | OFlag::O_NONBLOCK,
+            stat::Mode::empty(),
+        ) {
+            Ok(file_descriptor) => {
+                let _ = unistd::close(file_descriptor);
+                return;
+            }
+            Err(_) => thread::sleep(std::time::Duration::from_millis(10)),
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Export and import of normalized `Project` structs to and from JSON files.
+//!
+//! The files contain a header with the version number of the file format,
+//! so that files written by incompatible versions of the cwe_checker are rejected on import.
+
+use crate::intermediate_representation::Project;
+use crate::prelude::*;
+use crate::utils::error::{ErrorKind, WithErrorKind};
+use std::path::Path;
+
+/// The version number of the file format for exported `Project` structs.
+///
+/// Should be incremented whenever the serialized form of the intermediate representation changes.
+pub const IR_FORMAT_VERSION: u64 = 6;
+
+/// The content of a file containing an exported `Project`.
+///
+/// The type parameter allows to serialize a reference to a project without cloning it.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+struct IrFile<P> {
+    /// The version number of the file format.
+    format_version: u64,
+    /// The version of the cwe_checker that exported the project.
+    cwe_checker_version: String,
+    /// The exported (normalized) project.
+    project: P,
+}
+
+/// The header of a file containing an exported `Project`.
+/// Used to generate helpful error messages if the file cannot be imported.
+#[derive(Deserialize)]
+struct IrFileHeader {
+    format_version: u64,
+    cwe_checker_version: String,
+}
+
+/// Write the given project to a JSON file at the given path.
+///
+/// The project should already be normalized via [`Project::normalize`],
+/// since imported projects are not normalized again.
+pub fn export_ir(project: &Project, file_path: &Path) -> Result<(), Error> {
+    write_ir_file(project, file_path).error_kind(ErrorKind::Io)
+}
+
+/// Write the given project to a JSON file at the given path.
+fn write_ir_file(project: &Project, file_path: &Path) -> Result<(), Error> {
+    let ir_file = IrFile {
+        format_version: IR_FORMAT_VERSION,
+        cwe_checker_version: env!("CARGO_PKG_VERSION").to_string(),
+        project,
+    };
+    let file = std::fs::File::create(file_path)
+        .map_err(|err| anyhow!("Could not create file {}: {}", file_path.display(), err))?;
+    serde_json::to_writer(std::io::BufWriter::new(file), &ir_file)?;
+    Ok(())
+}
+
+/// Read a project exported via [`export_ir`] from the JSON file at the given path.
+///
+/// Returns an error if the file format version of the file does not match [`IR_FORMAT_VERSION`].
+pub fn import_ir(file_path: &Path) -> Result<Project, Error> {
+    read_ir_file(file_path).error_kind(ErrorKind::Frontend)
+}
+
+/// Read a project from the JSON file at the given path.
+fn read_ir_file(file_path: &Path) -> Result<Project, Error> {
+    let content = std::fs::read(file_path)
+        .map_err(|err| anyhow!("Could not read file {}: {}", file_path.display(), err))?;
+    match serde_json::from_slice::<IrFile<Project>>(&content) {
+        Ok(ir_file) if ir_file.format_version == IR_FORMAT_VERSION => Ok(ir_file.project),
+        Ok(ir_file) => Err(unsupported_format_version_error(
+            ir_file.format_version,
+            &ir_file.cwe_checker_version,
+        )),
+        Err(err) => match serde_json::from_slice::<IrFileHeader>(&content) {
+            Ok(header) if header.format_version != IR_FORMAT_VERSION => {
+                Err(unsupported_format_version_error(
+                    header.format_version,
+                    &header.cwe_checker_version,
+                ))
+            }
+            _ => Err(anyhow!("Parsing of the IR file failed: {}", err)),
+        },
+    }
+}
+
+/// Generate the error message for an IR file with a wrong format version.
+fn unsupported_format_version_error(format_version: u64, cwe_checker_version: &str) -> Error {
+    anyhow!(
+        "The IR file has format version {} (written by cwe_checker {}), but version {} is required.",
+        format_version,
+        cwe_checker_version,
+        IR_FORMAT_VERSION
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_and_import() {
+        let project = Project::mock_empty();
+        let file_path = std::env::temp_dir().join(format!(
+            "cwe_checker_ir_export_test_{}.json",
+            std::process::id()
+        ));
+        export_ir(&project, &file_path).unwrap();
+        assert_eq!(import_ir(&file_path).unwrap(), project);
+
+        let mut ir_file: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&file_path).unwrap()).unwrap();
+        ir_file["format_version"] = serde_json::json!(IR_FORMAT_VERSION + 1);
+        std::fs::write(&file_path, ir_file.to_string()).unwrap();
+        assert!(import_ir(&file_path).is_err());
+        std::fs::remove_file(&file_path).unwrap();
+    }
+}
@@ -0,0 +1,134 @@
+//! Frontends generate the intermediate representation of a binary,
+//! i.e. the [`Project`] struct that is the input for all analyses of the cwe_checker.
+//!
+//! All frontends implement the [`Frontend`] trait.
+//! The standard frontend is the [`GhidraFrontend`],
+//! which uses the P-Code Extractor plugin of Ghidra to disassemble the binary.
+//! Other frontends can be used to plug in other producers of the intermediate representation,
+//! e.g. the [`PcodeJsonFrontend`] reads the previously saved output of the P-Code Extractor plugin from a file.
+//!
+//! Already normalized projects can be exported to and imported from files via the functions in the [`ir_file`] module.
+
+use crate::intermediate_representation::Project;
+use crate::prelude::*;
+use crate::utils::binary::vector_table::CortexMVectorTable;
+use crate::utils::binary::BareMetalConfig;
+use crate::utils::log::LogMessage;
+use std::path::Path;
+
+mod cache;
+pub use cache::PcodeCache;
+mod ghidra;
+pub use ghidra::{GhidraFrontend, DEFAULT_ANALYSIS_TIMEOUT};
+pub mod ir_file;
+mod pcode_json;
+pub use pcode_json::PcodeJsonFrontend;
+
+/// A frontend generates the (not yet normalized) `Project` for a binary.
+pub trait Frontend {
+    /// Generate the `Project` for the binary at the given file path.
+    ///
+    /// The `binary` is the content of the file at `binary_path`.
+    /// Log messages generated while creating the project are returned alongside the project.
+    /// Note that [`Project::normalize`] still has to be called on the returned project before it can be analyzed.
+    fn get_project(
+        &self,
+        binary_path: &Path,
+        binary: &[u8],
+    ) -> Result<(Project, Vec<LogMessage>), Error>;
+}
+
+/// Normalize the given `pcode::Project` and convert it into the `Project` data structure.
+///
+/// The base address of the binary is needed to detect whether Ghidra shifted the addresses of the memory image.
+/// For bare metal binaries Ghidra never shifts the memory image, so no base address is needed.
+pub fn parse_pcode_project_to_ir_project(
+    mut project_pcode: crate::pcode::Project,
+    binary: &[u8],
+    bare_metal_config_opt: Option<&BareMetalConfig>,
+) -> (Project, Vec<LogMessage>) {
+    let mut log_messages = project_pcode.normalize();
+    if project_pcode.analysis_timeout_occurred {
+        log_messages.push(LogMessage::new_error(
+            "The Ghidra analysis timed out. The disassembly of the binary may be incomplete, so the analysis results are partial.",
+        ));
+    }
+    let project: Project = if let Some(bare_metal_config) = bare_metal_config_opt {
+        // Ghidra maps bare metal binaries to the addresses given in the bare metal configuration
+        // or in the address records of the firmware image.
+        // These are also the addresses used for the runtime memory image.
+        let mut project = project_pcode.into_ir_project(0);
+        project.program.term.address_base_offset = 0;
+        log_messages.append(&mut add_cortex_m_vector_table(
+            &mut project,
+            binary,
+            bare_metal_config,
+        ));
+        project
+    } else {
+        match crate::utils::get_binary_base_address(binary) {
+            Ok(binary_base_address) => project_pcode.into_ir_project(binary_base_address),
+            Err(_err) => {
+                log_messages.push(LogMessage::new_info("Could not determine binary base address. Using base address of Ghidra output as fallback."));
+                let mut project = project_pcode.into_ir_project(0);
+                // Without a known base address we have to assume that Ghidra did not shift the memory image.
+                project.program.term.address_base_offset = 0;
+                project
+            }
+        }
+    };
+    (project, log_messages)
+}
+
+/// Add the information contained in the vector table of Cortex-M bare metal binaries to the project.
+///
+/// All exception and interrupt handlers are added to the entry points of the program
+/// and the corresponding functions are renamed to the handler names.
+/// The initial stack pointer value and the handler functions are stored in the project.
+/// If the binary does not contain a Cortex-M vector table, the project is not changed.
+fn add_cortex_m_vector_table(
+    project: &mut Project,
+    binary: &[u8],
+    bare_metal_config: &BareMetalConfig,
+) -> Vec<LogMessage> {
+    let vector_table = match CortexMVectorTable::from_bare_metal_binary(binary, bare_metal_config) {
+        Ok(vector_table) => vector_table,
+        Err(err) => {
+            return vec![LogMessage::new_info(format!(
+                "No Cortex-M vector table found: {}",
+                err
+            ))]
+        }
+    };
+    project.initial_stack_pointer = Some(vector_table.initial_stack_pointer);
+    let program = &mut project.program.term;
+    for sub in program.subs.iter_mut() {
+        let handler_name = match u64::from_str_radix(&sub.tid.address, 16) {
+            Ok(address) => vector_table.get_handler_name(address),
+            Err(_) => None,
+        };
+        if let Some(handler_name) = handler_name {
+            sub.term.name = handler_name.to_string();
+            if !program.entry_points.contains(&sub.tid) {
+                program.entry_points.push(sub.tid.clone());
+            }
+            project.vector_table_handlers.push(sub.tid.clone());
+        }
+    }
+    vector_table
+        .handlers
+        .iter()
+        .filter(|(_, address)| {
+            !program
+                .subs
+                .iter()
+                .any(|sub| u64::from_str_radix(&sub.tid.address, 16) == Ok(*address))
+        })
+        .map(|(name, address)| {
+            LogMessage::new_info(format!(
+                "No function found for {} at address {:#x}",
+                name, address
+            ))
+        })
+        .collect()
+}
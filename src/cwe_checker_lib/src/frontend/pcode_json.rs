@@ -0,0 +1,67 @@
+use super::{parse_pcode_project_to_ir_project, Frontend};
+use crate::intermediate_representation::Project;
+use crate::prelude::*;
+use crate::utils::binary::BareMetalConfig;
+use crate::utils::error::{ErrorKind, WithErrorKind};
+use crate::utils::log::LogMessage;
+use std::path::{Path, PathBuf};
+
+/// A frontend that reads the saved output of the P-Code Extractor Ghidra plugin from a JSON file.
+///
+/// Since Ghidra is not executed, this frontend also works without a local Ghidra installation.
+/// The JSON file must have been generated from the same binary that is given to [`Frontend::get_project`].
+pub struct PcodeJsonFrontend {
+    /// The path to the JSON file containing the output of the P-Code Extractor plugin.
+    pub pcode_json_path: PathBuf,
+    /// The bare metal configuration, if the binary is a bare metal binary.
+    pub bare_metal_config: Option<BareMetalConfig>,
+}
+
+impl PcodeJsonFrontend {
+    /// Read the `pcode::Project` from the JSON file.
+    pub fn read_pcode_project(&self) -> Result<crate::pcode::Project, Error> {
+        let file = std::fs::File::open(&self.pcode_json_path)
+            .map_err(|err| {
+                anyhow!(
+                    "Could not open P-Code file {}: {}",
+                    self.pcode_json_path.display(),
+                    err
+                )
+            })
+            .error_kind(ErrorKind::Frontend)?;
+        serde_json::from_reader(std::io::BufReader::new(file))
+            .map_err(|err| anyhow!("Parsing of the P-Code file failed: {}", err))
+            .error_kind(ErrorKind::Frontend)
+    }
+}
+
+impl Frontend for PcodeJsonFrontend {
+    fn get_project(
+        &self,
+        _binary_path: &Path,
+        binary: &[u8],
+    ) -> Result<(Project, Vec<LogMessage>), Error> {
+        let project_pcode = self.read_pcode_project()?;
+        Ok(parse_pcode_project_to_ir_project(
+            project_pcode,
+            binary,
+            self.bare_metal_config.as_ref(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nonexisting_pcode_file() {
+        let frontend = PcodeJsonFrontend {
+            pcode_json_path: PathBuf::from("/nonexisting/path/pcode.json"),
+            bare_metal_config: None,
+        };
+        assert!(frontend
+            .get_project(Path::new("/nonexisting/path/binary"), &[])
+            .is_err());
+    }
+}
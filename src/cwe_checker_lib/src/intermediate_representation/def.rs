@@ -86,6 +86,16 @@ impl Term<Def> {
     }
 }
 
+impl std::fmt::Display for Def {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Def::Load { var, address } => write!(formatter, "{} := Load from {}", var, address),
+            Def::Store { address, value } => write!(formatter, "Store at {} := {}", address, value),
+            Def::Assign { var, value } => write!(formatter, "{} = {}", var, value),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -425,5 +425,45 @@ impl Expression {
     }
 }
 
+impl std::fmt::Display for Expression {
+    /// Print the expression in a P-Code-like syntax,
+    /// where constants and casts are annotated with their size in bits.
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Expression::Var(var) => write!(formatter, "{}", var),
+            Expression::Const(bitvector) => write!(
+                formatter,
+                "0x{:x}:{}",
+                bitvector,
+                bitvector.width().to_usize()
+            ),
+            Expression::BinOp { op, lhs, rhs } => write!(formatter, "({} {:?} {})", lhs, op, rhs),
+            Expression::UnOp { op, arg } => write!(formatter, "{:?}({})", op, arg),
+            Expression::Cast { op, size, arg } => {
+                write!(formatter, "{:?}({}):{}", op, arg, size.as_bit_length())
+            }
+            Expression::Unknown { description, size } => {
+                write!(
+                    formatter,
+                    "Unknown({}):{}",
+                    description,
+                    size.as_bit_length()
+                )
+            }
+            Expression::Subpiece {
+                low_byte,
+                size,
+                arg,
+            } => write!(
+                formatter,
+                "Subpiece({}, {}, {})",
+                arg,
+                u64::from(*low_byte),
+                u64::from(*size)
+            ),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests;
@@ -58,3 +58,36 @@ pub enum Jmp {
         return_: Option<Tid>,
     },
 }
+
+impl std::fmt::Display for Jmp {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let print_return =
+            |formatter: &mut std::fmt::Formatter, return_: &Option<Tid>| match return_ {
+                Some(return_) => write!(formatter, " (return to {})", return_),
+                None => write!(formatter, " (no return)"),
+            };
+        match self {
+            Jmp::Branch(target) => write!(formatter, "Jump to {}", target),
+            Jmp::BranchInd(target) => write!(formatter, "Jump to {}", target),
+            Jmp::CBranch { target, condition } => {
+                write!(formatter, "If {} jump to {}", condition, target)
+            }
+            Jmp::Call { target, return_ } => {
+                write!(formatter, "Call {}", target)?;
+                print_return(formatter, return_)
+            }
+            Jmp::CallInd { target, return_ } => {
+                write!(formatter, "Call {}", target)?;
+                print_return(formatter, return_)
+            }
+            Jmp::Return(expression) => write!(formatter, "Return to {}", expression),
+            Jmp::CallOther {
+                description,
+                return_,
+            } => {
+                write!(formatter, "Call {}", description)?;
+                print_return(formatter, return_)
+            }
+        }
+    }
+}
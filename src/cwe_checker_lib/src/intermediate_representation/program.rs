@@ -19,6 +19,12 @@ pub struct Program {
     /// Thus addresses as specified by the binary and addresses as reported by Ghidra may differ by a constant offset,
     /// which is stored in this value.
     pub address_base_offset: u64,
+    /// The names and start addresses of the memory blocks that the frontend created when loading the binary.
+    ///
+    /// Relocatable ELF files do not specify a memory layout,
+    /// so the addresses of their sections are chosen by the frontend and can only be taken from this list.
+    #[serde(default)]
+    pub section_addresses: Vec<(String, u64)>,
 }
 
 impl Program {
@@ -45,6 +51,7 @@ mod tests {
                 extern_symbols: Vec::new(),
                 entry_points: Vec::new(),
                 address_base_offset: 0,
+                section_addresses: Vec::new(),
             }
         }
     }
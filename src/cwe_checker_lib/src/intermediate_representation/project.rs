@@ -25,6 +25,21 @@ pub struct Project {
     pub register_list: Vec<Variable>,
     /// Contains the properties of C data types. (e.g. size)
     pub datatype_properties: DatatypeProperties,
+    /// The value of the stack pointer register at the start of the program, if known.
+    ///
+    /// For Cortex-M bare metal binaries it is read from the vector table.
+    #[serde(default)]
+    pub initial_stack_pointer: Option<u64>,
+    /// The reset handler and the exception and interrupt handlers read from the vector table of bare metal binaries.
+    ///
+    /// These handlers start executing on the stack given by the `initial_stack_pointer`.
+    #[serde(default)]
+    pub vector_table_handlers: Vec<Tid>,
+    /// Set if the disassembly of the binary is incomplete,
+    /// e.g. because the Ghidra analysis was aborted after the analysis timeout.
+    /// Analysis results for such a project only cover the disassembled parts of the binary.
+    #[serde(default)]
+    pub is_partial: bool,
 }
 
 impl Project {
@@ -120,6 +135,7 @@ impl Project {
                 tid: dummy_sub_tid,
                 term: Sub {
                     name: "Artificial Sink Sub".to_string(),
+                    signature: None,
                     blocks: vec![Term {
                         tid: dummy_blk_tid,
                         term: Blk {
@@ -235,6 +251,9 @@ mod tests {
                 calling_conventions: Vec::new(),
                 register_list,
                 datatype_properties: DatatypeProperties::mock(),
+                initial_stack_pointer: None,
+                vector_table_handlers: Vec::new(),
+                is_partial: false,
             }
         }
     }
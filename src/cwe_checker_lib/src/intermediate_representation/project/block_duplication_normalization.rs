@@ -230,6 +230,7 @@ mod tests {
             tid: Tid::new(sub_name),
             term: Sub {
                 name: sub_name.to_string(),
+                signature: None,
                 blocks,
             },
         }
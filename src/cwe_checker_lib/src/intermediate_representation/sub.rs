@@ -13,6 +13,26 @@ pub struct Sub {
     /// The basic blocks belonging to the subroutine.
     /// The first block is also the entry point of the subroutine.
     pub blocks: Vec<Term<Blk>>,
+    /// The parameters and return values of the subroutine,
+    /// if they were computed by the [function signature analysis](crate::analysis::function_signature).
+    #[serde(default)]
+    pub signature: Option<FunctionSignature>,
+}
+
+impl Sub {
+    /// Return the demangled name of the subroutine or the raw name if it is not mangled.
+    pub fn get_display_name(&self) -> String {
+        crate::utils::demangle::demangle(&self.name).unwrap_or_else(|| self.name.clone())
+    }
+}
+
+/// The parameters and return values of a function.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Default)]
+pub struct FunctionSignature {
+    /// The parameters of the function.
+    pub parameters: Vec<Arg>,
+    /// The return values of the function.
+    pub return_values: Vec<Arg>,
 }
 
 /// A parameter or return argument of a function.
@@ -133,6 +153,7 @@ mod tests {
                 tid: Tid::new(name.to_string()),
                 term: Sub {
                     name: name.to_string(),
+                    signature: None,
                     blocks: Vec::new(),
                 },
             }
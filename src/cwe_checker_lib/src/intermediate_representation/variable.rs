@@ -19,6 +19,13 @@ pub struct Variable {
     pub is_temp: bool,
 }
 
+impl std::fmt::Display for Variable {
+    /// Print the variable as `name:size`, where the size is given in bits.
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "{}:{}", self.name, self.size.as_bit_length())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
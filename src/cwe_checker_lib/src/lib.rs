@@ -36,6 +36,12 @@ The cwe_checker offers experimental support for analyzing bare-metal binaries.
 For that one needs to provide a bare metal configuration file via the `--bare-metal-config` command line option.
 An example for such a configuration file can be found at `bare_metal/stm32f407vg.json`
 (which was created and tested for an STM32F407VG MCU).
+For chips with more complex memory maps (several flash banks or SRAMs, memory-mapped peripherals)
+the configuration file can instead contain a list of named memory regions,
+see `bare_metal/stm32f407vg_memory_map.json` for an example.
+Besides raw memory dumps the input binary may also be a firmware image in Intel HEX, Motorola S-record or ELF format.
+For ARM Cortex-M chips the exception and interrupt handlers listed in the vector table at the start of the binary
+are analyzed as entry points of the program.
 
 For more information on the necessary fields of the configuration file
 and the assumed memory model when analyzing bare metal binaries
@@ -43,6 +49,13 @@ see the [configuration struct documentation](crate::utils::binary::BareMetalConf
 
 # Integration into other tools
 
+### Usage as a Rust library
+
+The [`analyze_binary`] function runs the cwe_checker on a binary file
+and returns a [`Report`] containing all CWE warnings, log messages and statistics of the analysis.
+Use [`analyze`] instead if the intermediate representation of the binary was already generated by other means,
+e.g. by a custom [frontend](crate::frontend::Frontend).
+
 ### Integration into Ghidra
 
 To import the results of the cwe_checker as bookmarks and end-of-line comments into Ghidra,
@@ -65,14 +78,19 @@ use crate::analysis::pointer_inference::PointerInference;
 use crate::intermediate_representation::Project;
 use crate::utils::binary::RuntimeMemoryImage;
 use crate::utils::log::{CweWarning, LogMessage};
+use anyhow::Error;
 
 pub mod abstract_domain;
 pub mod analysis;
 pub mod checkers;
+pub mod frontend;
 pub mod intermediate_representation;
 pub mod pcode;
+pub mod pipeline;
 pub mod utils;
 
+pub use pipeline::{analyze, analyze_binary, AnalysisOptions, BinaryFile, Report};
+
 mod prelude {
     pub use apint::Width;
     pub use serde::{Deserialize, Serialize};
@@ -87,6 +105,9 @@ mod prelude {
 pub type CweModuleFn =
     fn(&AnalysisResults, &serde_json::Value) -> (Vec<LogMessage>, Vec<CweWarning>);
 
+/// The generic function signature for the function checking the configuration of a CWE module
+pub type CweConfigCheckFn = fn(&serde_json::Value) -> Result<(), Error>;
+
 /// A structure containing general information about a CWE analysis module,
 /// including the function to be called to run the analysis.
 pub struct CweModule {
@@ -97,6 +118,19 @@ pub struct CweModule {
     pub version: &'static str,
     /// The function that executes the check and returns CWE warnings found during the check.
     pub run: CweModuleFn,
+    /// The function that checks whether the configuration section of the CWE check can be parsed.
+    /// The `run` function may panic if it is called with a configuration that does not pass this check.
+    pub check_config: CweConfigCheckFn,
+}
+
+/// Check that the given configuration section can be deserialized into the configuration struct `T` of a CWE module.
+///
+/// Modules without configuration parameters can use `serde::de::IgnoredAny` as `T`.
+pub fn check_config<T: serde::de::DeserializeOwned>(
+    config: &serde_json::Value,
+) -> Result<(), Error> {
+    T::deserialize(config)?;
+    Ok(())
 }
 
 impl std::fmt::Display for CweModule {
@@ -161,19 +195,21 @@ impl<'a> AnalysisResults<'a> {
 
     /// Compute the pointer inference analysis.
     /// The result gets returned, but not saved to the `AnalysisResults` struct itself.
+    ///
+    /// Returns an error if the given configuration cannot be parsed.
     pub fn compute_pointer_inference(
         &'a self,
         config: &serde_json::Value,
         print_stats: bool,
-    ) -> PointerInference<'a> {
-        crate::analysis::pointer_inference::run(
+    ) -> Result<PointerInference<'a>, Error> {
+        Ok(crate::analysis::pointer_inference::run(
             self.project,
             self.runtime_memory_image,
             self.control_flow_graph,
-            serde_json::from_value(config.clone()).unwrap(),
+            serde_json::from_value(config.clone())?,
             false,
             print_stats,
-        )
+        ))
     }
 
     /// Create a new `AnalysisResults` struct containing the given pointer inference analysis results.
@@ -371,6 +371,7 @@ impl Term<Sub> {
             tid: self.tid,
             term: IrSub {
                 name: self.term.name,
+                signature: None,
                 blocks,
             },
         }
@@ -585,6 +586,19 @@ pub struct Program {
     ///
     /// Note that Ghidra may add an offset to the image base address as reported by the binary itself.
     pub image_base: String,
+    /// The memory blocks that Ghidra created when loading the binary.
+    #[serde(default)]
+    pub memory_blocks: Vec<MemoryBlock>,
+}
+
+/// A memory block created by Ghidra when loading the binary,
+/// e.g. for a section of an ELF file.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct MemoryBlock {
+    /// The name of the memory block.
+    pub name: String,
+    /// The start address of the memory block.
+    pub address: String,
 }
 
 impl Program {
@@ -612,13 +626,25 @@ impl Program {
             .into_iter()
             .map(|symbol| symbol.into_ir_symbol(conventions, stack_pointer, cpu_arch))
             .collect();
-        let address_base_offset =
-            u64::from_str_radix(&self.image_base, 16).unwrap() - binary_base_address;
+        // Ghidra may have loaded the binary to a lower address than its base address,
+        // so the offset has to wrap around on underflow.
+        let address_base_offset = u64::from_str_radix(&self.image_base, 16)
+            .unwrap()
+            .wrapping_sub(binary_base_address);
+        let section_addresses = self
+            .memory_blocks
+            .into_iter()
+            .filter_map(|block| {
+                let address = u64::from_str_radix(&block.address, 16).ok()?;
+                Some((block.name, address))
+            })
+            .collect();
         IrProgram {
             subs,
             extern_symbols,
             entry_points: self.entry_points,
             address_base_offset,
+            section_addresses,
         }
     }
 }
@@ -668,6 +694,10 @@ pub struct Project {
     pub register_calling_convention: Vec<CallingConvention>,
     /// Contains the properties of C data types. (e.g. size)
     pub datatype_properties: DatatypeProperties,
+    /// Set if the Ghidra analysis was aborted because of the analysis timeout.
+    /// In this case the disassembly of the binary may be incomplete.
+    #[serde(default)]
+    pub analysis_timeout_occurred: bool,
 }
 
 impl Project {
@@ -801,6 +831,9 @@ impl Project {
                 .collect(),
             register_list,
             datatype_properties: self.datatype_properties.clone(),
+            initial_stack_pointer: None,
+            vector_table_handlers: Vec::new(),
+            is_partial: self.analysis_timeout_occurred,
         }
     }
 }
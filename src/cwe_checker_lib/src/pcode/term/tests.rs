@@ -638,8 +638,13 @@ fn program_deserialization() {
 #[test]
 fn project_deserialization() {
     let setup = Setup::new();
-    let project: Project = setup.project.clone();
-    let _: IrProject = project.into_ir_project(10000);
+    let mut project: Project = setup.project.clone();
+    assert!(!project.analysis_timeout_occurred);
+    let ir_project: IrProject = project.clone().into_ir_project(10000);
+    assert!(!ir_project.is_partial);
+    project.analysis_timeout_occurred = true;
+    let ir_project: IrProject = project.into_ir_project(10000);
+    assert!(ir_project.is_partial);
 }
 
 #[test]
@@ -0,0 +1,521 @@
+//! High-level entry points for running the cwe_checker as a library.
+//!
+//! The [`analyze`] function runs the selected CWE checks on an already generated and normalized [`Project`].
+//! The [`analyze_binary`] function additionally uses a [`Frontend`] to generate the project for a binary file.
+//! Both return a [`Report`] containing all CWE warnings, log messages and some statistics about the analysis.
+
+use crate::analysis::function_signature::compute_function_signatures;
+use crate::analysis::graph;
+use crate::frontend::Frontend;
+use crate::intermediate_representation::Project;
+use crate::prelude::*;
+use crate::utils::binary::{get_macho_fat_slice, BareMetalConfig, RuntimeMemoryImage};
+use crate::utils::demangle::{demangle_cwe_warning, get_demangled_names};
+use crate::utils::dwarf::SourceMap;
+use crate::utils::error::{ErrorKind, WithErrorKind};
+use crate::utils::fingerprint::add_fingerprints;
+use crate::utils::graph_utils::add_witness_traces;
+use crate::utils::log::{add_debug_log_statistics, CweWarning, LogMessage};
+use crate::utils::prototypes::PrototypeDatabase;
+use crate::CweModule;
+use std::collections::hash_map::RandomState;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::hash::{BuildHasher, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// The names of the modules that need the results of the pointer inference analysis as input.
+const MODULES_DEPENDING_ON_POINTER_INFERENCE: [&str; 4] = ["CWE78", "CWE134", "CWE476", "Memory"];
+
+/// The names of the modules that use the parameters and return values of internal functions,
+/// see [`compute_function_signatures`].
+const MODULES_DEPENDING_ON_FUNCTION_SIGNATURES: [&str; 2] = ["CWE78", "CWE476"];
+
+/// Options for running the analysis.
+#[derive(Debug, PartialEq, Clone)]
+pub struct AnalysisOptions {
+    /// The configuration of the CWE checks, i.e. the content of the `config.json` configuration file.
+    pub config: serde_json::Value,
+    /// The names of the CWE checks to run.
+    ///
+    /// If not set, all checks except for the (very resource-intensive) CWE-78 check are run.
+    pub modules: Option<Vec<String>>,
+    /// If set, various statistics about the analysis quality are added to the log messages.
+    pub statistics: bool,
+    /// The bare metal configuration, if the binary is a bare metal binary.
+    pub bare_metal_config: Option<BareMetalConfig>,
+    /// The architecture to analyze if the binary is a fat Mach-O binary containing several architectures,
+    /// e.g. `x86_64` or `arm64`.
+    ///
+    /// Only used by [`analyze_binary`], see [`BinaryFile::read`].
+    pub macho_architecture: Option<String>,
+    /// The function prototypes used to fill in missing signatures of extern symbols.
+    ///
+    /// Only used by [`analyze_binary`], see [`PrototypeDatabase::add_missing_signatures`].
+    pub prototypes: PrototypeDatabase,
+}
+
+/// The content of an input binary file.
+///
+/// For fat Mach-O binaries only the slice containing the selected architecture is kept.
+/// Since frontends need a file containing exactly the analyzed binary,
+/// the slice is written to a temporary file that is removed again when the `BinaryFile` is dropped.
+#[derive(Debug)]
+pub struct BinaryFile {
+    path: PathBuf,
+    content: Vec<u8>,
+    is_temporary: bool,
+}
+
+impl BinaryFile {
+    /// Read the binary at the given path.
+    ///
+    /// If the file is a fat Mach-O binary, the slice for the given architecture is selected.
+    /// The architecture can be omitted if the fat binary contains only one architecture.
+    pub fn read(path: &Path, macho_architecture: Option<&str>) -> Result<BinaryFile, Error> {
+        let file_content = std::fs::read(path)
+            .map_err(|err| anyhow!("Could not read from file path {}: {}", path.display(), err))
+            .error_kind(ErrorKind::UnsupportedBinary)?;
+        let slice = get_macho_fat_slice(&file_content, macho_architecture)
+            .error_kind(ErrorKind::UnsupportedBinary)?;
+        if slice.len() == file_content.len() {
+            return Ok(BinaryFile {
+                path: path.to_path_buf(),
+                content: file_content,
+                is_temporary: false,
+            });
+        }
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| anyhow!("Invalid file name"))
+            .error_kind(ErrorKind::UnsupportedBinary)?
+            .to_string_lossy();
+        let (slice_path, mut slice_file) =
+            create_temporary_file(&file_name).error_kind(ErrorKind::Io)?;
+        if let Err(err) = slice_file.write_all(slice) {
+            drop(slice_file);
+            let _ = std::fs::remove_file(&slice_path);
+            return Err(anyhow!(
+                "Could not write Mach-O slice to {}: {}",
+                slice_path.display(),
+                err
+            ))
+            .error_kind(ErrorKind::Io);
+        }
+        Ok(BinaryFile {
+            path: slice_path,
+            content: slice.to_vec(),
+            is_temporary: true,
+        })
+    }
+
+    /// The path to a file containing exactly the bytes returned by [`BinaryFile::content`].
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The content of the binary.
+    pub fn content(&self) -> &[u8] {
+        &self.content
+    }
+}
+
+impl Drop for BinaryFile {
+    fn drop(&mut self) {
+        if self.is_temporary {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Exclusively create a new file with a unique name in the temporary directory of the system.
+///
+/// The name contains a random suffix and the file is created with [`OpenOptions::create_new`],
+/// so that the file cannot be a file or symbolic link prepared by another user of the shared temporary directory.
+fn create_temporary_file(file_name: &str) -> Result<(PathBuf, File), Error> {
+    const MAX_ATTEMPTS: u32 = 100;
+    for _ in 0..MAX_ATTEMPTS {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u128(
+            std::time::SystemTime::now()
+                .duration_since(std::time::SystemTime::UNIX_EPOCH)
+                .map(|duration| duration.as_nanos())
+                .unwrap_or_default(),
+        );
+        let path = std::env::temp_dir().join(format!(
+            "cwe_checker_{}_{:016x}_{}",
+            std::process::id(),
+            hasher.finish(),
+            file_name
+        ));
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(err) => {
+                return Err(anyhow!(
+                    "Could not create temporary file {}: {}",
+                    path.display(),
+                    err
+                ))
+            }
+        }
+    }
+    Err(anyhow!(
+        "Could not create a temporary file with a unique name"
+    ))
+}
+
+/// The results of an analysis run.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Report {
+    /// The CWE warnings generated by the CWE checks.
+    pub warnings: Vec<CweWarning>,
+    /// All log messages generated during the analysis.
+    pub logs: Vec<LogMessage>,
+    /// Statistics about the analyzed binary and the analysis run.
+    pub statistics: Statistics,
+    /// Set if the analysis results are partial,
+    /// because the disassembly of the binary is incomplete (see [`Project::is_partial`]).
+    #[serde(default)]
+    pub is_partial: bool,
+}
+
+/// Statistics about the analyzed binary and the analysis run.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct Statistics {
+    /// The names and version numbers of the CWE checks that were run.
+    pub module_versions: BTreeMap<String, String>,
+    /// The number of functions contained in the binary.
+    pub num_subs: usize,
+    /// The number of basic blocks contained in the binary.
+    pub num_blocks: usize,
+    /// The number of extern symbols referenced by the binary.
+    pub num_extern_symbols: usize,
+    /// The number of CWE warnings generated by each check.
+    pub num_warnings: BTreeMap<String, usize>,
+    /// The running time in milliseconds of each CWE check.
+    ///
+    /// The running time of the pointer inference analysis is listed separately as `PointerInference`.
+    pub running_time_ms: BTreeMap<String, u64>,
+}
+
+/// Get the modules to run for the given list of module names.
+///
+/// If no list of names is given, all modules except for the CWE-78 check are returned.
+/// Returns an error if a module name does not correspond to a known module.
+pub fn select_modules(module_names: Option<&[String]>) -> Result<Vec<&'static CweModule>, Error> {
+    let modules = crate::get_modules();
+    match module_names {
+        Some(module_names) => {
+            let module_names: HashSet<&str> = module_names
+                .iter()
+                .map(|name| name.as_str())
+                .filter(|name| !name.is_empty())
+                .collect();
+            module_names
+                .into_iter()
+                .map(|module_name| {
+                    modules
+                        .iter()
+                        .find(|module| module.name == module_name)
+                        .copied()
+                        .ok_or_else(|| anyhow!("{} is not a valid module name.", module_name))
+                        .error_kind(ErrorKind::Configuration)
+                })
+                .collect()
+        }
+        // TODO: CWE78 is disabled on a standard run for now,
+        // because it uses up huge amounts of RAM and computation time on some binaries.
+        None => Ok(modules
+            .into_iter()
+            .filter(|module| module.name != "CWE78")
+            .collect()),
+    }
+}
+
+/// Check that the configuration sections of the given modules can be parsed.
+/// If the pointer inference analysis is needed, its configuration section is checked, too.
+///
+/// This way an invalid configuration is reported as a configuration error
+/// before any (possibly long-running) analysis is started.
+fn check_module_configs(
+    modules: &[&'static CweModule],
+    needs_pointer_inference: bool,
+    config: &serde_json::Value,
+) -> Result<(), Error> {
+    let pointer_inference_module =
+        needs_pointer_inference.then_some(&crate::analysis::pointer_inference::CWE_MODULE);
+    for module in modules.iter().copied().chain(pointer_inference_module) {
+        (module.check_config)(&config[module.name])
+            .map_err(|err| {
+                anyhow!(
+                    "Invalid configuration section for module {}: {}",
+                    module.name,
+                    err
+                )
+            })
+            .error_kind(ErrorKind::Configuration)?;
+    }
+    Ok(())
+}
+
+/// Generate the runtime memory image of the binary
+/// with memory addresses matching the addresses of the given project.
+pub fn get_runtime_memory_image(
+    binary: &[u8],
+    project: &Project,
+    bare_metal_config: Option<&BareMetalConfig>,
+) -> Result<RuntimeMemoryImage, Error> {
+    let mut runtime_memory_image = if let Some(bare_metal_config) = bare_metal_config {
+        RuntimeMemoryImage::new_from_bare_metal(binary, bare_metal_config)
+    } else {
+        // The memory image is moved by the address base offset below,
+        // so the section addresses have to be given without the offset.
+        let section_addresses: Vec<(String, u64)> = project
+            .program
+            .term
+            .section_addresses
+            .iter()
+            .map(|(name, address)| {
+                (
+                    name.clone(),
+                    address.wrapping_sub(project.program.term.address_base_offset),
+                )
+            })
+            .collect();
+        RuntimeMemoryImage::new_with_section_addresses(binary, &section_addresses)
+    }
+    .map_err(|err| anyhow!("Error while generating runtime memory image: {}", err))
+    .error_kind(ErrorKind::UnsupportedBinary)?;
+    if project.program.term.address_base_offset != 0 {
+        // We adjust the memory addresses once globally
+        // so that other analyses do not have to adjust their addresses.
+        runtime_memory_image.add_global_memory_offset(project.program.term.address_base_offset);
+    }
+    Ok(runtime_memory_image)
+}
+
+/// Run the CWE checks selected in the `options` on the given project.
+///
+/// The project has to be normalized (see [`Project::normalize`]) before calling this function.
+/// The `binary` must be the content of the binary file from which the project was generated.
+/// If one of the selected checks uses them, the parameters and return values of internal functions
+/// are computed and added to the project first.
+pub fn analyze(
+    binary: &[u8],
+    project: &mut Project,
+    options: &AnalysisOptions,
+) -> Result<Report, Error> {
+    let modules = select_modules(options.modules.as_deref())?;
+    let needs_pointer_inference = modules
+        .iter()
+        .any(|module| MODULES_DEPENDING_ON_POINTER_INFERENCE.contains(&module.name));
+    check_module_configs(&modules, needs_pointer_inference, &options.config)?;
+    let mut all_logs = Vec::new();
+    if modules
+        .iter()
+        .any(|module| MODULES_DEPENDING_ON_FUNCTION_SIGNATURES.contains(&module.name))
+    {
+        all_logs.append(&mut compute_function_signatures(project));
+    }
+    let project: &Project = project;
+    let runtime_memory_image =
+        get_runtime_memory_image(binary, project, options.bare_metal_config.as_ref())?;
+    let extern_sub_tids = project
+        .program
+        .term
+        .extern_symbols
+        .iter()
+        .map(|symbol| symbol.tid.clone())
+        .collect();
+    let control_flow_graph = graph::get_program_cfg(&project.program, extern_sub_tids);
+    let analysis_results =
+        AnalysisResults::new(binary, &runtime_memory_image, &control_flow_graph, project);
+
+    let mut statistics = Statistics {
+        num_subs: project.program.term.subs.len(),
+        num_blocks: project
+            .program
+            .term
+            .subs
+            .iter()
+            .map(|sub| sub.term.blocks.len())
+            .sum(),
+        num_extern_symbols: project.program.term.extern_symbols.len(),
+        ..Statistics::default()
+    };
+
+    let pointer_inference_results = if needs_pointer_inference {
+        let start_time = std::time::Instant::now();
+        let pointer_inference_results = analysis_results
+            .compute_pointer_inference(&options.config["Memory"], options.statistics)
+            .error_kind(ErrorKind::Configuration)?;
+        statistics.running_time_ms.insert(
+            "PointerInference".to_string(),
+            start_time.elapsed().as_millis() as u64,
+        );
+        Some(pointer_inference_results)
+    } else {
+        None
+    };
+    let analysis_results =
+        analysis_results.set_pointer_inference(pointer_inference_results.as_ref());
+
+    // Execute the modules and collect their logs and CWE-warnings.
+    let mut all_cwes = Vec::new();
+    for module in modules {
+        let start_time = std::time::Instant::now();
+        let (mut logs, mut cwes) = (module.run)(&analysis_results, &options.config[&module.name]);
+        statistics.running_time_ms.insert(
+            module.name.to_string(),
+            start_time.elapsed().as_millis() as u64,
+        );
+        statistics
+            .module_versions
+            .insert(module.name.to_string(), module.version.to_string());
+        statistics
+            .num_warnings
+            .insert(module.name.to_string(), cwes.len());
+        all_logs.append(&mut logs);
+        all_cwes.append(&mut cwes);
+    }
+    add_witness_traces(&mut all_cwes, &control_flow_graph, project);
+    match SourceMap::new(binary, project.program.term.address_base_offset) {
+        Ok(Some(source_map)) => {
+            for cwe in all_cwes.iter_mut() {
+                source_map.add_source_locations(cwe);
+            }
+        }
+        Ok(None) => (),
+        Err(err) => all_logs.push(LogMessage::new_info(format!(
+            "Could not read the DWARF debug information of the binary: {}",
+            err
+        ))),
+    }
+    add_fingerprints(&mut all_cwes, project);
+    let demangled_names = get_demangled_names(project);
+    for cwe in all_cwes.iter_mut() {
+        demangle_cwe_warning(cwe, &demangled_names);
+    }
+    if options.statistics {
+        add_debug_log_statistics(&mut all_logs);
+    }
+
+    Ok(Report {
+        warnings: all_cwes,
+        logs: all_logs,
+        statistics,
+        is_partial: project.is_partial,
+    })
+}
+
+/// Generate the project for the binary at the given path with the given frontend
+/// and then run the CWE checks selected in the `options` on it.
+///
+/// Missing signatures of extern symbols are filled in from the prototypes given in the `options`
+/// before the project is normalized.
+/// Log messages generated by the frontend are also contained in the returned report.
+pub fn analyze_binary(
+    binary_path: &Path,
+    frontend: &dyn Frontend,
+    options: &AnalysisOptions,
+) -> Result<Report, Error> {
+    let binary = BinaryFile::read(binary_path, options.macho_architecture.as_deref())?;
+    let (mut project, mut logs) = frontend.get_project(binary.path(), binary.content())?;
+    logs.append(&mut options.prototypes.add_missing_signatures(&mut project));
+    logs.append(&mut project.normalize());
+    let mut report = analyze(binary.content(), &mut project, options)?;
+    logs.append(&mut report.logs);
+    report.logs = logs;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn module_selection() {
+        let default_modules = select_modules(None).unwrap();
+        assert_eq!(default_modules.len(), crate::get_modules().len() - 1);
+        assert!(default_modules.iter().all(|module| module.name != "CWE78"));
+
+        let module_names = vec!["CWE78".to_string(), "".to_string(), "Memory".to_string()];
+        let mut selected_names: Vec<&str> = select_modules(Some(&module_names))
+            .unwrap()
+            .into_iter()
+            .map(|module| module.name)
+            .collect();
+        selected_names.sort_unstable();
+        assert_eq!(selected_names, vec!["CWE78", "Memory"]);
+
+        assert!(select_modules(Some(&["CWE000".to_string()])).is_err());
+    }
+
+    #[test]
+    fn temporary_file_creation() {
+        let (first_path, _) = create_temporary_file("binary").unwrap();
+        let (second_path, _) = create_temporary_file("binary").unwrap();
+        assert_ne!(first_path, second_path);
+        assert!(first_path.is_file() && second_path.is_file());
+        std::fs::remove_file(first_path).unwrap();
+        std::fs::remove_file(second_path).unwrap();
+    }
+
+    #[test]
+    fn analyze_empty_project() {
+        let bare_metal_config = BareMetalConfig {
+            processor_id: "ARM:LE:32:v8".to_string(),
+            flash_base_address: "0x08000000".to_string(),
+            ram_base_address: "0x20000000".to_string(),
+            ram_size: "0x100".to_string(),
+            memory_regions: Vec::new(),
+        };
+        let options = AnalysisOptions {
+            config: serde_json::from_str(include_str!("../../config.json")).unwrap(),
+            modules: Some(vec!["CWE676".to_string(), "CWE782".to_string()]),
+            statistics: false,
+            bare_metal_config: Some(bare_metal_config),
+            macho_architecture: None,
+            prototypes: PrototypeDatabase::default(),
+        };
+        let report = analyze(&[0u8; 16], &mut Project::mock_empty(), &options).unwrap();
+        assert!(report.warnings.is_empty());
+        assert_eq!(report.statistics.module_versions.len(), 2);
+        assert_eq!(report.statistics.num_subs, 0);
+    }
+
+    #[test]
+    fn invalid_module_config() {
+        let mut config: serde_json::Value =
+            serde_json::from_str(include_str!("../../config.json")).unwrap();
+        config["Memory"] = serde_json::json!({ "allocation_symbols": 42 });
+        let bare_metal_config = BareMetalConfig {
+            processor_id: "ARM:LE:32:v8".to_string(),
+            flash_base_address: "0x08000000".to_string(),
+            ram_base_address: "0x20000000".to_string(),
+            ram_size: "0x100".to_string(),
+            memory_regions: Vec::new(),
+        };
+        let mut options = AnalysisOptions {
+            config,
+            modules: Some(vec!["CWE676".to_string()]),
+            statistics: false,
+            bare_metal_config: Some(bare_metal_config),
+            macho_architecture: None,
+            prototypes: PrototypeDatabase::default(),
+        };
+        // The pointer inference configuration is only checked if a module depends on it.
+        assert!(analyze(&[0u8; 16], &mut Project::mock_empty(), &options).is_ok());
+
+        options.modules = Some(vec!["CWE476".to_string()]);
+        let error = analyze(&[0u8; 16], &mut Project::mock_empty(), &options).unwrap_err();
+        assert_eq!(ErrorKind::of(&error), ErrorKind::Configuration);
+
+        options.modules = Some(vec!["CWE676".to_string()]);
+        options.config["CWE676"] = serde_json::json!({ "symbols": "strcpy" });
+        let error = analyze(&[0u8; 16], &mut Project::mock_empty(), &options).unwrap_err();
+        assert_eq!(ErrorKind::of(&error), ErrorKind::Configuration);
+    }
+}
@@ -0,0 +1,459 @@
+//! Application of ELF relocations to the runtime memory image.
+//!
+//! Only relocations that write complete addresses (see [`get_relocation_kind`]) are applied,
+//! while relocations patching parts of instructions (e.g. PC-relative branch targets) are skipped,
+//! since they are already resolved by Ghidra during disassembly.
+//! For linked binaries the applied dynamic relocations only target data locations.
+//! For relocatable ELF files, however, absolute relocations are also applied to executable sections,
+//! where they patch address operands of instructions (like `R_X86_64_32S`).
+//! Relocations referencing symbols imported from other libraries are not applied,
+//! since the addresses of imported symbols are unknown.
+
+use super::RuntimeMemoryImage;
+use goblin::elf;
+use goblin::elf::header::{EM_386, EM_AARCH64, EM_ARM, EM_MIPS, EM_PPC, EM_PPC64, EM_X86_64};
+use goblin::elf::reloc::*;
+
+/// Relocation type of PowerPC for 32-bit absolute addresses (not defined by goblin).
+const R_PPC_ADDR32: u32 = 1;
+/// Relocation type of 64-bit PowerPC for 64-bit absolute addresses (not defined by goblin).
+const R_PPC64_ADDR64: u32 = 38;
+/// Relocation type of (64-bit) PowerPC for GOT entries (not defined by goblin).
+const R_PPC_GLOB_DAT: u32 = 20;
+/// Relocation type of (64-bit) PowerPC for addresses relative to the base address (not defined by goblin).
+const R_PPC_RELATIVE: u32 = 22;
+
+/// Describes how the value written by a relocation is computed.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum RelocationKind {
+    /// The address of the symbol plus the addend (`S + A`) is written to a location of the given size in bytes.
+    Absolute(usize),
+    /// The base address plus the addend (`B + A`) is written to a location of the given size in bytes.
+    ///
+    /// Since the memory image is generated for the base address zero, the addend is the relocated value.
+    /// If the relocation references a symbol (like `R_MIPS_REL32` may do), its address is added, too.
+    Relative(usize),
+    /// The address of the symbol (`S`) is written to a location of the given size in bytes,
+    /// e.g. for entries in the global offset table.
+    Symbol(usize),
+}
+
+/// The value of a symbol referenced by a relocation.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(super) enum SymbolValue {
+    /// The address of a symbol contained in the memory image.
+    Address(u64),
+    /// The value of an absolute symbol (with section index `SHN_ABS`).
+    ///
+    /// Absolute values do not point into the memory image,
+    /// so they do not change when the memory image is moved to another base address.
+    Absolute(u64),
+}
+
+impl RelocationKind {
+    /// The size in bytes of the location written by the relocation.
+    fn size(&self) -> usize {
+        match self {
+            RelocationKind::Absolute(size)
+            | RelocationKind::Relative(size)
+            | RelocationKind::Symbol(size) => *size,
+        }
+    }
+}
+
+/// Get the kind of the relocation type `r_type` for the CPU architecture `machine`.
+/// The pointer size of the architecture is needed for relocation types whose size depends on it.
+///
+/// Returns `None` for relocation types that do not write addresses into data locations.
+fn get_relocation_kind(machine: u16, r_type: u32, pointer_size: usize) -> Option<RelocationKind> {
+    use RelocationKind::*;
+    let kind = match (machine, r_type) {
+        (EM_X86_64, R_X86_64_64) => Absolute(8),
+        (EM_X86_64, R_X86_64_32) | (EM_X86_64, R_X86_64_32S) => Absolute(4),
+        (EM_X86_64, R_X86_64_RELATIVE) => Relative(8),
+        (EM_X86_64, R_X86_64_GLOB_DAT) | (EM_X86_64, R_X86_64_JUMP_SLOT) => Symbol(8),
+        (EM_386, R_386_32) => Absolute(4),
+        (EM_386, R_386_RELATIVE) => Relative(4),
+        (EM_386, R_386_GLOB_DAT) | (EM_386, R_386_JMP_SLOT) => Symbol(4),
+        (EM_ARM, R_ARM_ABS32) => Absolute(4),
+        (EM_ARM, R_ARM_RELATIVE) => Relative(4),
+        (EM_ARM, R_ARM_GLOB_DAT) | (EM_ARM, R_ARM_JUMP_SLOT) => Symbol(4),
+        (EM_AARCH64, R_AARCH64_ABS64) => Absolute(8),
+        (EM_AARCH64, R_AARCH64_ABS32) => Absolute(4),
+        (EM_AARCH64, R_AARCH64_RELATIVE) => Relative(8),
+        (EM_AARCH64, R_AARCH64_GLOB_DAT) | (EM_AARCH64, R_AARCH64_JUMP_SLOT) => Symbol(8),
+        (EM_MIPS, R_MIPS_32) => Absolute(4),
+        (EM_MIPS, R_MIPS_64) => Absolute(8),
+        (EM_MIPS, R_MIPS_REL32) => Relative(pointer_size),
+        (EM_MIPS, R_MIPS_JUMP_SLOT) => Symbol(pointer_size),
+        (EM_PPC, R_PPC_ADDR32) => Absolute(4),
+        (EM_PPC, R_PPC_RELATIVE) => Relative(4),
+        (EM_PPC, R_PPC_GLOB_DAT) => Symbol(4),
+        (EM_PPC64, R_PPC64_ADDR64) => Absolute(8),
+        (EM_PPC64, R_PPC_RELATIVE) => Relative(8),
+        (EM_PPC64, R_PPC_GLOB_DAT) => Symbol(8),
+        _ => return None,
+    };
+    Some(kind)
+}
+
+impl RuntimeMemoryImage {
+    /// Apply the dynamic relocations of the given (not relocatable) ELF file to the memory image.
+    ///
+    /// The memory image is assumed to be loaded at the addresses given in the program headers,
+    /// i.e. the base address for relative relocations is zero.
+    pub(super) fn apply_elf_dynamic_relocations(&mut self, elf_file: &elf::Elf) {
+        let get_symbol_value = |symbol_index: usize| -> Option<SymbolValue> {
+            let symbol = elf_file.dynsyms.get(symbol_index)?;
+            match symbol.st_shndx as u32 {
+                elf::section_header::SHN_UNDEF => None,
+                elf::section_header::SHN_ABS => Some(SymbolValue::Absolute(symbol.st_value)),
+                _ if symbol.st_value == 0 => None,
+                _ => Some(SymbolValue::Address(symbol.st_value)),
+            }
+        };
+        for relocations in [&elf_file.dynrelas, &elf_file.dynrels, &elf_file.pltrelocs] {
+            self.apply_elf_relocations(
+                elf_file.header.e_machine,
+                if elf_file.is_64 { 8 } else { 4 },
+                relocations.iter(),
+                0,
+                &get_symbol_value,
+            );
+        }
+    }
+
+    /// Apply the given relocations for the CPU architecture `machine` to the memory image.
+    ///
+    /// The address of a relocated location is `offset_base + r_offset`.
+    /// The `get_symbol_value` function returns the value of the symbol with the given symbol table index
+    /// or `None` if the symbol is not defined.
+    /// Relocations of unsupported types, referencing undefined symbols
+    /// or pointing outside of the memory image are skipped.
+    /// The locations of relocations skipped because of their type or an undefined symbol
+    /// are added to the unresolved relocations of the memory image, since their content is only known at runtime.
+    /// The locations of applied relocations are added to the relocated pointers of the memory image
+    /// unless the written value is the value of an absolute symbol.
+    pub(super) fn apply_elf_relocations(
+        &mut self,
+        machine: u16,
+        pointer_size: usize,
+        relocations: impl Iterator<Item = elf::Reloc>,
+        offset_base: u64,
+        get_symbol_value: &dyn Fn(usize) -> Option<SymbolValue>,
+    ) {
+        for relocation in relocations {
+            let address = offset_base.wrapping_add(relocation.r_offset);
+            if relocation.r_type == 0 {
+                // The `R_*_NONE` relocation type is zero for all supported architectures.
+                continue;
+            }
+            let kind = match get_relocation_kind(machine, relocation.r_type, pointer_size) {
+                Some(kind) => kind,
+                None => {
+                    self.unresolved_relocations.push((address, pointer_size));
+                    continue;
+                }
+            };
+            let symbol_value = match (kind, relocation.r_sym) {
+                (RelocationKind::Relative(_), 0) => SymbolValue::Absolute(0),
+                _ => match get_symbol_value(relocation.r_sym) {
+                    Some(value) => value,
+                    None => {
+                        self.unresolved_relocations.push((address, kind.size()));
+                        continue;
+                    }
+                },
+            };
+            let (symbol_address, is_image_pointer) = match symbol_value {
+                SymbolValue::Address(symbol_address) => (symbol_address, true),
+                // Relative relocations add the base address, so their result always points into the memory image.
+                SymbolValue::Absolute(value) => {
+                    (value, matches!(kind, RelocationKind::Relative(_)))
+                }
+            };
+            let (size, value) = match kind {
+                RelocationKind::Symbol(size) => (size, symbol_address),
+                RelocationKind::Absolute(size) | RelocationKind::Relative(size) => {
+                    // For relocations without explicit addend the addend is stored at the relocated location.
+                    let addend = match relocation.r_addend {
+                        Some(addend) => addend as u64,
+                        None => match self.read_unsigned_raw(address, size) {
+                            Some(addend) => addend,
+                            None => continue,
+                        },
+                    };
+                    (size, symbol_address.wrapping_add(addend))
+                }
+            };
+            if self.write_unsigned_raw(address, size, value) && is_image_pointer {
+                self.relocated_pointers.push((address, size));
+            }
+        }
+    }
+
+    /// Get the bytes of the memory image in the interval of the given size starting at `address`
+    /// if the interval is completely contained in one memory segment.
+    fn get_bytes(&self, address: u64, size: usize) -> Option<&[u8]> {
+        self.memory_segments.iter().find_map(|segment| {
+            let start = address.checked_sub(segment.base_address)? as usize;
+            segment.bytes.get(start..start.checked_add(size)?)
+        })
+    }
+
+    /// Mutable variant of [`RuntimeMemoryImage::get_bytes`].
+    fn get_bytes_mut(&mut self, address: u64, size: usize) -> Option<&mut [u8]> {
+        self.memory_segments.iter_mut().find_map(|segment| {
+            let start = address.checked_sub(segment.base_address)? as usize;
+            segment.bytes.get_mut(start..start.checked_add(size)?)
+        })
+    }
+
+    /// Read the unsigned integer of the given size at the given address, regardless of segment permissions.
+    pub(super) fn read_unsigned_raw(&self, address: u64, size: usize) -> Option<u64> {
+        let bytes = self.get_bytes(address, size)?;
+        let value = if self.is_little_endian {
+            bytes
+                .iter()
+                .rev()
+                .fold(0u64, |value, byte| (value << 8) | *byte as u64)
+        } else {
+            bytes
+                .iter()
+                .fold(0u64, |value, byte| (value << 8) | *byte as u64)
+        };
+        Some(value)
+    }
+
+    /// Write the given value as an unsigned integer of the given size to the given address.
+    /// Higher bits of the value that do not fit into the given size are truncated.
+    /// Returns `false` if the address is not contained in the memory image.
+    pub(super) fn write_unsigned_raw(&mut self, address: u64, size: usize, value: u64) -> bool {
+        let is_little_endian = self.is_little_endian;
+        match self.get_bytes_mut(address, size) {
+            Some(bytes) => {
+                for (index, byte) in bytes.iter_mut().enumerate() {
+                    let shift = if is_little_endian {
+                        index
+                    } else {
+                        size - 1 - index
+                    };
+                    *byte = (value >> (8 * shift)) as u8;
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    /// Generate a minimal relocatable x86-64 ELF file with a `.data` and a `.bss` section
+    /// and two relocations in the `.data` section.
+    fn mock_relocatable_elf() -> Vec<u8> {
+        fn append_u16(bytes: &mut Vec<u8>, value: u16) {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        fn append_u32(bytes: &mut Vec<u8>, value: u32) {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        fn append_u64(bytes: &mut Vec<u8>, value: u64) {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        let shstrtab = b"\0.data\0.bss\0.symtab\0.strtab\0.rela.data\0.shstrtab\0";
+        let mut binary = vec![0x7f, b'E', b'L', b'F', 2, 1, 1];
+        binary.resize(16, 0);
+        append_u16(&mut binary, 1); // ET_REL
+        append_u16(&mut binary, EM_X86_64);
+        append_u32(&mut binary, 1);
+        append_u64(&mut binary, 0); // entry
+        append_u64(&mut binary, 0); // program header offset
+        append_u64(&mut binary, 0x110); // section header offset
+        append_u32(&mut binary, 0);
+        for value in [64, 56, 0, 64, 7, 6] {
+            append_u16(&mut binary, value);
+        }
+        // .data content at 0x40
+        binary.resize(0x50, 0);
+        // .symtab at 0x50: the null symbol, the section symbol of .data and a global symbol in .bss
+        binary.resize(0x50 + 24, 0);
+        for (name, info, section_index, value) in [(0u32, 3u8, 1u16, 0u64), (1, 0x11, 2, 8)] {
+            append_u32(&mut binary, name);
+            binary.push(info);
+            binary.push(0);
+            append_u16(&mut binary, section_index);
+            append_u64(&mut binary, value);
+            append_u64(&mut binary, 0);
+        }
+        // .strtab at 0x98
+        binary.extend_from_slice(b"\0g\0");
+        binary.resize(0xa0, 0);
+        // .rela.data at 0xa0
+        for (offset, symbol, r_type, addend) in
+            [(0u64, 2u64, R_X86_64_64, 4u64), (8, 1, R_X86_64_32, 2)]
+        {
+            append_u64(&mut binary, offset);
+            append_u64(&mut binary, (symbol << 32) | r_type as u64);
+            append_u64(&mut binary, addend);
+        }
+        // .shstrtab at 0xd0
+        binary.extend_from_slice(shstrtab);
+        binary.resize(0x110, 0);
+        // Section headers at 0x110
+        binary.resize(0x110 + 64, 0);
+        // name, type, flags, offset, size, link, info, alignment, entry size
+        let section_headers: [[u64; 9]; 6] = [
+            [1, 1, 3, 0x40, 16, 0, 0, 8, 0],
+            [7, 8, 3, 0x50, 0x20, 0, 0, 32, 0],
+            [12, 2, 0, 0x50, 72, 4, 2, 8, 24],
+            [20, 3, 0, 0x98, 3, 0, 0, 1, 0],
+            [28, 4, 0x40, 0xa0, 48, 3, 1, 8, 24],
+            [39, 3, 0, 0xd0, shstrtab.len() as u64, 0, 0, 1, 0],
+        ];
+        for [name, sh_type, flags, offset, size, link, info, align, entsize] in section_headers {
+            append_u32(&mut binary, name as u32);
+            append_u32(&mut binary, sh_type as u32);
+            append_u64(&mut binary, flags);
+            append_u64(&mut binary, 0);
+            append_u64(&mut binary, offset);
+            append_u64(&mut binary, size);
+            append_u32(&mut binary, link as u32);
+            append_u32(&mut binary, info as u32);
+            append_u64(&mut binary, align);
+            append_u64(&mut binary, entsize);
+        }
+        binary
+    }
+
+    #[test]
+    fn relocatable_elf_memory_image() {
+        let binary = mock_relocatable_elf();
+        assert_eq!(
+            crate::utils::get_binary_base_address(&binary).unwrap(),
+            0x100000
+        );
+        let mem_image = RuntimeMemoryImage::new(&binary).unwrap();
+        assert_eq!(mem_image.memory_segments.len(), 2);
+        assert_eq!(mem_image.memory_segments[0].base_address, 0x100000);
+        assert_eq!(mem_image.memory_segments[0].bytes.len(), 16);
+        // The .bss section is aligned to 32 bytes.
+        assert_eq!(mem_image.memory_segments[1].base_address, 0x100020);
+        assert_eq!(mem_image.memory_segments[1].bytes, vec![0u8; 0x20]);
+        // Pointer to the global symbol in .bss plus addend
+        assert_eq!(mem_image.read_unsigned_raw(0x100000, 8), Some(0x10002c));
+        // Pointer into the .data section relative to its section symbol
+        assert_eq!(mem_image.read_unsigned_raw(0x100008, 4), Some(0x100002));
+        assert_eq!(mem_image.read_unsigned_raw(0x10000c, 4), Some(0));
+    }
+
+    #[test]
+    fn relocatable_elf_with_frontend_section_addresses() {
+        let binary = mock_relocatable_elf();
+        // Memory blocks of the frontend that do not correspond to sections are ignored.
+        let section_addresses = vec![
+            ("EXTERNAL".to_string(), 0x300000),
+            (".bss".to_string(), 0x200000),
+            (".data".to_string(), 0x100100),
+        ];
+        let mem_image =
+            RuntimeMemoryImage::new_with_section_addresses(&binary, &section_addresses).unwrap();
+        assert_eq!(mem_image.memory_segments.len(), 2);
+        assert_eq!(mem_image.memory_segments[0].base_address, 0x100100);
+        assert_eq!(mem_image.memory_segments[1].base_address, 0x200000);
+        assert_eq!(mem_image.read_unsigned_raw(0x100100, 8), Some(0x20000c));
+        assert_eq!(mem_image.read_unsigned_raw(0x100108, 4), Some(0x100102));
+    }
+
+    #[test]
+    fn read_and_write_raw() {
+        let mut mem_image = RuntimeMemoryImage::mock();
+        mem_image.write_unsigned_raw(0x2000, 4, 0x1122334455);
+        assert_eq!(mem_image.read_unsigned_raw(0x2000, 8), Some(0x22334455));
+        mem_image.is_little_endian = false;
+        assert_eq!(mem_image.read_unsigned_raw(0x2000, 2), Some(0x5544));
+        assert_eq!(mem_image.read_unsigned_raw(0x2006, 4), None);
+    }
+
+    #[test]
+    fn dynamic_relocations() {
+        let mut mem_image = RuntimeMemoryImage::mock();
+        let get_symbol_value = |symbol_index: usize| match symbol_index {
+            1 => Some(SymbolValue::Address(0x3002)),
+            3 => Some(SymbolValue::Absolute(0x1234)),
+            _ => None,
+        };
+        let relocations = vec![
+            // Relative relocation with explicit addend
+            elf::Reloc {
+                r_offset: 0x2000,
+                r_addend: Some(0x1001),
+                r_sym: 0,
+                r_type: R_ARM_RELATIVE,
+            },
+            // GOT entry for a defined symbol
+            elf::Reloc {
+                r_offset: 0x2004,
+                r_addend: None,
+                r_sym: 1,
+                r_type: R_ARM_GLOB_DAT,
+            },
+            // GOT entry for an imported symbol is not applied.
+            elf::Reloc {
+                r_offset: 0x3000,
+                r_addend: None,
+                r_sym: 2,
+                r_type: R_ARM_JUMP_SLOT,
+            },
+            // Absolute symbols are not pointers into the memory image.
+            elf::Reloc {
+                r_offset: 0x4000,
+                r_addend: Some(1),
+                r_sym: 3,
+                r_type: R_ARM_ABS32,
+            },
+        ];
+        mem_image.apply_elf_relocations(EM_ARM, 4, relocations.into_iter(), 0, &get_symbol_value);
+        assert_eq!(mem_image.read_unsigned_raw(0x2000, 4), Some(0x1001));
+        assert_eq!(mem_image.read_unsigned_raw(0x2004, 4), Some(0x3002));
+        assert_eq!(mem_image.read_unsigned_raw(0x3000, 4), Some(0x65480201));
+        assert_eq!(mem_image.read_unsigned_raw(0x4000, 4), Some(0x1235));
+        assert_eq!(mem_image.unresolved_relocations, vec![(0x3000, 4)]);
+        // The content of the GOT entry of the imported symbol is unknown.
+        assert_eq!(
+            mem_image
+                .read(&Bitvector::from_u64(0x3000), ByteSize::new(4))
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            mem_image
+                .read(&Bitvector::from_u64(0x3002), ByteSize::new(4))
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            mem_image
+                .read(&Bitvector::from_u64(0x3004), ByteSize::new(4))
+                .unwrap(),
+            Some(Bitvector::from_u32(0x206f6c6c))
+        );
+        assert_eq!(mem_image.relocated_pointers, vec![(0x2000, 4), (0x2004, 4)]);
+
+        // Relocated pointers are moved together with the memory image.
+        mem_image.add_global_memory_offset(0x10000);
+        assert_eq!(mem_image.read_unsigned_raw(0x12000, 4), Some(0x11001));
+        assert_eq!(mem_image.read_unsigned_raw(0x12004, 4), Some(0x13002));
+        assert_eq!(mem_image.read_unsigned_raw(0x13000, 4), Some(0x65480201));
+        assert_eq!(mem_image.read_unsigned_raw(0x14000, 4), Some(0x1235));
+        assert_eq!(
+            mem_image
+                .read(&Bitvector::from_u64(0x13000), ByteSize::new(4))
+                .unwrap(),
+            None
+        );
+    }
+}
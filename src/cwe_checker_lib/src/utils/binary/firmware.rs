@@ -0,0 +1,250 @@
+//! Parsers for file formats in which firmware images for bare metal chips are commonly distributed.
+//!
+//! Intel HEX and Motorola S-record files consist of text records
+//! that each contain a chunk of data together with its memory address.
+//! The parsers merge the records into contiguous memory chunks.
+
+use crate::prelude::*;
+
+/// The file format of a bare metal firmware image.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum FirmwareFormat {
+    /// A raw dump of (a part of) the memory of the chip.
+    Raw,
+    /// An Intel HEX file.
+    IntelHex,
+    /// A Motorola S-record file.
+    SRecord,
+    /// An ELF file, which may contain only program headers and no section headers.
+    Elf,
+}
+
+impl FirmwareFormat {
+    /// Detect the format of the given firmware image.
+    ///
+    /// Files that are neither ELF files nor valid Intel HEX or S-record files are treated as raw memory dumps.
+    pub fn detect(binary: &[u8]) -> FirmwareFormat {
+        if binary.starts_with(b"\x7fELF") {
+            return FirmwareFormat::Elf;
+        }
+        let text = match std::str::from_utf8(binary) {
+            Ok(text) => text.trim_start_matches('\u{feff}').trim_start(),
+            Err(_) => return FirmwareFormat::Raw,
+        };
+        if text.starts_with(':') && parse_intel_hex(text).is_ok() {
+            FirmwareFormat::IntelHex
+        } else if text.starts_with('S') && parse_srecord(text).is_ok() {
+            FirmwareFormat::SRecord
+        } else {
+            FirmwareFormat::Raw
+        }
+    }
+}
+
+/// Parse the hexadecimal digits of a record into bytes and check that the sum of all bytes matches the checksum.
+///
+/// For Intel HEX files the sum of all bytes including the checksum has to be zero (modulo 256).
+/// For S-record files the sum of all bytes including the checksum has to be `0xff` (modulo 256).
+fn parse_record_bytes(
+    hex_digits: &str,
+    expected_sum: u8,
+    line_number: usize,
+) -> Result<Vec<u8>, Error> {
+    if hex_digits.len() % 2 == 1 || !hex_digits.is_ascii() {
+        return Err(anyhow!("Malformed record in line {}", line_number));
+    }
+    let bytes = (0..hex_digits.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&hex_digits[index..index + 2], 16))
+        .collect::<Result<Vec<u8>, _>>()
+        .map_err(|_| anyhow!("Malformed record in line {}", line_number))?;
+    let sum = bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    if sum != expected_sum {
+        return Err(anyhow!("Wrong checksum in line {}", line_number));
+    }
+    Ok(bytes)
+}
+
+/// Merge data records given as pairs of addresses and data into contiguous memory chunks.
+///
+/// If records overlap, records appearing later in the file overwrite earlier ones.
+fn merge_records(mut records: Vec<(u64, Vec<u8>)>) -> Vec<(u64, Vec<u8>)> {
+    // The sort is stable, so records with the same address keep their order in the file.
+    records.sort_by_key(|(address, _)| *address);
+    let mut chunks: Vec<(u64, Vec<u8>)> = Vec::new();
+    for (address, data) in records {
+        match chunks.last_mut() {
+            Some((chunk_address, chunk_data))
+                if address <= *chunk_address + chunk_data.len() as u64 =>
+            {
+                let start = (address - *chunk_address) as usize;
+                let end = start + data.len();
+                if end > chunk_data.len() {
+                    chunk_data.resize(end, 0);
+                }
+                chunk_data[start..end].copy_from_slice(&data);
+            }
+            _ => chunks.push((address, data)),
+        }
+    }
+    chunks
+}
+
+/// Parse the content of an Intel HEX file into contiguous memory chunks given as pairs of addresses and data.
+pub fn parse_intel_hex(text: &str) -> Result<Vec<(u64, Vec<u8>)>, Error> {
+    let mut records = Vec::new();
+    let mut base_address: u64 = 0;
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let hex_digits = line
+            .strip_prefix(':')
+            .ok_or_else(|| anyhow!("Missing start code in line {}", line_number))?;
+        let bytes = parse_record_bytes(hex_digits, 0, line_number)?;
+        if bytes.len() < 5 || bytes.len() != bytes[0] as usize + 5 {
+            return Err(anyhow!("Wrong record length in line {}", line_number));
+        }
+        let offset = u16::from_be_bytes([bytes[1], bytes[2]]) as u64;
+        let data = &bytes[4..bytes.len() - 1];
+        match bytes[3] {
+            // Data record
+            0x00 => records.push((base_address + offset, data.to_vec())),
+            // End of file record
+            0x01 => break,
+            // Extended segment address record
+            0x02 if data.len() == 2 => {
+                base_address = (u16::from_be_bytes([data[0], data[1]]) as u64) << 4
+            }
+            // Extended linear address record
+            0x04 if data.len() == 2 => {
+                base_address = (u16::from_be_bytes([data[0], data[1]]) as u64) << 16
+            }
+            // Start segment and start linear address records only contain the entry point.
+            0x03 | 0x05 => (),
+            _ => return Err(anyhow!("Invalid record in line {}", line_number)),
+        }
+    }
+    Ok(merge_records(records))
+}
+
+/// Parse the content of a Motorola S-record file into contiguous memory chunks given as pairs of addresses and data.
+pub fn parse_srecord(text: &str) -> Result<Vec<(u64, Vec<u8>)>, Error> {
+    let mut records = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut chars = line.chars();
+        if chars.next() != Some('S') {
+            return Err(anyhow!("Missing start code in line {}", line_number));
+        }
+        let address_length = match chars.next() {
+            Some('0') | Some('1') | Some('5') | Some('9') => 2,
+            Some('2') | Some('6') | Some('8') => 3,
+            Some('3') | Some('7') => 4,
+            _ => return Err(anyhow!("Invalid record type in line {}", line_number)),
+        };
+        let bytes = parse_record_bytes(&line[2..], 0xff, line_number)?;
+        if bytes.len() < address_length + 2 || bytes.len() != bytes[0] as usize + 1 {
+            return Err(anyhow!("Wrong record length in line {}", line_number));
+        }
+        // Only S1, S2 and S3 records contain data.
+        // The other records contain a header, record counts or the entry point.
+        if matches!(&line[1..2], "1" | "2" | "3") {
+            let address = bytes[1..=address_length]
+                .iter()
+                .fold(0u64, |address, byte| (address << 8) | *byte as u64);
+            let data = &bytes[address_length + 1..bytes.len() - 1];
+            records.push((address, data.to_vec()));
+        }
+    }
+    Ok(merge_records(records))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intel_hex_parsing() {
+        let hex_file = ":020000040800F2\n\
+            :0400000001020304F2\n\
+            :02000400AABB95\n\
+            :0200000212340000000000000000B6\n\
+            :00000001FF\n";
+        assert!(parse_intel_hex(hex_file).is_err());
+        let hex_file = ":020000040800F2\n\
+            :0400000001020304F2\n\
+            :02000400AABB95\n\
+            :020000021000EC\n\
+            :01000000CC33\n\
+            :00000001FF\n";
+        assert_eq!(
+            parse_intel_hex(hex_file).unwrap(),
+            vec![
+                (0x10000, vec![0xcc]),
+                (0x08000000, vec![0x01, 0x02, 0x03, 0x04, 0xaa, 0xbb])
+            ]
+        );
+        assert_eq!(
+            FirmwareFormat::detect(hex_file.as_bytes()),
+            FirmwareFormat::IntelHex
+        );
+        // Wrong checksum
+        assert!(parse_intel_hex(":0400000001020304F3\n").is_err());
+    }
+
+    #[test]
+    fn srecord_parsing() {
+        let srecord_file = "S00600004844521B\n\
+            S107000001020304EE\n\
+            S30908000004AABBCCDDDC\n\
+            S5030002FA\n\
+            S9030000FC\n";
+        assert_eq!(
+            parse_srecord(srecord_file).unwrap(),
+            vec![
+                (0, vec![0x01, 0x02, 0x03, 0x04]),
+                (0x08000004, vec![0xaa, 0xbb, 0xcc, 0xdd])
+            ]
+        );
+        assert_eq!(
+            FirmwareFormat::detect(srecord_file.as_bytes()),
+            FirmwareFormat::SRecord
+        );
+        // Wrong checksum
+        assert!(parse_srecord("S1070000010203046F\n").is_err());
+    }
+
+    #[test]
+    fn format_detection() {
+        assert_eq!(
+            FirmwareFormat::detect(b"\x7fELF\x01\x01"),
+            FirmwareFormat::Elf
+        );
+        assert_eq!(
+            FirmwareFormat::detect(&[0x00, 0x20, 0x00, 0x20]),
+            FirmwareFormat::Raw
+        );
+        assert_eq!(FirmwareFormat::detect(b"Some text"), FirmwareFormat::Raw);
+    }
+
+    #[test]
+    fn overlapping_records() {
+        let records = vec![
+            (0x10, vec![1, 2, 3]),
+            (0x12, vec![4, 5]),
+            (0x20, vec![6]),
+            (0x11, vec![7]),
+        ];
+        assert_eq!(
+            merge_records(records),
+            vec![(0x10, vec![1, 7, 4, 5]), (0x20, vec![6])]
+        );
+    }
+}
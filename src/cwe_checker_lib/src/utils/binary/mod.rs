@@ -0,0 +1,1525 @@
+//! Utility structs and functions which directly parse the binary file.
+
+use crate::intermediate_representation::BinOpType;
+use crate::intermediate_representation::BitvectorExtended;
+use crate::prelude::*;
+use elf_relocation::SymbolValue;
+use firmware::FirmwareFormat;
+use goblin::elf;
+use goblin::mach;
+use goblin::pe;
+use goblin::Object;
+use std::collections::HashMap;
+
+mod elf_relocation;
+pub mod firmware;
+pub mod vector_table;
+
+/// Contains all information parsed out of the bare metal configuration JSON file.
+///
+/// The content is information that is necessary for handling bare metal binaries
+/// and that the cwe_checker cannot automatically deduce from the binary itself.
+///
+/// The memory map of the MCU can be described in two ways:
+/// * By the `flash_base_address`, `ram_base_address` and `ram_size` fields.
+///   Then we assume that the corresponding MCU uses a very simple memory layout
+///   consisting of exactly one region of non-volatile (flash) memory
+///   and exactly one region of volatile memory (RAM).
+///   Furthermore, we assume that the binary itself is just a dump of the non-volatile memory region.
+/// * By a list of `memory_regions` (see [`MemoryRegion`]),
+///   e.g. for MCUs with several flash banks or SRAMs and with windows of memory-mapped peripherals.
+///   If the list is not empty, the other fields describing the memory layout are ignored.
+///
+/// If the binary is a firmware image in Intel HEX, S-record or ELF format (see [`FirmwareFormat`]),
+/// its content is mapped according to the addresses contained in the image
+/// and memory regions backed by the input binary are ignored.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct BareMetalConfig {
+    /// The CPU type.
+    ///
+    /// The string has to match the `processor_id` that Ghidra uses for the specific CPU type,
+    /// as it is forwarded to Ghidra to identify the CPU.
+    pub processor_id: String,
+    /// The base address of the non-volatile memory (usually flash memory) used by the chip.
+    /// The string is parsed as a hexadecimal number.
+    ///
+    /// We assume that the size of the non-volatile memory equals the size of the input binary.
+    /// In other words, we assume
+    /// that the input binary is a complete dump of the contents of the non-volatile memory of the chip.
+    #[serde(default)]
+    pub flash_base_address: String,
+    /// The base address of the volatile memory (RAM) used by the chip.
+    /// The string is parsed as a hexadecimal number.
+    #[serde(default)]
+    pub ram_base_address: String,
+    /// The size of the volatile memory (RAM) used by the chip.
+    /// The string is parsed as a hexadecimal number.
+    ///
+    /// If the exact size is unknown, then one can try to use an upper approximation instead.
+    #[serde(default)]
+    pub ram_size: String,
+    /// The memory regions of the chip.
+    ///
+    /// At least one region has to be backed by the input binary.
+    /// The first such region is the one that Ghidra uses as the base address of the binary.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub memory_regions: Vec<MemoryRegion>,
+}
+
+impl BareMetalConfig {
+    /// Return the base address of the binary as an integer,
+    /// i.e. the base address of the first memory region backed by the input binary.
+    ///
+    /// Returns an error if no memory region is backed by the input binary
+    /// or if its base address cannot be parsed.
+    pub fn parse_binary_base_address(&self) -> Result<u64, Error> {
+        let region = self.get_primary_memory_region()?;
+        parse_hex_string_to_u64(&region.base_address)
+            .map_err(|err| anyhow!("Parsing of the binary base address failed: {}", err))
+    }
+
+    /// Get the memory regions of the chip.
+    ///
+    /// If no memory regions are configured,
+    /// the flash and RAM regions described by the other fields of the configuration are returned.
+    pub fn get_memory_regions(&self) -> Vec<MemoryRegion> {
+        if !self.memory_regions.is_empty() {
+            return self.memory_regions.clone();
+        }
+        vec![
+            MemoryRegion {
+                name: "flash".to_string(),
+                base_address: self.flash_base_address.clone(),
+                size: None,
+                permissions: "rwx".to_string(),
+                file: None,
+                file_offset: Some("0x0".to_string()),
+                volatile: false,
+            },
+            MemoryRegion {
+                name: "ram".to_string(),
+                base_address: self.ram_base_address.clone(),
+                size: Some(self.ram_size.clone()),
+                permissions: "rw".to_string(),
+                file: None,
+                file_offset: None,
+                volatile: false,
+            },
+        ]
+    }
+
+    /// Get the first memory region that is backed by the input binary.
+    pub fn get_primary_memory_region(&self) -> Result<MemoryRegion, Error> {
+        self.get_memory_regions()
+            .into_iter()
+            .find(|region| region.is_backed_by_input_binary())
+            .ok_or_else(|| anyhow!("No memory region is backed by the input binary."))
+    }
+}
+
+/// A named memory region of a bare metal chip,
+/// e.g. a flash bank, an SRAM or a window of memory-mapped peripherals.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct MemoryRegion {
+    /// The name of the memory region.
+    pub name: String,
+    /// The base address of the memory region.
+    /// The string is parsed as a hexadecimal number.
+    pub base_address: String,
+    /// The size of the memory region in bytes.
+    /// The string is parsed as a hexadecimal number.
+    ///
+    /// May be omitted for regions backed by a file.
+    /// Then the region extends to the end of the file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<String>,
+    /// The access permissions of the memory region
+    /// as a combination of the characters `r` (read), `w` (write) and `x` (execute), e.g. `"rx"`.
+    pub permissions: String,
+    /// The path to a file containing the initial content of the memory region.
+    ///
+    /// If the path is not set but the `file_offset` is set, the region is backed by the input binary.
+    /// If neither is set, the region is uninitialized.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// The offset of the content of the memory region in the backing file.
+    /// The string is parsed as a hexadecimal number.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_offset: Option<String>,
+    /// Set for memory-mapped peripherals, whose content may change independently of the program.
+    ///
+    /// In the runtime memory image volatile regions are handled like writeable regions,
+    /// i.e. their content is never assumed to be known.
+    #[serde(default)]
+    pub volatile: bool,
+}
+
+impl MemoryRegion {
+    /// Returns `true` if the content of the region is read from the input binary.
+    pub fn is_backed_by_input_binary(&self) -> bool {
+        self.file.is_none() && self.file_offset.is_some()
+    }
+
+    /// Parse the base address of the region.
+    pub fn parse_base_address(&self) -> Result<u64, Error> {
+        parse_hex_string_to_u64(&self.base_address).map_err(|err| {
+            anyhow!(
+                "Invalid base address of memory region {}: {}",
+                self.name,
+                err
+            )
+        })
+    }
+
+    /// Parse the offset of the region content in the backing file.
+    /// Defaults to zero if no offset is given.
+    pub fn parse_file_offset(&self) -> Result<u64, Error> {
+        match &self.file_offset {
+            Some(offset) => parse_hex_string_to_u64(offset).map_err(|err| {
+                anyhow!(
+                    "Invalid file offset of memory region {}: {}",
+                    self.name,
+                    err
+                )
+            }),
+            None => Ok(0),
+        }
+    }
+
+    /// Compute the size of the region.
+    /// For regions without explicit size the size is determined by the length of the backing file,
+    /// which has to be given as `backing_file_length`.
+    pub fn parse_size(&self, backing_file_length: Option<u64>) -> Result<u64, Error> {
+        match (&self.size, backing_file_length) {
+            (Some(size), _) => parse_hex_string_to_u64(size)
+                .map_err(|err| anyhow!("Invalid size of memory region {}: {}", self.name, err)),
+            (None, Some(file_length)) => file_length
+                .checked_sub(self.parse_file_offset()?)
+                .ok_or_else(|| anyhow!("File offset of memory region {} out of bounds", self.name)),
+            (None, None) => Err(anyhow!("No size given for memory region {}", self.name)),
+        }
+    }
+
+    /// Returns `true` if the region has an explicitly configured size and contains the given address.
+    pub fn contains(&self, address: u64) -> bool {
+        match (self.parse_base_address(), self.size.is_some()) {
+            (Ok(base_address), true) => match self.parse_size(None) {
+                Ok(size) => address >= base_address && address - base_address < size,
+                Err(_) => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Returns whether the permissions of the region contain the given permission character.
+    pub fn has_permission(&self, permission: char) -> bool {
+        self.permissions.contains(permission)
+    }
+}
+
+/// A helper function to parse a hex string to an integer.
+fn parse_hex_string_to_u64(mut string: &str) -> Result<u64, Error> {
+    if string.starts_with("0x") {
+        string = &string[2..]
+    }
+    Ok(u64::from_str_radix(string, 16)?)
+}
+
+/// Returns `true` if the given Mach-O segment is the `__PAGEZERO` segment
+/// or another segment that is not accessible at runtime.
+///
+/// The `__PAGEZERO` segment reserves the (usually huge) lowest part of the address space
+/// to catch null pointer dereferences. It has no content in the file and cannot be accessed.
+fn is_macho_page_zero_segment(segment: &mach::segment::Segment) -> bool {
+    segment.name().ok() == Some("__PAGEZERO") || (segment.initprot == 0 && segment.filesize == 0)
+}
+
+/// For a fat Mach-O binary, return the slice containing the binary for the given architecture,
+/// e.g. `x86_64` or `arm64`.
+///
+/// If the fat binary contains only one architecture, the architecture name may be omitted.
+/// If the binary is not a fat Mach-O binary, it is returned unchanged.
+pub fn get_macho_fat_slice<'a>(
+    binary: &'a [u8],
+    architecture: Option<&str>,
+) -> Result<&'a [u8], Error> {
+    let multi_arch = match Object::parse(binary) {
+        Ok(Object::Mach(mach::Mach::Fat(multi_arch))) => multi_arch,
+        _ => return Ok(binary),
+    };
+    let fat_arches = multi_arch.arches()?;
+    let arch_names: Vec<&str> = fat_arches
+        .iter()
+        .map(|arch| {
+            mach::constants::cputype::get_arch_name_from_types(arch.cputype(), arch.cpusubtype())
+                .unwrap_or("unknown")
+        })
+        .collect();
+    let selected_arch = match architecture {
+        Some(architecture) => {
+            let (cputype, cpusubtype) = mach::constants::cputype::get_arch_from_flag(architecture)
+                .ok_or_else(|| anyhow!("Unknown Mach-O architecture {}", architecture))?;
+            // Prefer an exact match of CPU type and subtype over a match of the CPU type only.
+            fat_arches
+                .iter()
+                .find(|arch| arch.cputype() == cputype && arch.cpusubtype() == cpusubtype)
+                .or_else(|| fat_arches.iter().find(|arch| arch.cputype() == cputype))
+        }
+        None if fat_arches.len() == 1 => fat_arches.first(),
+        None => None,
+    };
+    match selected_arch {
+        Some(arch) => binary
+            .get(arch.offset as usize..(arch.offset as usize + arch.size as usize))
+            .ok_or_else(|| anyhow!("Malformed fat Mach-O binary")),
+        None => Err(anyhow!(
+            "Select one of the architectures contained in the fat Mach-O binary: {}",
+            arch_names.join(", ")
+        )),
+    }
+}
+
+/// Get the base address of the memory image of a relocatable ELF file, e.g. an object file or a Linux kernel module.
+///
+/// Relocatable files do not specify a memory layout,
+/// so we use the default base addresses that Ghidra uses when loading relocatable ELF files.
+pub fn get_relocatable_elf_base_address(elf_file: &elf::Elf) -> u64 {
+    if elf_file.is_64 {
+        0x100000
+    } else {
+        0x10000
+    }
+}
+
+/// Compute the addresses of the allocatable sections of a relocatable ELF file.
+/// The returned map maps section header indices to section addresses.
+///
+/// Relocatable files do not specify a memory layout, so the addresses are chosen by the loader of the frontend.
+/// The `frontend_section_addresses` contain the names and addresses of the sections as mapped by the frontend
+/// (see [`Program::section_addresses`](crate::intermediate_representation::Program::section_addresses)).
+/// Sections with the same name are matched in the order of the section header table.
+/// Allocatable sections not mapped by the frontend are not contained in the returned map.
+///
+/// If none of the sections is contained in `frontend_section_addresses`,
+/// the sections are mapped in the order of the section header table starting at [`get_relocatable_elf_base_address`],
+/// where each section is aligned according to its alignment requirement.
+fn get_relocatable_elf_section_addresses(
+    elf_file: &elf::Elf,
+    frontend_section_addresses: &[(String, u64)],
+) -> HashMap<usize, u64> {
+    let allocatable_sections = elf_file
+        .section_headers
+        .iter()
+        .enumerate()
+        .filter(|(_, section_header)| section_header.is_alloc() && section_header.sh_size != 0);
+    let mut section_addresses = HashMap::new();
+    let mut unused_frontend_sections: Vec<&(String, u64)> =
+        frontend_section_addresses.iter().collect();
+    for (index, section_header) in allocatable_sections.clone() {
+        let section_name = match elf_file.shdr_strtab.get(section_header.sh_name) {
+            Some(Ok(section_name)) => section_name,
+            _ => continue,
+        };
+        if let Some(position) = unused_frontend_sections
+            .iter()
+            .position(|(name, _)| name == section_name)
+        {
+            section_addresses.insert(index, unused_frontend_sections.remove(position).1);
+        }
+    }
+    if !section_addresses.is_empty() {
+        return section_addresses;
+    }
+    let mut next_address = get_relocatable_elf_base_address(elf_file);
+    for (index, section_header) in allocatable_sections {
+        let alignment = std::cmp::max(section_header.sh_addralign, 1);
+        let address = match next_address % alignment {
+            0 => next_address,
+            remainder => next_address + (alignment - remainder),
+        };
+        section_addresses.insert(index, address);
+        next_address = address + section_header.sh_size;
+    }
+    section_addresses
+}
+
+/// A representation of the runtime image of a binary after being loaded into memory by the loader.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct RuntimeMemoryImage {
+    memory_segments: Vec<MemorySegment>,
+    is_little_endian: bool,
+    /// The locations (address and size in bytes) of pointers that were written by applying relocations.
+    /// The pointers point into the memory image,
+    /// so they have to be adjusted when the memory image is moved to another base address.
+    relocated_pointers: Vec<(u64, usize)>,
+    /// The locations (address and size in bytes) of relocations that could not be applied,
+    /// e.g. because they reference symbols imported from other libraries.
+    /// The content of these locations is only known at runtime.
+    unresolved_relocations: Vec<(u64, usize)>,
+}
+
+/// A continuous segment in the memory image.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+struct MemorySegment {
+    /// The contents of the segment
+    pub bytes: Vec<u8>,
+    /// The base address, i.e. the address of the first byte of the segment
+    pub base_address: u64,
+    /// Is the segment readable
+    pub read_flag: bool,
+    /// Is the segment writeable
+    pub write_flag: bool,
+    /// Is the segment executable
+    pub execute_flag: bool,
+}
+
+impl MemorySegment {
+    /// Generate a segment from a program header of an ELF file.
+    pub fn from_elf_segment(binary: &[u8], program_header: &elf::ProgramHeader) -> MemorySegment {
+        let mut bytes: Vec<u8> = binary[program_header.file_range()].to_vec();
+        if program_header.vm_range().len() > program_header.file_range().len() {
+            // The additional memory space must be filled with null bytes.
+            bytes.resize(program_header.vm_range().len(), 0u8);
+        }
+        MemorySegment {
+            bytes,
+            base_address: program_header.p_vaddr,
+            read_flag: program_header.is_read(),
+            write_flag: program_header.is_write(),
+            execute_flag: program_header.is_executable(),
+        }
+    }
+
+    /// Generate a segment from an allocatable section of a relocatable ELF file
+    /// that gets mapped to the given base address.
+    pub fn from_elf_section(
+        binary: &[u8],
+        section_header: &elf::SectionHeader,
+        base_address: u64,
+    ) -> MemorySegment {
+        let bytes = if section_header.sh_type == elf::section_header::SHT_NOBITS {
+            vec![0u8; section_header.sh_size as usize]
+        } else {
+            binary
+                .get(section_header.file_range())
+                .unwrap_or_default()
+                .to_vec()
+        };
+        MemorySegment {
+            bytes,
+            base_address,
+            read_flag: true,
+            write_flag: section_header.is_writable(),
+            execute_flag: section_header.is_executable(),
+        }
+    }
+
+    /// Generate a segment from a section table from a PE file.
+    pub fn from_pe_section(
+        binary: &[u8],
+        section_header: &pe::section_table::SectionTable,
+    ) -> MemorySegment {
+        // Sections may be truncated in the file or not contained in the file at all (e.g. for `.bss` sections).
+        let raw_data_start =
+            std::cmp::min(section_header.pointer_to_raw_data as usize, binary.len());
+        let raw_data_end = std::cmp::min(
+            raw_data_start + section_header.size_of_raw_data as usize,
+            binary.len(),
+        );
+        let mut bytes: Vec<u8> = binary[raw_data_start..raw_data_end].to_vec();
+        // The size of the section in memory is given by the virtual size.
+        // If the virtual size is zero, the size of the raw data is used instead.
+        // If the raw data is larger than the virtual size, it contains padding that is not mapped into memory.
+        // If the raw data is smaller than the virtual size, the additional memory space is filled with null bytes.
+        if section_header.virtual_size != 0 {
+            bytes.resize(section_header.virtual_size as usize, 0u8);
+        }
+        MemorySegment {
+            bytes,
+            base_address: section_header.virtual_address as u64,
+            read_flag: (section_header.characteristics & 0x40000000) != 0,
+            write_flag: (section_header.characteristics & 0x80000000) != 0,
+            execute_flag: (section_header.characteristics & 0x20000000) != 0,
+        }
+    }
+
+    /// Generate a read-only segment containing the headers of a PE file.
+    ///
+    /// The loader maps the headers to the start of the memory image, i.e. to the relative virtual address zero.
+    pub fn from_pe_headers(binary: &[u8], size_of_headers: u32) -> MemorySegment {
+        let headers_end = std::cmp::min(size_of_headers as usize, binary.len());
+        MemorySegment {
+            bytes: binary[..headers_end].to_vec(),
+            base_address: 0,
+            read_flag: true,
+            write_flag: false,
+            execute_flag: false,
+        }
+    }
+
+    /// Generate a segment from a segment load command of a Mach-O file.
+    pub fn from_macho_segment(segment: &mach::segment::Segment) -> MemorySegment {
+        let mut bytes: Vec<u8> = segment.data.to_vec();
+        if segment.vmsize > bytes.len() as u64 {
+            // The additional memory space must be filled with null bytes.
+            bytes.resize(segment.vmsize as usize, 0u8);
+        }
+        MemorySegment {
+            bytes,
+            base_address: segment.vmaddr,
+            read_flag: (segment.initprot & mach::constants::VM_PROT_READ) != 0,
+            write_flag: (segment.initprot & mach::constants::VM_PROT_WRITE) != 0,
+            execute_flag: (segment.initprot & mach::constants::VM_PROT_EXECUTE) != 0,
+        }
+    }
+
+    /// Generate the segments containing the content of a firmware image in Intel HEX, S-record or ELF format.
+    ///
+    /// For ELF files the segments are generated from the loadable program headers,
+    /// so that ELF files without section headers are supported.
+    /// Segments generated from the address records of Intel HEX and S-record files
+    /// get the permissions of the configured memory region containing them.
+    /// If there is no such region, they are assumed to be read-only and executable, as is usual for flash memory.
+    pub fn from_firmware_image(
+        binary: &[u8],
+        format: FirmwareFormat,
+        regions: &[MemoryRegion],
+    ) -> Result<Vec<MemorySegment>, Error> {
+        let chunks = match format {
+            FirmwareFormat::Elf => {
+                let elf_file = elf::Elf::parse(binary)?;
+                let segments: Vec<MemorySegment> = elf_file
+                    .program_headers
+                    .iter()
+                    .filter(|header| header.p_type == elf::program_header::PT_LOAD)
+                    .map(|header| MemorySegment::from_elf_segment(binary, header))
+                    .collect();
+                if segments.is_empty() {
+                    return Err(anyhow!("No loadable segments found"));
+                }
+                return Ok(segments);
+            }
+            FirmwareFormat::IntelHex => firmware::parse_intel_hex(std::str::from_utf8(binary)?)?,
+            FirmwareFormat::SRecord => firmware::parse_srecord(std::str::from_utf8(binary)?)?,
+            FirmwareFormat::Raw => return Err(anyhow!("Raw binaries contain no address records.")),
+        };
+        let segments = chunks
+            .into_iter()
+            .map(|(address, bytes)| {
+                let (read_flag, write_flag, execute_flag) =
+                    match regions.iter().find(|region| region.contains(address)) {
+                        Some(region) => (
+                            region.has_permission('r'),
+                            region.has_permission('w') || region.volatile,
+                            region.has_permission('x'),
+                        ),
+                        None => (true, false, true),
+                    };
+                MemorySegment {
+                    bytes,
+                    base_address: address,
+                    read_flag,
+                    write_flag,
+                    execute_flag,
+                }
+            })
+            .collect();
+        Ok(segments)
+    }
+
+    /// Generate a segment for a memory region of a bare metal binary.
+    ///
+    /// The content of the segment is read from the backing file of the region,
+    /// where `binary` is the content of the input binary.
+    /// Uninitialized memory and memory not covered by the backing file is filled with null bytes.
+    pub fn from_bare_metal_region(
+        region: &MemoryRegion,
+        binary: &[u8],
+    ) -> Result<MemorySegment, Error> {
+        let backing_file_content: Option<std::borrow::Cow<[u8]>> = if let Some(path) = &region.file
+        {
+            Some(
+                std::fs::read(path)
+                    .map_err(|err| anyhow!("Could not read file {}: {}", path, err))?
+                    .into(),
+            )
+        } else if region.is_backed_by_input_binary() {
+            Some(binary.into())
+        } else {
+            None
+        };
+        let size = region.parse_size(
+            backing_file_content
+                .as_ref()
+                .map(|content| content.len() as u64),
+        )?;
+        // For large uninitialized regions (e.g. windows of memory-mapped peripherals)
+        // the zeroed memory is only allocated lazily by the operating system.
+        let mut bytes = vec![0u8; size as usize];
+        if let Some(content) = backing_file_content {
+            let start = std::cmp::min(region.parse_file_offset()? as usize, content.len());
+            let end = std::cmp::min(start.saturating_add(size as usize), content.len());
+            bytes[..(end - start)].copy_from_slice(&content[start..end]);
+        }
+        Ok(MemorySegment {
+            bytes,
+            base_address: region.parse_base_address()?,
+            read_flag: region.has_permission('r'),
+            write_flag: region.has_permission('w') || region.volatile,
+            execute_flag: region.has_permission('x'),
+        })
+    }
+}
+
+impl RuntimeMemoryImage {
+    /// Generate a runtime memory image for a given binary.
+    ///
+    /// The function can parse ELF (including relocatable ELF files), PE and Mach-O files as input.
+    /// Fat Mach-O binaries containing several architectures are not supported,
+    /// use [`get_macho_fat_slice`] to select one architecture first.
+    ///
+    /// The sections of relocatable ELF files are mapped to the default layout of [`get_relocatable_elf_section_addresses`].
+    /// Use [`RuntimeMemoryImage::new_with_section_addresses`] to map them to the addresses chosen by the frontend.
+    pub fn new(binary: &[u8]) -> Result<Self, Error> {
+        RuntimeMemoryImage::new_with_section_addresses(binary, &[])
+    }
+
+    /// Generate a runtime memory image for a given binary,
+    /// where the sections of relocatable ELF files are mapped to the given `section_addresses`
+    /// (see [`get_relocatable_elf_section_addresses`]).
+    ///
+    /// For all other file types the section addresses are ignored, see [`RuntimeMemoryImage::new`].
+    pub fn new_with_section_addresses(
+        binary: &[u8],
+        section_addresses: &[(String, u64)],
+    ) -> Result<Self, Error> {
+        let parsed_object = Object::parse(binary)?;
+
+        match parsed_object {
+            Object::Elf(elf_file) if elf_file.header.e_type == elf::header::ET_REL => {
+                RuntimeMemoryImage::new_from_relocatable_elf(binary, &elf_file, section_addresses)
+            }
+            Object::Elf(elf_file) => {
+                let mut memory_segments = Vec::new();
+                for header in elf_file.program_headers.iter() {
+                    if header.p_type == elf::program_header::PT_LOAD {
+                        memory_segments.push(MemorySegment::from_elf_segment(binary, header));
+                    }
+                }
+                if memory_segments.is_empty() {
+                    return Err(anyhow!("No loadable segments found"));
+                }
+                let mut memory_image = RuntimeMemoryImage {
+                    memory_segments,
+                    is_little_endian: elf_file.header.endianness().unwrap().is_little(),
+                    relocated_pointers: Vec::new(),
+                    unresolved_relocations: Vec::new(),
+                };
+                memory_image.apply_elf_dynamic_relocations(&elf_file);
+                for header in elf_file.program_headers.iter() {
+                    if header.p_type == elf::program_header::PT_GNU_RELRO {
+                        // The loader makes this region read-only after applying the relocations.
+                        memory_image.mark_as_read_only(header.p_vaddr, header.p_memsz);
+                    }
+                }
+                Ok(memory_image)
+            }
+            Object::PE(pe_file) => {
+                let mut memory_segments = Vec::new();
+                for header in pe_file.sections.iter() {
+                    if (header.characteristics & 0x02000000) == 0 {
+                        // Only load segments which are not discardable
+                        memory_segments.push(MemorySegment::from_pe_section(binary, header));
+                    }
+                }
+                if memory_segments.is_empty() {
+                    return Err(anyhow!("No loadable segments found"));
+                }
+                if let Some(optional_header) = pe_file.header.optional_header {
+                    let size_of_headers = optional_header.windows_fields.size_of_headers;
+                    if size_of_headers != 0 {
+                        memory_segments
+                            .push(MemorySegment::from_pe_headers(binary, size_of_headers));
+                    }
+                }
+                // The section addresses are relative to the image base given in the optional header.
+                let mut memory_image = RuntimeMemoryImage {
+                    memory_segments,
+                    is_little_endian: true,
+                    relocated_pointers: Vec::new(),
+                    unresolved_relocations: Vec::new(),
+                };
+                memory_image.add_global_memory_offset(pe_file.image_base as u64);
+                Ok(memory_image)
+            }
+            Object::Mach(mach::Mach::Binary(macho_file)) => {
+                let mut memory_segments = Vec::new();
+                for segment in macho_file.segments.iter() {
+                    if !is_macho_page_zero_segment(segment) {
+                        memory_segments.push(MemorySegment::from_macho_segment(segment));
+                    }
+                }
+                if memory_segments.is_empty() {
+                    return Err(anyhow!("No loadable segments found"));
+                }
+                Ok(RuntimeMemoryImage {
+                    memory_segments,
+                    is_little_endian: macho_file.little_endian,
+                    relocated_pointers: Vec::new(),
+                    unresolved_relocations: Vec::new(),
+                })
+            }
+            Object::Mach(mach::Mach::Fat(_)) => Err(anyhow!(
+                "Fat Mach-O binaries are not supported. Select an architecture first."
+            )),
+            _ => Err(anyhow!("Object type not supported.")),
+        }
+    }
+
+    /// Generate the runtime memory image of a relocatable ELF file, e.g. an object file or a Linux kernel module.
+    ///
+    /// Since relocatable files are not linked yet, the allocatable sections are mapped to the addresses
+    /// computed by [`get_relocatable_elf_section_addresses`] from the `frontend_section_addresses`.
+    /// Afterwards the relocations writing addresses into the allocatable sections are applied.
+    fn new_from_relocatable_elf(
+        binary: &[u8],
+        elf_file: &elf::Elf,
+        frontend_section_addresses: &[(String, u64)],
+    ) -> Result<Self, Error> {
+        let section_addresses =
+            get_relocatable_elf_section_addresses(elf_file, frontend_section_addresses);
+        let mut memory_segments: Vec<MemorySegment> = section_addresses
+            .iter()
+            .map(|(index, address)| {
+                MemorySegment::from_elf_section(binary, &elf_file.section_headers[*index], *address)
+            })
+            .collect();
+        if memory_segments.is_empty() {
+            return Err(anyhow!("No allocatable sections found"));
+        }
+        memory_segments.sort_by_key(|segment| segment.base_address);
+        let mut memory_image = RuntimeMemoryImage {
+            memory_segments,
+            is_little_endian: elf_file.little_endian,
+            relocated_pointers: Vec::new(),
+            unresolved_relocations: Vec::new(),
+        };
+
+        let get_symbol_value = |symbol_index: usize| -> Option<SymbolValue> {
+            let symbol = elf_file.syms.get(symbol_index)?;
+            match symbol.st_shndx as u32 {
+                elf::section_header::SHN_UNDEF => None,
+                elf::section_header::SHN_ABS => Some(SymbolValue::Absolute(symbol.st_value)),
+                section_index => section_addresses
+                    .get(&(section_index as usize))
+                    .map(|section_address| SymbolValue::Address(section_address + symbol.st_value)),
+            }
+        };
+        for (relocation_section_index, relocations) in elf_file.shdr_relocs.iter() {
+            // The `sh_info` field of a relocation section contains the index of the section to relocate.
+            let target_section_index =
+                elf_file.section_headers[*relocation_section_index].sh_info as usize;
+            if let Some(target_address) = section_addresses.get(&target_section_index) {
+                memory_image.apply_elf_relocations(
+                    elf_file.header.e_machine,
+                    if elf_file.is_64 { 8 } else { 4 },
+                    relocations.iter(),
+                    *target_address,
+                    &get_symbol_value,
+                );
+            }
+        }
+        Ok(memory_image)
+    }
+
+    /// Generate a runtime memory image for a bare metal binary.
+    ///
+    /// The binary may be a raw memory dump or a firmware image in Intel HEX, S-record or ELF format
+    /// (see [`FirmwareFormat`]).
+    /// For firmware images the content is mapped according to the addresses contained in the image,
+    /// and memory regions backed by the input binary are ignored.
+    ///
+    /// The generated runtime memory image contains one memory segment for each memory region
+    /// returned by [`BareMetalConfig::get_memory_regions`].
+    /// Without an explicitly configured list of memory regions these are:
+    /// * one memory region corresponding to non-volatile memory
+    /// * one memory region corresponding to volatile memory (RAM)
+    ///
+    /// See [`BareMetalConfig`] for more information about the assumed memory layout for bare metal binaries.
+    pub fn new_from_bare_metal(
+        binary: &[u8],
+        bare_metal_config: &BareMetalConfig,
+    ) -> Result<Self, Error> {
+        let processor_id_parts: Vec<&str> = bare_metal_config.processor_id.split(':').collect();
+        if processor_id_parts.len() < 3 {
+            return Err(anyhow!("Could not parse processor ID."));
+        }
+        let is_little_endian = match processor_id_parts[1] {
+            "LE" => true,
+            "BE" => false,
+            _ => return Err(anyhow!("Could not parse endianness of the processor ID.")),
+        };
+        let address_bit_length = processor_id_parts[2].parse::<u64>()?;
+        let format = FirmwareFormat::detect(binary);
+        let regions = bare_metal_config.get_memory_regions();
+        let mut memory_segments = if format == FirmwareFormat::Raw {
+            bare_metal_config.get_primary_memory_region()?;
+            Vec::new()
+        } else {
+            MemorySegment::from_firmware_image(binary, format, &regions)?
+        };
+        for region in regions.iter() {
+            // For firmware images with address information
+            // the content of the input binary is already contained in the segments generated from it.
+            if format != FirmwareFormat::Raw && region.is_backed_by_input_binary() {
+                continue;
+            }
+            memory_segments.push(MemorySegment::from_bare_metal_region(region, binary)?);
+        }
+        // Check that all segments are contained in addressable space.
+        for segment in memory_segments.iter() {
+            let is_addressable = match segment
+                .base_address
+                .checked_add((segment.bytes.len() as u64).saturating_sub(1))
+            {
+                Some(last_address) => {
+                    last_address
+                        .checked_shr(address_bit_length as u32)
+                        .unwrap_or(0)
+                        == 0
+                }
+                None => false,
+            };
+            if !is_addressable {
+                return Err(anyhow!(
+                    "Memory segment at address {:#x} too large for the address space",
+                    segment.base_address
+                ));
+            }
+        }
+
+        Ok(RuntimeMemoryImage {
+            memory_segments,
+            is_little_endian,
+            relocated_pointers: Vec::new(),
+            unresolved_relocations: Vec::new(),
+        })
+    }
+
+    /// Return whether values in the memory image should be interpreted in little-endian
+    /// or big-endian byte order.
+    pub fn is_little_endian_byte_order(&self) -> bool {
+        self.is_little_endian
+    }
+
+    /// Add a global offset to the base addresses of all memory segments.
+    /// Useful to align the addresses with those reported by Ghidra
+    /// if the Ghidra backend added such an offset to all addresses.
+    pub fn add_global_memory_offset(&mut self, offset: u64) {
+        for segment in self.memory_segments.iter_mut() {
+            segment.base_address = segment.base_address.wrapping_add(offset);
+        }
+        // Pointers written by relocations point into the memory image, so they have to be moved, too.
+        let mut relocated_pointers = std::mem::take(&mut self.relocated_pointers);
+        for (address, size) in relocated_pointers.iter_mut() {
+            *address = address.wrapping_add(offset);
+            if let Some(value) = self.read_unsigned_raw(*address, *size) {
+                self.write_unsigned_raw(*address, *size, value.wrapping_add(offset));
+            }
+        }
+        self.relocated_pointers = relocated_pointers;
+        for (address, _size) in self.unresolved_relocations.iter_mut() {
+            *address = address.wrapping_add(offset);
+        }
+    }
+
+    /// Check whether the interval of the given size starting at `address`
+    /// intersects a location of a relocation that could not be applied.
+    fn contains_unresolved_relocation(&self, address: u64, size: u64) -> bool {
+        self.unresolved_relocations
+            .iter()
+            .any(|(location, location_size)| {
+                *location < address.saturating_add(size)
+                    && address < location.saturating_add(*location_size as u64)
+            })
+    }
+
+    /// Remove write permissions from the memory region of the given size starting at `start_address`.
+    ///
+    /// Memory segments partially overlapping the region are split accordingly.
+    fn mark_as_read_only(&mut self, start_address: u64, size: u64) {
+        let end_address = start_address.saturating_add(size);
+        let mut memory_segments = Vec::new();
+        for segment in std::mem::take(&mut self.memory_segments) {
+            let segment_end = segment.base_address + segment.bytes.len() as u64;
+            if !segment.write_flag
+                || segment_end <= start_address
+                || segment.base_address >= end_address
+            {
+                memory_segments.push(segment);
+                continue;
+            }
+            let split_start = (std::cmp::max(start_address, segment.base_address)
+                - segment.base_address) as usize;
+            let split_end =
+                (std::cmp::min(end_address, segment_end) - segment.base_address) as usize;
+            for (range, write_flag) in [
+                (0..split_start, true),
+                (split_start..split_end, false),
+                (split_end..segment.bytes.len(), true),
+            ] {
+                if !range.is_empty() {
+                    memory_segments.push(MemorySegment {
+                        base_address: segment.base_address + range.start as u64,
+                        bytes: segment.bytes[range].to_vec(),
+                        read_flag: segment.read_flag,
+                        write_flag,
+                        execute_flag: segment.execute_flag,
+                    });
+                }
+            }
+        }
+        self.memory_segments = memory_segments;
+    }
+
+    /// Read the contents of the memory image at the given address
+    /// to emulate a read instruction to global data at runtime.
+    ///
+    /// The read method is endian-aware,
+    /// i.e. values are interpreted with the endianness of the CPU architecture.
+    /// If the address points to a writeable segment, the returned value is a `Ok(None)` value,
+    /// since the data may change during program execution.
+    /// The same holds for locations of relocations that could not be applied (e.g. GOT entries of imported symbols),
+    /// since their content is only known at runtime.
+    ///
+    /// Returns an error if the address is not contained in the global data address range.
+    pub fn read(&self, address: &Bitvector, size: ByteSize) -> Result<Option<Bitvector>, Error> {
+        let address = address.try_to_u64().unwrap();
+        for segment in self.memory_segments.iter() {
+            if address >= segment.base_address
+                && u64::from(size) <= segment.base_address + segment.bytes.len() as u64
+                && address <= segment.base_address + segment.bytes.len() as u64 - u64::from(size)
+            {
+                if segment.write_flag
+                    || self.contains_unresolved_relocation(address, u64::from(size))
+                {
+                    // The segment is writeable or the value is set by the loader,
+                    // thus we do not know the content at runtime.
+                    return Ok(None);
+                }
+                let index = (address - segment.base_address) as usize;
+                let mut bytes = segment.bytes[index..index + u64::from(size) as usize].to_vec();
+                if self.is_little_endian {
+                    bytes = bytes.into_iter().rev().collect();
+                }
+                let mut bytes = bytes.into_iter();
+                let mut bitvector = Bitvector::from_u8(bytes.next().unwrap());
+                for byte in bytes {
+                    let new_byte = Bitvector::from_u8(byte);
+                    bitvector = bitvector.bin_op(BinOpType::Piece, &new_byte)?;
+                }
+                return Ok(Some(bitvector));
+            }
+        }
+        // No segment fully contains the read.
+        Err(anyhow!("Address is not a valid global memory address."))
+    }
+
+    /// Read the contents of memory from a given address onwards until a null byte is reached and checks whether the
+    /// content is a valid UTF8 string.
+    pub fn read_string_until_null_terminator(&self, address: &Bitvector) -> Result<&str, Error> {
+        let address = address.try_to_u64().unwrap();
+        for segment in self.memory_segments.iter() {
+            if address >= segment.base_address
+                && address <= segment.base_address + segment.bytes.len() as u64
+            {
+                let start_index = (address - segment.base_address) as usize;
+                if let Some(end_index) = segment.bytes[start_index..].iter().position(|&b| b == 0) {
+                    let c_str = std::ffi::CStr::from_bytes_with_nul(
+                        &segment.bytes[start_index..start_index + end_index + 1],
+                    )?;
+                    return Ok(c_str.to_str()?);
+                } else {
+                    return Err(anyhow!("Not a valid string in memory."));
+                }
+            }
+        }
+
+        Err(anyhow!("Address is not a valid global memory address."))
+    }
+
+    /// Checks whether the constant is a global memory address.
+    pub fn is_global_memory_address(&self, constant: &Bitvector) -> bool {
+        if self.read(constant, constant.bytesize()).is_ok() {
+            return true;
+        }
+        false
+    }
+
+    /// Check whether all addresses in the given interval point to a readable segment in the runtime memory image.
+    ///
+    /// Returns an error if the address interval intersects more than one memory segment
+    /// or if it does not point to global memory at all.
+    pub fn is_interval_readable(
+        &self,
+        start_address: u64,
+        end_address: u64,
+    ) -> Result<bool, Error> {
+        for segment in self.memory_segments.iter() {
+            if start_address >= segment.base_address
+                && start_address < segment.base_address + segment.bytes.len() as u64
+            {
+                if end_address <= segment.base_address + segment.bytes.len() as u64 {
+                    return Ok(segment.read_flag);
+                } else {
+                    return Err(anyhow!("Interval spans more than one segment"));
+                }
+            }
+        }
+        Err(anyhow!("Address not contained in runtime memory image"))
+    }
+
+    /// For an address to global read-only memory, return the memory segment it points to
+    /// and the index inside the segment, where the address points to.
+    ///
+    /// Returns an error if the target memory segment is marked as writeable,
+    /// if the address points to the location of a relocation that could not be applied
+    /// or if the pointer does not point to global memory.
+    pub fn get_ro_data_pointer_at_address(
+        &self,
+        address: &Bitvector,
+    ) -> Result<(&[u8], usize), Error> {
+        let address = address.try_to_u64().unwrap();
+        for segment in self.memory_segments.iter() {
+            if address >= segment.base_address
+                && address < segment.base_address + segment.bytes.len() as u64
+            {
+                if segment.write_flag {
+                    return Err(anyhow!("Target segment is writeable"));
+                } else if self.contains_unresolved_relocation(address, 1) {
+                    return Err(anyhow!(
+                        "Target is the location of an unresolved relocation"
+                    ));
+                } else {
+                    return Ok((&segment.bytes, (address - segment.base_address) as usize));
+                }
+            }
+        }
+        Err(anyhow!("Pointer target not in global memory."))
+    }
+
+    /// Check whether the given address points to a writeable segment in the runtime memory image.
+    ///
+    /// Returns an error if the address does not point to global memory.
+    pub fn is_address_writeable(&self, address: &Bitvector) -> Result<bool, Error> {
+        let address = address.try_to_u64().unwrap();
+        for segment in self.memory_segments.iter() {
+            if address >= segment.base_address
+                && address < segment.base_address + segment.bytes.len() as u64
+            {
+                return Ok(segment.write_flag);
+            }
+        }
+        Err(anyhow!("Address not contained in runtime memory image"))
+    }
+
+    /// Check whether all addresses in the given interval point to a writeable segment in the runtime memory image.
+    ///
+    /// Returns an error if the address interval intersects more than one memory segment
+    /// or if it does not point to global memory at all.
+    pub fn is_interval_writeable(
+        &self,
+        start_address: u64,
+        end_address: u64,
+    ) -> Result<bool, Error> {
+        for segment in self.memory_segments.iter() {
+            if start_address >= segment.base_address
+                && start_address < segment.base_address + segment.bytes.len() as u64
+            {
+                if end_address <= segment.base_address + segment.bytes.len() as u64 {
+                    return Ok(segment.write_flag);
+                } else {
+                    return Err(anyhow!("Interval spans more than one segment"));
+                }
+            }
+        }
+        Err(anyhow!("Address not contained in runtime memory image"))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    impl RuntimeMemoryImage {
+        /// Create a mock runtime memory image for unit tests.
+        pub fn mock() -> RuntimeMemoryImage {
+            RuntimeMemoryImage {
+                memory_segments: vec![
+                    MemorySegment {
+                        bytes: [0xb0u8, 0xb1, 0xb2, 0xb3, 0xb4].to_vec(),
+                        base_address: 0x1000,
+                        read_flag: true,
+                        write_flag: false,
+                        execute_flag: false,
+                    },
+                    MemorySegment {
+                        bytes: [0u8; 8].to_vec(),
+                        base_address: 0x2000,
+                        read_flag: true,
+                        write_flag: true,
+                        execute_flag: false,
+                    },
+                    // Contains the Hello World string at byte 3002.
+                    MemorySegment {
+                        bytes: [
+                            0x01, 0x02, 0x48, 0x65, 0x6c, 0x6c, 0x6f, 0x20, 0x57, 0x6f, 0x72, 0x6c,
+                            0x64, 0x00,
+                        ]
+                        .to_vec(),
+                        base_address: 0x3000,
+                        read_flag: true,
+                        write_flag: false,
+                        execute_flag: false,
+                    },
+                    MemorySegment {
+                        bytes: [0x02, 0x30, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00].to_vec(),
+                        base_address: 0x4000,
+                        read_flag: true,
+                        write_flag: false,
+                        execute_flag: false,
+                    },
+                    // Contains strings: '/dev/sd%c%d' and 'cat %s'
+                    MemorySegment {
+                        bytes: [
+                            0x2f, 0x64, 0x65, 0x76, 0x2f, 0x73, 0x64, 0x25, 0x63, 0x25, 0x64, 0x00,
+                            0x63, 0x61, 0x74, 0x20, 0x25, 0x73, 0x00,
+                        ]
+                        .to_vec(),
+                        base_address: 0x5000,
+                        read_flag: true,
+                        write_flag: false,
+                        execute_flag: false,
+                    },
+                    // Contains string: 'cat %s %s %s %s' starting at the first byte.
+                    MemorySegment {
+                        bytes: [
+                            0x63, 0x61, 0x74, 0x20, 0x25, 0x73, 0x20, 0x25, 0x73, 0x20, 0x25, 0x73,
+                            0x20, 0x25, 0x73, 0x00,
+                        ]
+                        .to_vec(),
+                        base_address: 0x6000,
+                        read_flag: true,
+                        write_flag: false,
+                        execute_flag: false,
+                    },
+                    // Contains string: 'str1 str2 str3 str4'
+                    MemorySegment {
+                        bytes: [
+                            0x73, 0x74, 0x72, 0x31, 0x20, 0x73, 0x74, 0x72, 0x32, 0x20, 0x73, 0x74,
+                            0x72, 0x33, 0x20, 0x73, 0x74, 0x72, 0x34, 0x00,
+                        ]
+                        .to_vec(),
+                        base_address: 0x7000,
+                        read_flag: true,
+                        write_flag: false,
+                        execute_flag: false,
+                    },
+                ],
+                is_little_endian: true,
+                relocated_pointers: Vec::new(),
+                unresolved_relocations: Vec::new(),
+            }
+        }
+    }
+
+    #[test]
+    fn read_endianness() {
+        let mut mem_image = RuntimeMemoryImage::mock();
+        let address = Bitvector::from_u32(0x1001);
+        assert_eq!(
+            mem_image.read(&address, ByteSize::new(4)).unwrap(),
+            Bitvector::from_u32(0xb4b3b2b1).into()
+        );
+        mem_image.is_little_endian = false;
+        assert_eq!(
+            mem_image.read(&address, ByteSize::new(4)).unwrap(),
+            Bitvector::from_u32(0xb1b2b3b4).into()
+        );
+    }
+
+    #[test]
+    fn ro_data_pointer() {
+        let mem_image = RuntimeMemoryImage::mock();
+        let address = Bitvector::from_u32(0x1002);
+        let (slice, index) = mem_image.get_ro_data_pointer_at_address(&address).unwrap();
+        assert_eq!(index, 2);
+        assert_eq!(&slice[index..], &[0xb2u8, 0xb3, 0xb4]);
+    }
+
+    #[test]
+    fn test_read_string_until_null_terminator() {
+        let mem_image = RuntimeMemoryImage::mock();
+        // the byte array contains "Hello World".
+        let expected_string: &str =
+            std::str::from_utf8(b"\x48\x65\x6c\x6c\x6f\x20\x57\x6f\x72\x6c\x64").unwrap();
+        let address = Bitvector::from_u32(0x3002);
+        assert_eq!(
+            expected_string,
+            mem_image
+                .read_string_until_null_terminator(&address)
+                .unwrap(),
+        );
+    }
+
+    /// Generate a minimal 64-bit little-endian Mach-O executable with a `__PAGEZERO`,
+    /// a `__TEXT` and a `__DATA` segment.
+    fn mock_macho_binary() -> Vec<u8> {
+        fn segment_command(
+            name: &str,
+            vmaddr: u64,
+            vmsize: u64,
+            fileoff: u64,
+            filesize: u64,
+            initprot: u32,
+        ) -> Vec<u8> {
+            let mut command = Vec::new();
+            command.extend_from_slice(&0x19u32.to_le_bytes()); // LC_SEGMENT_64
+            command.extend_from_slice(&72u32.to_le_bytes());
+            let mut segname = [0u8; 16];
+            segname[..name.len()].copy_from_slice(name.as_bytes());
+            command.extend_from_slice(&segname);
+            for value in [vmaddr, vmsize, fileoff, filesize] {
+                command.extend_from_slice(&value.to_le_bytes());
+            }
+            for value in [initprot, initprot, 0, 0] {
+                command.extend_from_slice(&value.to_le_bytes());
+            }
+            command
+        }
+        let mut binary = Vec::new();
+        for value in [0xfeedfacfu32, 0x0100_0007, 3, 2, 3, 3 * 72, 0, 0] {
+            binary.extend_from_slice(&value.to_le_bytes());
+        }
+        binary.append(&mut segment_command(
+            "__PAGEZERO",
+            0,
+            0x1_0000_0000,
+            0,
+            0,
+            0,
+        ));
+        binary.append(&mut segment_command(
+            "__TEXT",
+            0x1_0000_0000,
+            0x100,
+            0,
+            0x100,
+            5,
+        ));
+        binary.append(&mut segment_command(
+            "__DATA",
+            0x1_0000_1000,
+            0x20,
+            0x100,
+            0x10,
+            3,
+        ));
+        binary.resize(0x110, 0xaa);
+        binary
+    }
+
+    #[test]
+    fn macho_memory_image() {
+        let binary = mock_macho_binary();
+        let mem_image = RuntimeMemoryImage::new(&binary).unwrap();
+        assert_eq!(mem_image.memory_segments.len(), 2);
+        assert!(mem_image.is_little_endian);
+        let text_segment = &mem_image.memory_segments[0];
+        assert_eq!(text_segment.base_address, 0x1_0000_0000);
+        assert_eq!(text_segment.bytes.len(), 0x100);
+        assert!(text_segment.read_flag && !text_segment.write_flag && text_segment.execute_flag);
+        let data_segment = &mem_image.memory_segments[1];
+        assert_eq!(data_segment.base_address, 0x1_0000_1000);
+        assert_eq!(data_segment.bytes[..0x10], [0xaa; 0x10]);
+        assert_eq!(data_segment.bytes[0x10..], [0; 0x10]);
+        assert!(data_segment.read_flag && data_segment.write_flag && !data_segment.execute_flag);
+        assert_eq!(
+            crate::utils::get_binary_base_address(&binary).unwrap(),
+            0x1_0000_0000
+        );
+    }
+
+    #[test]
+    fn macho_fat_slice() {
+        let thin_binary = mock_macho_binary();
+        let mut fat_binary = Vec::new();
+        for value in [
+            0xcafebabeu32,
+            1,
+            0x0100_0007,
+            3,
+            0x40,
+            thin_binary.len() as u32,
+            0,
+        ] {
+            fat_binary.extend_from_slice(&value.to_be_bytes());
+        }
+        fat_binary.resize(0x40, 0);
+        fat_binary.extend_from_slice(&thin_binary);
+
+        assert!(RuntimeMemoryImage::new(&fat_binary).is_err());
+        assert_eq!(
+            get_macho_fat_slice(&fat_binary, Some("x86_64")).unwrap(),
+            &thin_binary[..]
+        );
+        assert_eq!(
+            get_macho_fat_slice(&fat_binary, None).unwrap(),
+            &thin_binary[..]
+        );
+        assert!(get_macho_fat_slice(&fat_binary, Some("arm64")).is_err());
+        assert!(get_macho_fat_slice(&fat_binary, Some("no_arch")).is_err());
+        // Thin binaries are returned unchanged.
+        assert_eq!(
+            get_macho_fat_slice(&thin_binary, Some("arm64")).unwrap(),
+            &thin_binary[..]
+        );
+    }
+
+    /// Generate a minimal 32-bit PE file with image base `0x400000`,
+    /// a `.text` section with raw data larger than its virtual size
+    /// and a `.data` section with raw data smaller than its virtual size.
+    fn mock_pe_binary() -> Vec<u8> {
+        fn section_header(
+            name: &str,
+            virtual_size: u32,
+            virtual_address: u32,
+            raw_size: u32,
+            raw_pointer: u32,
+            characteristics: u32,
+        ) -> Vec<u8> {
+            let mut header = Vec::new();
+            let mut section_name = [0u8; 8];
+            section_name[..name.len()].copy_from_slice(name.as_bytes());
+            header.extend_from_slice(&section_name);
+            for value in [
+                virtual_size,
+                virtual_address,
+                raw_size,
+                raw_pointer,
+                0,
+                0,
+                0,
+                characteristics,
+            ] {
+                header.extend_from_slice(&value.to_le_bytes());
+            }
+            header
+        }
+        let mut binary = vec![0u8; 0x40];
+        binary[0..2].copy_from_slice(b"MZ");
+        binary[0x3c..0x40].copy_from_slice(&0x40u32.to_le_bytes());
+        binary.extend_from_slice(b"PE\0\0");
+        // COFF header
+        binary.extend_from_slice(&0x14cu16.to_le_bytes());
+        binary.extend_from_slice(&2u16.to_le_bytes());
+        binary.extend_from_slice(&[0u8; 12]);
+        binary.extend_from_slice(&224u16.to_le_bytes());
+        binary.extend_from_slice(&0x102u16.to_le_bytes());
+        // Optional header: standard fields
+        binary.extend_from_slice(&0x10bu16.to_le_bytes());
+        binary.extend_from_slice(&[0u8; 2]);
+        for value in [0x200u32, 0x200, 0, 0x1000, 0x1000, 0x2000] {
+            binary.extend_from_slice(&value.to_le_bytes());
+        }
+        // Optional header: Windows specific fields
+        for value in [0x400000u32, 0x1000, 0x200, 0, 0, 0, 0, 0x3000, 0x200, 0] {
+            binary.extend_from_slice(&value.to_le_bytes());
+        }
+        binary.extend_from_slice(&3u16.to_le_bytes());
+        binary.extend_from_slice(&0u16.to_le_bytes());
+        for value in [0x100000u32, 0x1000, 0x100000, 0x1000, 0, 16] {
+            binary.extend_from_slice(&value.to_le_bytes());
+        }
+        // Data directories
+        binary.extend_from_slice(&[0u8; 128]);
+        binary.append(&mut section_header(
+            ".text", 0x10, 0x1000, 0x200, 0x200, 0x60000020,
+        ));
+        binary.append(&mut section_header(
+            ".data", 0x300, 0x2000, 0x200, 0x400, 0xc0000040,
+        ));
+        binary.resize(0x200, 0);
+        binary.resize(0x400, 0xcc);
+        binary.resize(0x600, 0xdd);
+        binary
+    }
+
+    #[test]
+    fn pe_memory_image() {
+        let binary = mock_pe_binary();
+        assert_eq!(
+            crate::utils::get_binary_base_address(&binary).unwrap(),
+            0x400000
+        );
+        let mem_image = RuntimeMemoryImage::new(&binary).unwrap();
+        assert_eq!(mem_image.memory_segments.len(), 3);
+        let text_segment = &mem_image.memory_segments[0];
+        assert_eq!(text_segment.base_address, 0x401000);
+        assert_eq!(text_segment.bytes, vec![0xcc; 0x10]);
+        assert!(text_segment.execute_flag && !text_segment.write_flag);
+        let data_segment = &mem_image.memory_segments[1];
+        assert_eq!(data_segment.base_address, 0x402000);
+        assert_eq!(data_segment.bytes.len(), 0x300);
+        assert_eq!(data_segment.bytes[0x1ff], 0xdd);
+        assert_eq!(data_segment.bytes[0x200], 0);
+        assert!(data_segment.write_flag && !data_segment.execute_flag);
+        let header_segment = &mem_image.memory_segments[2];
+        assert_eq!(header_segment.base_address, 0x400000);
+        assert_eq!(header_segment.bytes.len(), 0x200);
+        assert_eq!(
+            mem_image
+                .read(&Bitvector::from_u32(0x400000), ByteSize::new(2))
+                .unwrap()
+                .unwrap(),
+            Bitvector::from_u16(0x5a4d)
+        );
+    }
+
+    #[test]
+    fn read_only_marking() {
+        let mut mem_image = RuntimeMemoryImage::mock();
+        let num_segments = mem_image.memory_segments.len();
+        // The writeable segment at 0x2000 is split into three segments.
+        mem_image.mark_as_read_only(0x2002, 4);
+        assert_eq!(mem_image.memory_segments.len(), num_segments + 2);
+        assert!(mem_image
+            .is_address_writeable(&Bitvector::from_u32(0x2001))
+            .unwrap());
+        assert!(!mem_image
+            .is_address_writeable(&Bitvector::from_u32(0x2002))
+            .unwrap());
+        assert!(!mem_image
+            .is_address_writeable(&Bitvector::from_u32(0x2005))
+            .unwrap());
+        assert!(mem_image
+            .is_address_writeable(&Bitvector::from_u32(0x2006))
+            .unwrap());
+        assert_eq!(
+            mem_image
+                .read(&Bitvector::from_u32(0x2002), ByteSize::new(4))
+                .unwrap(),
+            Some(Bitvector::from_u32(0))
+        );
+        assert_eq!(
+            mem_image
+                .read(&Bitvector::from_u32(0x2006), ByteSize::new(2))
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn bare_metal_memory_regions() {
+        let legacy_config: BareMetalConfig =
+            serde_json::from_str(include_str!("../../../../../bare_metal/stm32f407vg.json"))
+                .unwrap();
+        assert_eq!(
+            legacy_config.parse_binary_base_address().unwrap(),
+            0x08000000
+        );
+        let mem_image =
+            RuntimeMemoryImage::new_from_bare_metal(&[1, 2, 3], &legacy_config).unwrap();
+        assert_eq!(mem_image.memory_segments.len(), 2);
+        assert_eq!(mem_image.memory_segments[0].bytes, vec![1, 2, 3]);
+        assert_eq!(mem_image.memory_segments[1].base_address, 0x20000000);
+        assert_eq!(mem_image.memory_segments[1].bytes.len(), 0x30000);
+
+        let backing_file_path = std::env::temp_dir().join(format!(
+            "cwe_checker_memory_region_test_{}",
+            std::process::id()
+        ));
+        std::fs::write(&backing_file_path, [0xaa, 0xbb, 0xcc]).unwrap();
+        let config: BareMetalConfig = serde_json::from_value(serde_json::json!({
+            "processor_id": "ARM:LE:32:Cortex",
+            "memory_regions": [
+                { "name": "sram", "base_address": "0x20000000", "size": "0x10", "permissions": "rw" },
+                { "name": "bank1", "base_address": "0x08000000", "size": "0x2", "permissions": "rx", "file_offset": "0x0" },
+                { "name": "bank2", "base_address": "0x08100000", "permissions": "r", "file_offset": "0x2" },
+                { "name": "external", "base_address": "0x90000000", "size": "0x4", "permissions": "r", "file": backing_file_path.to_str().unwrap(), "file_offset": "0x1" },
+                { "name": "peripherals", "base_address": "0xe0000000", "size": "0x20000000", "permissions": "r", "volatile": true },
+            ]
+        }))
+        .unwrap();
+        assert_eq!(config.parse_binary_base_address().unwrap(), 0x08000000);
+        let mem_image = RuntimeMemoryImage::new_from_bare_metal(&[1, 2, 3, 4], &config).unwrap();
+        std::fs::remove_file(&backing_file_path).unwrap();
+        let segments = &mem_image.memory_segments;
+        assert_eq!(segments.len(), 5);
+        assert_eq!(segments[0].bytes, vec![0; 0x10]);
+        assert!(segments[0].write_flag && !segments[0].execute_flag);
+        assert_eq!(segments[1].bytes, vec![1, 2]);
+        assert!(!segments[1].write_flag && segments[1].execute_flag);
+        assert_eq!(segments[2].bytes, vec![3, 4]);
+        assert_eq!(segments[3].bytes, vec![0xbb, 0xcc, 0, 0]);
+        // Volatile regions are treated as writeable.
+        assert!(segments[4].write_flag);
+        assert_eq!(
+            mem_image
+                .read(&Bitvector::from_u32(0x08000000), ByteSize::new(2))
+                .unwrap(),
+            Some(Bitvector::from_u16(0x0201))
+        );
+        assert_eq!(
+            mem_image
+                .read(&Bitvector::from_u32(0xe0000000), ByteSize::new(4))
+                .unwrap(),
+            None
+        );
+
+        let mut config = config;
+        config.memory_regions[1].file_offset = None;
+        config.memory_regions[2].file_offset = None;
+        assert!(RuntimeMemoryImage::new_from_bare_metal(&[1, 2, 3, 4], &config).is_err());
+    }
+
+    #[test]
+    fn bare_metal_firmware_image() {
+        let config: BareMetalConfig = serde_json::from_str(include_str!(
+            "../../../../../bare_metal/stm32f407vg_memory_map.json"
+        ))
+        .unwrap();
+        let hex_file = ":020000040800F2\n\
+            :0400000001020304F2\n\
+            :020000042000DA\n\
+            :01000000CC33\n\
+            :00000001FF\n";
+        let mem_image =
+            RuntimeMemoryImage::new_from_bare_metal(hex_file.as_bytes(), &config).unwrap();
+        let segments = &mem_image.memory_segments;
+        // The flash region backed by the input binary is replaced by the content of the HEX file.
+        assert_eq!(segments.len(), 2 + config.memory_regions.len() - 1);
+        assert_eq!(segments[0].base_address, 0x08000000);
+        assert_eq!(segments[0].bytes, vec![1, 2, 3, 4]);
+        assert!(!segments[0].write_flag && segments[0].execute_flag);
+        // Chunks inside a configured region get the permissions of the region.
+        assert_eq!(segments[1].base_address, 0x20000000);
+        assert!(segments[1].write_flag);
+        assert_eq!(
+            mem_image
+                .read(&Bitvector::from_u32(0x08000000), ByteSize::new(4))
+                .unwrap(),
+            Some(Bitvector::from_u32(0x04030201))
+        );
+
+        let invalid_config: BareMetalConfig = serde_json::from_value(serde_json::json!({
+            "processor_id": "ARM:LE:16:Cortex",
+            "memory_regions": []
+        }))
+        .unwrap();
+        assert!(invalid_config.parse_binary_base_address().is_err());
+        assert!(
+            RuntimeMemoryImage::new_from_bare_metal(hex_file.as_bytes(), &invalid_config).is_err()
+        );
+    }
+}
@@ -0,0 +1,247 @@
+//! Parsing of the vector table of ARM Cortex-M microcontrollers.
+//!
+//! The vector table is located at the start of the boot memory (usually the flash memory) of the chip.
+//! Its first entry is the initial value of the main stack pointer,
+//! the following entries are the addresses of the reset handler and of all other exception and interrupt handlers.
+
+use super::{BareMetalConfig, RuntimeMemoryImage};
+use crate::prelude::*;
+use std::collections::HashMap;
+
+/// The names of the system exception handlers at the indices 1 to 15 of the vector table.
+///
+/// Reserved entries are marked with `None`.
+const SYSTEM_HANDLER_NAMES: [Option<&str>; 15] = [
+    Some("Reset_Handler"),
+    Some("NMI_Handler"),
+    Some("HardFault_Handler"),
+    Some("MemManage_Handler"),
+    Some("BusFault_Handler"),
+    Some("UsageFault_Handler"),
+    Some("SecureFault_Handler"),
+    None,
+    None,
+    None,
+    Some("SVC_Handler"),
+    Some("DebugMon_Handler"),
+    None,
+    Some("PendSV_Handler"),
+    Some("SysTick_Handler"),
+];
+
+/// The maximal number of external interrupts supported by the Cortex-M architecture.
+const MAX_NUM_INTERRUPTS: u64 = 496;
+
+/// The content of the vector table of a Cortex-M microcontroller.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CortexMVectorTable {
+    /// The initial value of the main stack pointer.
+    pub initial_stack_pointer: u64,
+    /// The exception and interrupt handlers given as pairs of handler name and handler address.
+    ///
+    /// The handler addresses do not contain the Thumb bit.
+    /// Handlers that are used for more than one exception or interrupt are named `Default_Handler`.
+    pub handlers: Vec<(String, u64)>,
+}
+
+impl CortexMVectorTable {
+    /// Read the vector table of the given bare metal binary.
+    ///
+    /// The vector table is assumed to be located at the base address
+    /// of the first memory region backed by the input binary.
+    /// Returns an error if the processor is not a 32-bit little-endian ARM processor
+    /// or if no valid vector table was found at that address.
+    pub fn from_bare_metal_binary(
+        binary: &[u8],
+        bare_metal_config: &BareMetalConfig,
+    ) -> Result<CortexMVectorTable, Error> {
+        if !bare_metal_config.processor_id.starts_with("ARM:LE:32:") {
+            return Err(anyhow!("Processor is not a Cortex-M processor"));
+        }
+        let table_address = bare_metal_config
+            .get_primary_memory_region()?
+            .parse_base_address()?;
+        let memory_image = RuntimeMemoryImage::new_from_bare_metal(binary, bare_metal_config)?;
+        memory_image.read_cortex_m_vector_table(table_address)
+    }
+
+    /// Return the name of the handler at the given address, if it is contained in the vector table.
+    pub fn get_handler_name(&self, address: u64) -> Option<&str> {
+        self.handlers
+            .iter()
+            .find(|(_, handler_address)| *handler_address == address)
+            .map(|(name, _)| name.as_str())
+    }
+}
+
+impl RuntimeMemoryImage {
+    /// Read a Cortex-M vector table at the given address.
+    ///
+    /// The vector table is fetched by the hardware on reset,
+    /// so its content is read even if it is located in a writeable memory segment.
+    /// The table is considered valid if the initial stack pointer points into (or directly behind)
+    /// writeable memory and the reset handler is a Thumb address in executable memory.
+    /// The list of interrupt handlers ends at the first entry that is neither zero nor a valid handler address.
+    pub fn read_cortex_m_vector_table(&self, address: u64) -> Result<CortexMVectorTable, Error> {
+        let initial_stack_pointer = self
+            .read_unsigned_raw(address, 4)
+            .ok_or_else(|| anyhow!("Vector table address {:#x} is not mapped", address))?;
+        if initial_stack_pointer % 4 != 0
+            || !self.is_writeable_address(initial_stack_pointer.wrapping_sub(4))
+        {
+            return Err(anyhow!(
+                "No valid initial stack pointer at {:#x}: {:#x}",
+                address,
+                initial_stack_pointer
+            ));
+        }
+        let mut handler_entries: Vec<(String, u64)> = Vec::new();
+        for index in 1..(16 + MAX_NUM_INTERRUPTS) {
+            let entry = match self.read_unsigned_raw(address + 4 * index, 4) {
+                Some(entry) => entry,
+                None => break,
+            };
+            let handler_address = match self.get_thumb_handler_address(entry) {
+                Some(handler_address) => handler_address,
+                None if index == 1 => {
+                    return Err(anyhow!(
+                        "No valid reset handler in vector table at {:#x}",
+                        address
+                    ))
+                }
+                // Reserved or unused system exception entries.
+                None if index < 16 || entry == 0 => continue,
+                // We reached the end of the vector table.
+                None => break,
+            };
+            let name = match SYSTEM_HANDLER_NAMES.get(index as usize - 1) {
+                Some(Some(name)) => name.to_string(),
+                Some(None) => continue,
+                None => format!("IRQ{}_Handler", index - 16),
+            };
+            handler_entries.push((name, handler_address));
+        }
+        let mut num_uses: HashMap<u64, usize> = HashMap::new();
+        for (_, handler_address) in handler_entries.iter() {
+            *num_uses.entry(*handler_address).or_insert(0) += 1;
+        }
+        let mut handlers: Vec<(String, u64)> = Vec::new();
+        for (name, handler_address) in handler_entries {
+            if handlers
+                .iter()
+                .any(|(_, known_address)| *known_address == handler_address)
+            {
+                continue;
+            }
+            if num_uses[&handler_address] > 1 {
+                handlers.push(("Default_Handler".to_string(), handler_address));
+            } else {
+                handlers.push((name, handler_address));
+            }
+        }
+        Ok(CortexMVectorTable {
+            initial_stack_pointer,
+            handlers,
+        })
+    }
+
+    /// Return the handler address without the Thumb bit
+    /// if the given vector table entry is a Thumb address pointing to executable memory.
+    fn get_thumb_handler_address(&self, entry: u64) -> Option<u64> {
+        if entry & 1 == 0 {
+            return None;
+        }
+        let handler_address = entry - 1;
+        let is_executable = self.memory_segments.iter().any(|segment| {
+            segment.execute_flag
+                && handler_address >= segment.base_address
+                && handler_address - segment.base_address < segment.bytes.len() as u64
+        });
+        if is_executable {
+            Some(handler_address)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if the given address is contained in a writeable memory segment.
+    fn is_writeable_address(&self, address: u64) -> bool {
+        self.memory_segments.iter().any(|segment| {
+            segment.write_flag
+                && address >= segment.base_address
+                && address - segment.base_address < segment.bytes.len() as u64
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_config() -> BareMetalConfig {
+        serde_json::from_value(serde_json::json!({
+            "processor_id": "ARM:LE:32:Cortex",
+            "memory_regions": [
+                { "name": "flash", "base_address": "0x08000000", "permissions": "rx", "file_offset": "0x0" },
+                { "name": "sram", "base_address": "0x20000000", "size": "0x1000", "permissions": "rw" },
+            ]
+        }))
+        .unwrap()
+    }
+
+    fn mock_binary(entries: &[u32]) -> Vec<u8> {
+        let mut binary: Vec<u8> = entries
+            .iter()
+            .flat_map(|entry| entry.to_le_bytes())
+            .collect();
+        // Some code that does not look like a vector table entry.
+        binary.extend([0x70, 0x47, 0x00, 0xbf]);
+        binary.resize(0x200, 0);
+        binary
+    }
+
+    #[test]
+    fn vector_table_parsing() {
+        let mut entries = vec![0x20001000, 0x08000101, 0x08000111, 0x08000121];
+        entries.extend([0x08000131; 7]);
+        entries.extend([0x08000141, 0, 0, 0x08000131, 0x08000151]);
+        // External interrupts
+        entries.extend([0x08000161, 0, 0x08000131]);
+        let vector_table =
+            CortexMVectorTable::from_bare_metal_binary(&mock_binary(&entries), &mock_config())
+                .unwrap();
+        assert_eq!(vector_table.initial_stack_pointer, 0x20001000);
+        assert_eq!(
+            vector_table.handlers,
+            vec![
+                ("Reset_Handler".to_string(), 0x08000100),
+                ("NMI_Handler".to_string(), 0x08000110),
+                ("HardFault_Handler".to_string(), 0x08000120),
+                ("Default_Handler".to_string(), 0x08000130),
+                ("SVC_Handler".to_string(), 0x08000140),
+                ("SysTick_Handler".to_string(), 0x08000150),
+                ("IRQ0_Handler".to_string(), 0x08000160),
+            ]
+        );
+        assert_eq!(
+            vector_table.get_handler_name(0x08000160),
+            Some("IRQ0_Handler")
+        );
+        assert_eq!(vector_table.get_handler_name(0x08000161), None);
+    }
+
+    #[test]
+    fn invalid_vector_tables() {
+        // Stack pointer not pointing to writeable memory
+        let binary = mock_binary(&[0x08000100, 0x08000101]);
+        assert!(CortexMVectorTable::from_bare_metal_binary(&binary, &mock_config()).is_err());
+        // Reset handler not in Thumb mode
+        let binary = mock_binary(&[0x20001000, 0x08000100]);
+        assert!(CortexMVectorTable::from_bare_metal_binary(&binary, &mock_config()).is_err());
+        // Not an ARM processor
+        let mut config = mock_config();
+        config.processor_id = "x86:LE:32:default".to_string();
+        let binary = mock_binary(&[0x20001000, 0x08000101]);
+        assert!(CortexMVectorTable::from_bare_metal_binary(&binary, &config).is_err());
+    }
+}
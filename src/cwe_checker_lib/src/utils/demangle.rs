@@ -0,0 +1,130 @@
+//! Demangling of C++ and Rust symbol names.
+//!
+//! Names of functions and extern symbols in the intermediate representation are the raw linker names,
+//! so that they can be matched against the symbol lists of the configuration file.
+//! The functions in this module translate them into human-readable names for the output of the cwe_checker.
+//! Supported are the Itanium C++ ABI (used e.g. by GCC and Clang), the MSVC scheme
+//! and both the legacy and the v0 scheme of Rust.
+
+use crate::intermediate_representation::Project;
+use crate::utils::log::CweWarning;
+use std::collections::BTreeMap;
+
+/// Demangle the given symbol name.
+///
+/// Returns `None` if the name is not mangled with one of the supported schemes.
+/// The hashes contained in Rust symbol names are removed from the demangled name.
+pub fn demangle(name: &str) -> Option<String> {
+    if let Ok(demangled) = rustc_demangle::try_demangle(name) {
+        // Legacy Rust symbols are also valid Itanium symbols.
+        // They can be distinguished from C++ symbols by the hash at the end of the name.
+        let with_hash = demangled.to_string();
+        let without_hash = format!("{:#}", demangled);
+        if name.starts_with("_R") || with_hash != without_hash {
+            return Some(without_hash);
+        }
+    }
+    if name.starts_with("_Z") || name.starts_with("__Z") {
+        let symbol = cpp_demangle::Symbol::new(name).ok()?;
+        return symbol
+            .demangle(&cpp_demangle::DemangleOptions::default())
+            .ok();
+    }
+    if name.starts_with('?') {
+        return msvc_demangler::demangle(name, msvc_demangler::DemangleFlags::llvm()).ok();
+    }
+    None
+}
+
+/// Collect the demangled names of all mangled function and extern symbol names of the project.
+///
+/// The returned map maps raw names to demangled names.
+pub fn get_demangled_names(project: &Project) -> BTreeMap<String, String> {
+    let program = &project.program.term;
+    program
+        .subs
+        .iter()
+        .map(|sub| &sub.term.name)
+        .chain(program.extern_symbols.iter().map(|symbol| &symbol.name))
+        .filter_map(|name| Some((name.clone(), demangle(name)?)))
+        .collect()
+}
+
+/// Replace the mangled names in the description of the CWE warning with their demangled counterparts
+/// and add all mangled names referenced by the warning to its `demangled_names` field.
+///
+/// The `demangled_names` map has to map raw names to demangled names (see [`get_demangled_names`]).
+pub fn demangle_cwe_warning(warning: &mut CweWarning, demangled_names: &BTreeMap<String, String>) {
+    // Replace longer names first, so that names which are prefixes of other names do not break the replacement.
+    let mut names: Vec<(&String, &String)> = demangled_names.iter().collect();
+    names.sort_by_key(|(raw_name, _)| std::cmp::Reverse(raw_name.len()));
+    for (raw_name, demangled_name) in names {
+        let is_referenced = warning.symbols.contains(raw_name)
+            || warning
+                .other
+                .iter()
+                .any(|other| other.iter().any(|entry| entry == raw_name))
+            || warning.trace.iter().any(|step| {
+                step.function == *raw_name || step.description.contains(raw_name.as_str())
+            });
+        if warning.description.contains(raw_name.as_str()) {
+            warning.description = warning
+                .description
+                .replace(raw_name.as_str(), demangled_name);
+        } else if !is_referenced {
+            continue;
+        }
+        warning
+            .demangled_names
+            .insert(raw_name.clone(), demangled_name.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn demangling() {
+        assert_eq!(
+            demangle("_ZNSt6vectorIiSaIiEE9push_backERKi").as_deref(),
+            Some("std::vector<int, std::allocator<int> >::push_back(int const&)")
+        );
+        assert_eq!(
+            demangle("?foo@bar@@YAHH@Z").as_deref(),
+            Some("int __cdecl bar::foo(int)")
+        );
+        assert_eq!(
+            demangle("_ZN4core3fmt5write17h0123456789abcdefE").as_deref(),
+            Some("core::fmt::write")
+        );
+        assert_eq!(
+            demangle("_RNvCs1234_7mycrate3foo").as_deref(),
+            Some("mycrate::foo")
+        );
+        assert_eq!(demangle("malloc"), None);
+        assert_eq!(demangle("_Z"), None);
+    }
+
+    #[test]
+    fn warning_demangling() {
+        let demangled_names = BTreeMap::from([
+            ("_Z3fooi".to_string(), "foo(int)".to_string()),
+            ("_Z3foov".to_string(), "foo()".to_string()),
+            (
+                "_Znwm".to_string(),
+                "operator new(unsigned long)".to_string(),
+            ),
+        ]);
+        let mut warning = CweWarning::new("CWE000", "0.1", "Call to _Znwm in _Z3fooi")
+            .symbols(vec!["_Znwm".to_string(), "malloc".to_string()]);
+        demangle_cwe_warning(&mut warning, &demangled_names);
+        assert_eq!(
+            warning.description,
+            "Call to operator new(unsigned long) in foo(int)"
+        );
+        assert_eq!(warning.symbols, vec!["_Znwm", "malloc"]);
+        assert_eq!(warning.demangled_names.len(), 2);
+        assert_eq!(warning.demangled_names["_Z3fooi"], "foo(int)");
+    }
+}
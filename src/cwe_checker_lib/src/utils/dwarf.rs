@@ -0,0 +1,217 @@
+//! Mapping of addresses to source code locations using the DWARF debug information of ELF binaries.
+//!
+//! If a binary was compiled with debug information,
+//! the addresses of CWE warnings can be resolved to the source file, line and column
+//! together with the chain of functions that were inlined at the address.
+
+use crate::prelude::*;
+use crate::utils::log::CweWarning;
+use addr2line::gimli;
+use goblin::elf::section_header::{SHF_COMPRESSED, SHT_NOBITS};
+
+type DwarfReader<'a> = gimli::EndianSlice<'a, gimli::RunTimeEndian>;
+
+/// A location in the source code of the binary.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, PartialOrd, Ord, Default)]
+pub struct SourceFrame {
+    /// The name of the function containing the location, as given by the debug information.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+    /// The path to the source file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// The line number, starting at 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+    /// The column number, starting at 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<u32>,
+}
+
+/// The source code location corresponding to an address of a CWE warning.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, PartialOrd, Ord, Default)]
+pub struct SourceLocation {
+    /// The address of the CWE warning, as given in [`CweWarning::addresses`].
+    pub address: String,
+    /// The path to the source file of the code at the address.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// The line number of the code at the address.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+    /// The column number of the code at the address.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<u32>,
+    /// The chain of functions containing the address, starting with the innermost inlined function.
+    ///
+    /// The location of a frame is the location inside its function,
+    /// i.e. for all but the first frame it is the call site of the function inlined into it.
+    /// The last frame is the function that was not inlined.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inlined_frames: Vec<SourceFrame>,
+}
+
+/// Maps addresses of a binary to source code locations using its DWARF debug information.
+pub struct SourceMap<'a> {
+    context: addr2line::Context<DwarfReader<'a>>,
+    /// The offset that has to be subtracted from addresses in the project
+    /// to get the addresses used in the debug information.
+    address_base_offset: u64,
+}
+
+impl<'a> SourceMap<'a> {
+    /// Load the DWARF debug information of the given binary.
+    ///
+    /// The `address_base_offset` is the difference between the addresses in the project
+    /// and the addresses in the binary (see [`Program::address_base_offset`](crate::intermediate_representation::Program::address_base_offset)).
+    /// Returns `Ok(None)` if the binary is not an ELF file or does not contain (uncompressed) DWARF line information.
+    /// Relocatable object files are not supported, since their debug information is not relocated.
+    pub fn new(binary: &'a [u8], address_base_offset: u64) -> Result<Option<SourceMap<'a>>, Error> {
+        let elf = match goblin::elf::Elf::parse(binary) {
+            Ok(elf) if elf.header.e_type != goblin::elf::header::ET_REL => elf,
+            _ => return Ok(None),
+        };
+        let get_section_data = |name: &str| -> Option<&'a [u8]> {
+            let header = elf.section_headers.iter().find(|header| {
+                matches!(elf.shdr_strtab.get(header.sh_name), Some(Ok(section_name)) if section_name == name)
+            })?;
+            if header.sh_type == SHT_NOBITS || header.sh_flags & SHF_COMPRESSED as u64 != 0 {
+                return None;
+            }
+            binary.get(header.file_range())
+        };
+        if get_section_data(".debug_line").is_none() {
+            return Ok(None);
+        }
+        let endian = if elf.little_endian {
+            gimli::RunTimeEndian::Little
+        } else {
+            gimli::RunTimeEndian::Big
+        };
+        let dwarf = gimli::Dwarf::load(|section: gimli::SectionId| {
+            Ok::<_, gimli::Error>(gimli::EndianSlice::new(
+                get_section_data(section.name()).unwrap_or(&[]),
+                endian,
+            ))
+        })?;
+        let context = addr2line::Context::from_dwarf(dwarf)?;
+        Ok(Some(SourceMap {
+            context,
+            address_base_offset,
+        }))
+    }
+
+    /// Get the source location of the given address of the project.
+    ///
+    /// Returns `None` if the address is not covered by the debug information.
+    pub fn get_source_location(&self, address: u64) -> Option<SourceLocation> {
+        let probe = address.wrapping_sub(self.address_base_offset);
+        let mut frame_iter = self.context.find_frames(probe).skip_all_loads().ok()?;
+        let mut inlined_frames = Vec::new();
+        while let Ok(Some(frame)) = frame_iter.next() {
+            let function = frame
+                .function
+                .as_ref()
+                .and_then(|name| name.raw_name().ok())
+                .map(|name| name.to_string());
+            let (file, line, column) = match frame.location {
+                Some(location) => (
+                    location.file.map(|file| file.to_string()),
+                    location.line,
+                    location.column,
+                ),
+                None => (None, None, None),
+            };
+            inlined_frames.push(SourceFrame {
+                function,
+                file,
+                line,
+                column,
+            });
+        }
+        let innermost_frame = inlined_frames
+            .iter()
+            .find(|frame| frame.file.is_some() || frame.line.is_some())?;
+        Some(SourceLocation {
+            address: format!("{:x}", address),
+            file: innermost_frame.file.clone(),
+            line: innermost_frame.line,
+            column: innermost_frame.column,
+            inlined_frames,
+        })
+    }
+
+    /// Set the source locations of the given CWE warning for all of its addresses
+    /// that are covered by the debug information.
+    pub fn add_source_locations(&self, warning: &mut CweWarning) {
+        warning.source_locations = warning
+            .addresses
+            .iter()
+            .filter_map(|address| {
+                let address_value = u64::from_str_radix(address, 16).ok()?;
+                let mut location = self.get_source_location(address_value)?;
+                location.address = address.clone();
+                Some(location)
+            })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binaries_without_debug_info() {
+        assert!(SourceMap::new(&[0u8; 64], 0).unwrap().is_none());
+        // A minimal 64-bit little-endian ELF executable without section headers.
+        let mut elf_header = vec![0x7f, b'E', b'L', b'F', 2, 1, 1, 0];
+        elf_header.resize(16, 0);
+        elf_header.extend([2, 0, 0x3e, 0, 1, 0, 0, 0]);
+        elf_header.resize(0x34, 0);
+        elf_header.extend([0x40, 0, 0x38, 0, 0, 0, 0x40, 0, 0, 0, 0, 0]);
+        assert!(SourceMap::new(&elf_header, 0).unwrap().is_none());
+    }
+
+    #[test]
+    fn inlined_function_locations() {
+        // See the source file of the sample for the build command.
+        let binary = include_bytes!("../../../../test/dwarf_samples/inlined_function");
+        let source_map = SourceMap::new(binary, 0x1000).unwrap().unwrap();
+
+        // The multiplication in `square` is inlined into `compute`.
+        let location = source_map.get_source_location(0x402004).unwrap();
+        assert_eq!(location.address, "402004");
+        assert!(location.file.unwrap().ends_with("inlined_function.c"));
+        assert_eq!(location.line, Some(9));
+        assert_eq!(location.inlined_frames.len(), 2);
+        assert_eq!(
+            location.inlined_frames[0].function.as_deref(),
+            Some("square")
+        );
+        assert_eq!(location.inlined_frames[0].line, Some(9));
+        assert_eq!(
+            location.inlined_frames[1].function.as_deref(),
+            Some("compute")
+        );
+        assert_eq!(location.inlined_frames[1].line, Some(14));
+
+        // The addition is not part of the inlined function.
+        let location = source_map.get_source_location(0x402007).unwrap();
+        assert_eq!(location.line, Some(14));
+        assert_eq!(location.inlined_frames.len(), 1);
+        assert_eq!(
+            location.inlined_frames[0].function.as_deref(),
+            Some("compute")
+        );
+
+        assert!(source_map.get_source_location(0x1000).is_none());
+
+        let mut warning = CweWarning::new("CWE000", "0.1", "description")
+            .addresses(vec!["402007".to_string(), "1000".to_string()]);
+        source_map.add_source_locations(&mut warning);
+        assert_eq!(warning.source_locations.len(), 1);
+        assert_eq!(warning.source_locations[0].address, "402007");
+        assert_eq!(warning.source_locations[0].line, Some(14));
+    }
+}
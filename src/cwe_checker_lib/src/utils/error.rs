@@ -0,0 +1,170 @@
+//! Structured errors describing why a run of the cwe_checker failed.
+//!
+//! Most functions of the cwe_checker return [`anyhow::Error`] errors.
+//! Errors on the paths that load the inputs of an analysis are tagged with a [`CweCheckerError`],
+//! so that callers can tell apart e.g. a misconfigured tool from an unsupported binary.
+//! Use [`ErrorKind::of`] to get the category of an arbitrary error.
+//!
+//! The command line interface exits with the following exit codes:
+//!
+//! | Exit code | Error kind                         |
+//! |-----------|------------------------------------|
+//! | 0         | No error                           |
+//! | 1         | Invalid command line arguments     |
+//! | 2         | [`ErrorKind::Configuration`]       |
+//! | 3         | [`ErrorKind::UnsupportedBinary`]   |
+//! | 4         | [`ErrorKind::Frontend`]            |
+//! | 5         | [`ErrorKind::Analysis`]            |
+//! | 6         | [`ErrorKind::Io`]                  |
+
+use crate::prelude::*;
+
+/// The category of an error.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum ErrorKind {
+    /// The cwe_checker is misconfigured,
+    /// e.g. a configuration file is missing or invalid or an unknown check was selected.
+    Configuration,
+    /// The input binary could not be read or its file format is not supported.
+    UnsupportedBinary,
+    /// The frontend failed to generate the intermediate representation of the binary,
+    /// e.g. because Ghidra crashed or its output could not be parsed.
+    Frontend,
+    /// The analysis of the binary failed or crashed.
+    Analysis,
+    /// Reading or writing auxiliary files failed, e.g. writing to the output file.
+    Io,
+}
+
+impl ErrorKind {
+    /// The exit code of the command line interface for errors of this kind.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ErrorKind::Configuration => 2,
+            ErrorKind::UnsupportedBinary => 3,
+            ErrorKind::Frontend => 4,
+            ErrorKind::Analysis => 5,
+            ErrorKind::Io => 6,
+        }
+    }
+
+    /// Get the kind of the given error.
+    ///
+    /// If the error or one of its causes is a [`CweCheckerError`], its kind is returned.
+    /// Untagged errors are treated as analysis errors.
+    pub fn of(error: &Error) -> ErrorKind {
+        error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<CweCheckerError>())
+            .map(|error| error.kind)
+            .unwrap_or(ErrorKind::Analysis)
+    }
+}
+
+/// An error message together with the category of the error.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct CweCheckerError {
+    /// The category of the error.
+    pub kind: ErrorKind,
+    /// The human-readable error message.
+    pub message: String,
+}
+
+impl CweCheckerError {
+    /// Create a new error of the given kind.
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> CweCheckerError {
+        CweCheckerError {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    /// Convert an arbitrary error into a `CweCheckerError`.
+    ///
+    /// The kind is determined by [`ErrorKind::of`] and the message contains all causes of the error.
+    pub fn from_error(error: &Error) -> CweCheckerError {
+        CweCheckerError {
+            kind: ErrorKind::of(error),
+            message: format!("{:#}", error),
+        }
+    }
+
+    /// Generate the JSON object printed by the command line interface if the `--json` flag is set.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "error": {
+                "kind": self.kind,
+                "message": self.message,
+                "exit_code": self.kind.exit_code(),
+            }
+        })
+    }
+}
+
+impl std::fmt::Display for CweCheckerError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "{}", self.message)
+    }
+}
+
+impl std::error::Error for CweCheckerError {}
+
+/// Extension trait for tagging the errors of a `Result` with an [`ErrorKind`].
+pub trait WithErrorKind<T> {
+    /// Tag the error with the given kind.
+    ///
+    /// Errors that are already tagged keep their original kind,
+    /// since the innermost tag describes the root cause of the error best.
+    fn error_kind(self, kind: ErrorKind) -> Result<T, Error>;
+}
+
+impl<T, E: Into<Error>> WithErrorKind<T> for Result<T, E> {
+    fn error_kind(self, kind: ErrorKind) -> Result<T, Error> {
+        self.map_err(|error| {
+            let error: Error = error.into();
+            if error
+                .chain()
+                .any(|cause| cause.downcast_ref::<CweCheckerError>().is_some())
+            {
+                error
+            } else {
+                Error::new(CweCheckerError::new(kind, format!("{:#}", error)))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_kinds() {
+        assert_eq!(
+            ErrorKind::of(&anyhow!("Something failed")),
+            ErrorKind::Analysis
+        );
+
+        let tagged: Result<(), Error> =
+            Err(anyhow!("File not found")).error_kind(ErrorKind::Configuration);
+        let error = tagged.unwrap_err();
+        assert_eq!(ErrorKind::of(&error), ErrorKind::Configuration);
+        assert_eq!(format!("{}", error), "File not found");
+
+        // Context does not hide the kind and retagging does not change it.
+        let error = Err::<(), _>(error.context("Loading failed"))
+            .error_kind(ErrorKind::Frontend)
+            .unwrap_err();
+        assert_eq!(ErrorKind::of(&error), ErrorKind::Configuration);
+        let cwe_checker_error = CweCheckerError::from_error(&error);
+        assert_eq!(cwe_checker_error.message, "Loading failed: File not found");
+        assert_eq!(
+            cwe_checker_error.to_json()["error"]["exit_code"],
+            serde_json::json!(2)
+        );
+        assert_eq!(
+            cwe_checker_error.to_json()["error"]["kind"],
+            serde_json::json!("Configuration")
+        );
+    }
+}
@@ -0,0 +1,444 @@
+//! Stable fingerprints of CWE warnings and the comparison of CWE warnings with a baseline.
+//!
+//! Absolute addresses change whenever a binary is rebuilt,
+//! so they cannot be used to recognize a warning in a later version of the binary.
+//! Instead, the fingerprint of a warning is computed from
+//! - the name of the check that generated the warning,
+//! - the name of the function containing the warning,
+//! - the symbols (usually the called extern functions) associated to the warning
+//! - and the position of the warning inside the function.
+//!
+//! Names generated by Ghidra for functions without symbol (like `FUN_00101000`) contain the address of the function.
+//! For such functions the shapes of all instructions of the function are used instead of the name.
+//!
+//! The position of a warning is the index of its call site among all calls to one of its symbols in the function.
+//! If the warning is not located at such a call,
+//! its position is the index of its address among the addresses of all instructions of the function
+//! with the same shape, i.e. with the same kinds of terms, assigned registers and call targets.
+
+use super::log::{BaselineState, CweWarning};
+use crate::intermediate_representation::*;
+use crate::prelude::*;
+use crate::utils::error::{ErrorKind, WithErrorKind};
+use lazy_static::lazy_static;
+use regex::Regex;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Compute the fingerprints of the given CWE warnings
+/// and set the [`CweWarning::fingerprint`] fields accordingly.
+///
+/// The function containing a warning is determined by the term IDs of the warning if possible
+/// and by its first address otherwise.
+pub fn add_fingerprints(warnings: &mut [CweWarning], project: &Project) {
+    let mut sub_of_tid: HashMap<String, &Term<Sub>> = HashMap::new();
+    let mut sub_of_address: HashMap<&str, &Term<Sub>> = HashMap::new();
+    let mut function_names: HashMap<&Tid, &str> = HashMap::new();
+    for sub in project.program.term.subs.iter() {
+        for (tid, address) in get_term_tids(sub) {
+            sub_of_tid.entry(tid.to_string()).or_insert(sub);
+            sub_of_address.entry(address).or_insert(sub);
+        }
+        function_names.insert(&sub.tid, get_normalized_name(&sub.term.name));
+    }
+    for symbol in project.program.term.extern_symbols.iter() {
+        function_names.insert(&symbol.tid, &symbol.name);
+    }
+    for warning in warnings.iter_mut() {
+        let sub = warning
+            .tids
+            .iter()
+            .find_map(|tid| sub_of_tid.get(tid))
+            .or_else(|| {
+                warning
+                    .addresses
+                    .first()
+                    .and_then(|address| sub_of_address.get(address.as_str()))
+            })
+            .copied();
+        warning.fingerprint = Some(compute_fingerprint(warning, sub, project, &function_names));
+    }
+}
+
+/// Compare the given CWE warnings with the CWE warnings of a baseline, e.g. of an older version of the binary.
+///
+/// Warnings are identified by their fingerprints.
+/// Returns the given warnings marked as [new](BaselineState::New) or [unchanged](BaselineState::Unchanged),
+/// followed by the warnings of the baseline without counterpart marked as [fixed](BaselineState::Fixed).
+/// Returns a [`Configuration`](ErrorKind::Configuration) error
+/// if a warning of the baseline has no fingerprint.
+pub fn compare_with_baseline(
+    warnings: Vec<CweWarning>,
+    baseline: Vec<CweWarning>,
+) -> Result<Vec<CweWarning>, Error> {
+    let mut baseline_warnings: BTreeMap<String, Vec<CweWarning>> = BTreeMap::new();
+    for warning in baseline {
+        let fingerprint = warning
+            .fingerprint
+            .clone()
+            .ok_or_else(|| {
+                anyhow!(
+                    "The baseline warning \"{}\" has no fingerprint. The baseline has to be generated with the --json flag.",
+                    warning
+                )
+            })
+            .error_kind(ErrorKind::Configuration)?;
+        baseline_warnings
+            .entry(fingerprint)
+            .or_default()
+            .push(warning);
+    }
+    let mut compared_warnings: Vec<CweWarning> = warnings
+        .into_iter()
+        .map(|mut warning| {
+            let is_unchanged = warning
+                .fingerprint
+                .as_ref()
+                .and_then(|fingerprint| baseline_warnings.get_mut(fingerprint))
+                .and_then(|matching_warnings| matching_warnings.pop())
+                .is_some();
+            warning.baseline_state = Some(if is_unchanged {
+                BaselineState::Unchanged
+            } else {
+                BaselineState::New
+            });
+            warning
+        })
+        .collect();
+    for mut warning in baseline_warnings.into_values().flatten() {
+        warning.baseline_state = Some(BaselineState::Fixed);
+        compared_warnings.push(warning);
+    }
+    Ok(compared_warnings)
+}
+
+/// Compute the fingerprint of a warning contained in the given function.
+///
+/// The `function_names` map the TIDs of all functions and extern symbols to their [normalized](get_normalized_name) names.
+fn compute_fingerprint(
+    warning: &CweWarning,
+    sub: Option<&Term<Sub>>,
+    project: &Project,
+    function_names: &HashMap<&Tid, &str>,
+) -> String {
+    let position = match (sub, warning.addresses.first()) {
+        (Some(sub), Some(address)) => {
+            get_position(sub, address, &warning.symbols, project, function_names)
+        }
+        _ => "none".to_string(),
+    };
+    let function_identity = match sub {
+        Some(sub) if is_generated_name(&sub.term.name) => {
+            let shapes = get_instruction_shapes(sub, function_names);
+            let shapes: Vec<&str> = shapes.values().map(|shape| shape.as_str()).collect();
+            format!("shapes:{}", shapes.join("|"))
+        }
+        Some(sub) => sub.term.name.clone(),
+        None => String::new(),
+    };
+    let mut hasher = Sha256::new();
+    for component in [
+        warning.name.as_str(),
+        &function_identity,
+        &warning.symbols.join(","),
+        &position,
+    ] {
+        hasher.update(component.as_bytes());
+        hasher.update([0u8]);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Get the position of the given address inside the function.
+///
+/// If the address is a call site of one of the given symbols,
+/// the position is the index of the call among all calls to these symbols in the function.
+/// Otherwise it is the index of the address among the addresses of all instructions of the function
+/// with the same shape as the instruction at the address.
+fn get_position(
+    sub: &Term<Sub>,
+    address: &str,
+    symbols: &[String],
+    project: &Project,
+    function_names: &HashMap<&Tid, &str>,
+) -> String {
+    let symbol_tids: BTreeSet<&Tid> = project
+        .program
+        .term
+        .extern_symbols
+        .iter()
+        .filter(|symbol| symbols.contains(&symbol.name))
+        .map(|symbol| &symbol.tid)
+        .collect();
+    let call_addresses: BTreeSet<&str> = sub
+        .term
+        .blocks
+        .iter()
+        .flat_map(|blk| blk.term.jmps.iter())
+        .filter_map(|jmp| match &jmp.term {
+            Jmp::Call { target, .. } if symbol_tids.contains(target) => {
+                Some(jmp.tid.address.as_str())
+            }
+            _ => None,
+        })
+        .collect();
+    if let Some(index) = call_addresses.iter().position(|call| *call == address) {
+        return format!("call:{}", index);
+    }
+    let shapes = get_instruction_shapes(sub, function_names);
+    match shapes.get(address) {
+        Some(shape) => {
+            let index = shapes
+                .range(..address)
+                .filter(|(_, other_shape)| *other_shape == shape)
+                .count();
+            format!("instruction:{}:{}", shape, index)
+        }
+        None => "none".to_string(),
+    }
+}
+
+/// Returns `true` if the given function name was generated by Ghidra from the address of the function,
+/// like `FUN_00101000`, `thunk_FUN_00101000` or `LAB_00101000`.
+fn is_generated_name(name: &str) -> bool {
+    lazy_static! {
+        static ref GENERATED_NAME_REGEX: Regex =
+            Regex::new(r"^(thunk_)?(FUN|LAB|SUB)_[0-9a-fA-F]+$").unwrap();
+    }
+    GENERATED_NAME_REGEX.is_match(name)
+}
+
+/// Get the name of a function with names [generated from addresses](is_generated_name) replaced by `FUN`.
+fn get_normalized_name(name: &str) -> &str {
+    if is_generated_name(name) {
+        "FUN"
+    } else {
+        name
+    }
+}
+
+/// Get the shapes of all instructions of the function, keyed by the address of the instruction.
+///
+/// The shape of an instruction lists the kinds of its terms together with the assigned registers
+/// and the [normalized](get_normalized_name) names of the called functions.
+/// It does not depend on the absolute addresses of the instruction or of other parts of the binary.
+fn get_instruction_shapes<'a>(
+    sub: &'a Term<Sub>,
+    function_names: &HashMap<&Tid, &str>,
+) -> BTreeMap<&'a str, String> {
+    let get_name = |tid: &Tid| function_names.get(tid).copied().unwrap_or("unknown");
+    let mut shapes: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for blk in sub.term.blocks.iter() {
+        for def in blk.term.defs.iter() {
+            let shape = match &def.term {
+                Def::Load { var, .. } => format!("load {}", var.name),
+                Def::Store { .. } => "store".to_string(),
+                Def::Assign { var, .. } => format!("assign {}", var.name),
+            };
+            shapes
+                .entry(def.tid.address.as_str())
+                .or_default()
+                .push(shape);
+        }
+        for jmp in blk.term.jmps.iter() {
+            let shape = match &jmp.term {
+                Jmp::Branch(_) => "branch".to_string(),
+                Jmp::BranchInd(_) => "branch_ind".to_string(),
+                Jmp::CBranch { .. } => "cbranch".to_string(),
+                Jmp::Call { target, .. } => format!("call {}", get_name(target)),
+                Jmp::CallInd { .. } => "call_ind".to_string(),
+                Jmp::Return(_) => "return".to_string(),
+                Jmp::CallOther { description, .. } => format!("call_other {}", description),
+            };
+            shapes
+                .entry(jmp.tid.address.as_str())
+                .or_default()
+                .push(shape);
+        }
+    }
+    shapes
+        .into_iter()
+        .filter(|(address, _)| *address != "UNKNOWN")
+        .map(|(address, shape)| (address, shape.join(",")))
+        .collect()
+}
+
+/// Get the TIDs and addresses of all blocks, `Def` and `Jmp` terms of the function.
+///
+/// Terms with unknown address are skipped.
+fn get_term_tids(sub: &Term<Sub>) -> Vec<(&Tid, &str)> {
+    let mut tids = Vec::new();
+    for blk in sub.term.blocks.iter() {
+        tids.push(&blk.tid);
+        tids.extend(blk.term.defs.iter().map(|def| &def.tid));
+        tids.extend(blk.term.jmps.iter().map(|jmp| &jmp.tid));
+    }
+    tids.into_iter()
+        .filter(|tid| tid.address != "UNKNOWN")
+        .map(|tid| (tid, tid.address.as_str()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_tid(id: String, address: String) -> Tid {
+        let mut tid = Tid::new(id);
+        tid.address = address;
+        tid
+    }
+
+    fn mock_project(instruction_offset: u64) -> Project {
+        let address = |offset: u64| format!("{:08x}", 0x1000 + instruction_offset + offset);
+        let call = |offset: u64, target: &str| Term {
+            tid: mock_tid(format!("instr_{}_call", address(offset)), address(offset)),
+            term: Jmp::Call {
+                target: Tid::new(target),
+                return_: None,
+            },
+        };
+        let blk = |offset: u64, target: &str| Term {
+            tid: Tid::blk_id_at_address(&address(offset)),
+            term: Blk {
+                defs: vec![Term {
+                    tid: mock_tid(format!("instr_{}_0", address(offset)), address(offset)),
+                    term: Def::assign("", Variable::mock("RAX", 8), Expression::const_from_i64(0))
+                        .term,
+                }],
+                jmps: vec![call(offset + 4, target)],
+                indirect_jmp_targets: Vec::new(),
+            },
+        };
+        let mut sub = Sub::mock("main");
+        sub.term.blocks = vec![blk(0, "strcpy"), blk(8, "printf"), blk(16, "strcpy")];
+        let mut strcpy = ExternSymbol::mock();
+        strcpy.tid = Tid::new("strcpy");
+        strcpy.name = "strcpy".to_string();
+        let mut project = Project::mock_empty();
+        project.program.term.subs = vec![sub];
+        project.program.term.extern_symbols = vec![strcpy];
+        project
+    }
+
+    #[test]
+    fn fingerprints_are_stable() {
+        let warning = |address: u64| {
+            CweWarning::new("CWE676", "0.1", "Call to strcpy")
+                .addresses(vec![format!("{:08x}", address)])
+                .symbols(vec!["strcpy".to_string()])
+        };
+        let mut warnings = vec![warning(0x1004), warning(0x1014), warning(0x1008)];
+        add_fingerprints(&mut warnings, &mock_project(0));
+        // A rebuild shifts all addresses
+        let mut shifted_warnings = vec![warning(0x1104), warning(0x1114), warning(0x1108)];
+        add_fingerprints(&mut shifted_warnings, &mock_project(0x100));
+
+        for (warning, shifted_warning) in warnings.iter().zip(shifted_warnings.iter()) {
+            assert!(warning.fingerprint.is_some());
+            assert_eq!(warning.fingerprint, shifted_warning.fingerprint);
+        }
+        assert_ne!(warnings[0].fingerprint, warnings[1].fingerprint);
+        assert_ne!(warnings[0].fingerprint, warnings[2].fingerprint);
+    }
+
+    #[test]
+    fn fingerprints_of_unnamed_functions() {
+        let warning = |address: u64| {
+            CweWarning::new("CWE476", "0.1", "Unchecked return value")
+                .addresses(vec![format!("{:08x}", address)])
+                .symbols(vec!["malloc".to_string()])
+        };
+        let mock_unnamed_project = |base_address: u64| {
+            let mut project = mock_project(base_address);
+            project.program.term.subs[0].term.name = format!("FUN_{:08x}", 0x1000 + base_address);
+            project
+        };
+        let mut warnings = vec![warning(0x1000), warning(0x1008), warning(0x1014)];
+        add_fingerprints(&mut warnings, &mock_unnamed_project(0));
+        // Only the base addresses and thus the generated function names differ.
+        let mut shifted_warnings = vec![warning(0x11000), warning(0x11008), warning(0x11014)];
+        add_fingerprints(&mut shifted_warnings, &mock_unnamed_project(0x10000));
+
+        for (warning, shifted_warning) in warnings.iter().zip(shifted_warnings.iter()) {
+            assert!(warning.fingerprint.is_some());
+            assert_eq!(warning.fingerprint, shifted_warning.fingerprint);
+        }
+        assert_ne!(warnings[0].fingerprint, warnings[1].fingerprint);
+        assert_ne!(warnings[1].fingerprint, warnings[2].fingerprint);
+
+        // The fingerprint of a named function does not depend on its instructions.
+        let mut named_warnings = vec![warning(0x1008)];
+        add_fingerprints(&mut named_warnings, &mock_project(0));
+        assert_ne!(named_warnings[0].fingerprint, warnings[1].fingerprint);
+    }
+
+    #[test]
+    fn instruction_positions_are_stable() {
+        let warning = CweWarning::new("CWE476", "0.1", "Unchecked return value")
+            .addresses(vec!["00001008".to_string()])
+            .symbols(vec!["malloc".to_string()]);
+        let mut warnings = vec![warning.clone()];
+        add_fingerprints(&mut warnings, &mock_project(0));
+
+        // Add an instruction in front of the instruction of the warning.
+        let mut project = mock_project(0);
+        let blk = &mut project.program.term.subs[0].term.blocks[0];
+        blk.term.defs.insert(
+            0,
+            Term {
+                tid: mock_tid("instr_00000ffc_0".to_string(), "00000ffc".to_string()),
+                term: Def::assign("", Variable::mock("RBX", 8), Expression::const_from_i64(0)).term,
+            },
+        );
+        let mut changed_warnings = vec![warning.clone()];
+        add_fingerprints(&mut changed_warnings, &project);
+        assert_eq!(warnings[0].fingerprint, changed_warnings[0].fingerprint);
+
+        // An added instruction of the same shape changes the position.
+        project.program.term.subs[0].term.blocks[0].term.defs[0] = Term {
+            tid: mock_tid("instr_00000ffc_0".to_string(), "00000ffc".to_string()),
+            term: Def::assign("", Variable::mock("RAX", 8), Expression::const_from_i64(0)).term,
+        };
+        let mut changed_warnings = vec![warning];
+        add_fingerprints(&mut changed_warnings, &project);
+        assert_ne!(warnings[0].fingerprint, changed_warnings[0].fingerprint);
+    }
+
+    #[test]
+    fn baseline_comparison() {
+        let warning = |name: &str, fingerprint: &str| {
+            let mut warning = CweWarning::new(name, "0.1", name);
+            warning.fingerprint = Some(fingerprint.to_string());
+            warning
+        };
+        let baseline = vec![
+            warning("old", "a"),
+            warning("fixed", "b"),
+            warning("old", "a"),
+        ];
+        let warnings = vec![
+            warning("new", "c"),
+            warning("old", "a"),
+            warning("old", "a"),
+        ];
+        let compared = compare_with_baseline(warnings, baseline.clone()).unwrap();
+        let states: Vec<_> = compared
+            .iter()
+            .map(|warning| (warning.name.as_str(), warning.baseline_state.unwrap()))
+            .collect();
+        assert_eq!(
+            states,
+            vec![
+                ("new", BaselineState::New),
+                ("old", BaselineState::Unchanged),
+                ("old", BaselineState::Unchanged),
+                ("fixed", BaselineState::Fixed),
+            ]
+        );
+
+        let mut baseline = baseline;
+        baseline[1].fingerprint = None;
+        assert!(compare_with_baseline(Vec::new(), baseline).is_err());
+    }
+}
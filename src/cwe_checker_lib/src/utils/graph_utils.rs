@@ -1,11 +1,12 @@
 //! Helper functions for common tasks utilizing the control flow graph of the binary.
 
 use crate::analysis::graph::*;
-use crate::intermediate_representation::Jmp;
+use crate::intermediate_representation::{Jmp, Project, Sub};
 use crate::prelude::*;
-use petgraph::graph::NodeIndex;
+use crate::utils::log::{CweWarning, TraceStep, TraceStepKind};
+use petgraph::graph::{EdgeIndex, NodeIndex};
 use petgraph::visit::EdgeRef;
-use std::collections::HashSet;
+use std::collections::{hash_map::Entry, HashMap, HashSet, VecDeque};
 
 /// Check whether a call to the `sink_symbol` is reachable from the given `source_node`
 /// through a path of intraprocedural edges in the control flow graph.
@@ -59,3 +60,342 @@ pub fn is_sink_call_reachable_from_source_call(
     }
     None
 }
+
+/// Fill in the witness traces of the given CWE warnings.
+///
+/// The checks only set the source and the sink of a witness trace,
+/// see [`CweWarning::trace_source_and_sink`].
+/// For each such trace the shortest path from the source to the sink in the control flow graph is searched
+/// and the blocks, calls and returns along the path are inserted into the trace.
+/// If no path is found, the trace only contains the source and the sink.
+///
+/// Calls to functions inside the binary can be skipped on a path through the call stub edges of the graph.
+/// Thus a shortest path never returns from a function that it entered before
+/// and returns are only followed before the first call on the path.
+/// Since the function containing the source may have several callers,
+/// such a return may lead to any of them.
+pub fn add_witness_traces(warnings: &mut [CweWarning], graph: &Graph, project: &Project) {
+    let mut term_nodes: HashMap<String, Vec<NodeIndex>> = HashMap::new();
+    for node in graph.node_indices() {
+        let tids: Vec<&Tid> = match graph[node] {
+            Node::BlkStart(blk, sub) => {
+                let mut tids = vec![&blk.tid];
+                tids.extend(blk.term.defs.iter().map(|def| &def.tid));
+                if sub.term.blocks.first().map(|first_blk| &first_blk.tid) == Some(&blk.tid) {
+                    tids.push(&sub.tid);
+                }
+                tids
+            }
+            Node::BlkEnd(blk, _) => blk.term.jmps.iter().map(|jmp| &jmp.tid).collect(),
+            Node::CallReturn { .. } | Node::CallSource { .. } => Vec::new(),
+        };
+        for tid in tids {
+            term_nodes.entry(tid.to_string()).or_default().push(node);
+        }
+    }
+    let mut function_names: HashMap<&Tid, &str> = HashMap::new();
+    for sub in project.program.term.subs.iter() {
+        function_names.insert(&sub.tid, &sub.term.name);
+    }
+    for symbol in project.program.term.extern_symbols.iter() {
+        function_names.insert(&symbol.tid, &symbol.name);
+    }
+
+    for warning in warnings.iter_mut() {
+        if let [source, sink] = &warning.trace[..] {
+            if source.kind != TraceStepKind::Source || sink.kind != TraceStepKind::Sink {
+                continue;
+            }
+            let source_nodes = term_nodes
+                .get(&source.tid)
+                .map(Vec::as_slice)
+                .unwrap_or(&[]);
+            let sink_nodes = term_nodes.get(&sink.tid).map(Vec::as_slice).unwrap_or(&[]);
+            let mut source = source.clone();
+            let mut sink = sink.clone();
+            let path = find_shortest_path(graph, source_nodes, sink_nodes);
+            let (first_node, last_node) = match &path {
+                Some((first_node, path)) => (
+                    Some(*first_node),
+                    path.last()
+                        .map(|edge| graph.edge_endpoints(*edge).unwrap().1)
+                        .or(Some(*first_node)),
+                ),
+                None => (source_nodes.first().copied(), sink_nodes.first().copied()),
+            };
+            if let Some(node) = first_node {
+                source.function = graph[node].get_sub().term.name.clone();
+            }
+            if let Some(node) = last_node {
+                sink.function = graph[node].get_sub().term.name.clone();
+            }
+            source.description = "Source of the value".to_string();
+            sink.description = "Location of the warning".to_string();
+            let mut trace = vec![source];
+            if let Some((_, path)) = path {
+                trace.append(&mut get_trace_steps(
+                    graph,
+                    &path,
+                    [&trace[0].tid, &sink.tid],
+                    &function_names,
+                ));
+            }
+            trace.push(sink);
+            warning.trace = trace;
+        }
+    }
+}
+
+/// A node together with a flag indicating whether a call was already entered on the path to the node.
+type SearchState = (NodeIndex, bool);
+
+/// Search for a shortest path from one of the source nodes to one of the sink nodes
+/// that does not return from a function after entering a function.
+///
+/// Returns the start node and the edges of the path.
+fn find_shortest_path(
+    graph: &Graph,
+    source_nodes: &[NodeIndex],
+    sink_nodes: &[NodeIndex],
+) -> Option<(NodeIndex, Vec<EdgeIndex>)> {
+    let mut predecessors: HashMap<SearchState, Option<(SearchState, EdgeIndex)>> = HashMap::new();
+    let mut worklist = VecDeque::new();
+    for node in source_nodes {
+        predecessors.insert((*node, false), None);
+        worklist.push_back((*node, false));
+    }
+    while let Some((node, entered_call)) = worklist.pop_front() {
+        if sink_nodes.contains(&node) {
+            let mut path = Vec::new();
+            let mut state = (node, entered_call);
+            while let Some((predecessor, edge)) = predecessors[&state] {
+                path.push(edge);
+                state = predecessor;
+            }
+            path.reverse();
+            return Some((state.0, path));
+        }
+        for edge in graph.edges(node) {
+            let next_state = match edge.weight() {
+                Edge::Call(_) => (edge.target(), true),
+                Edge::CrReturnStub if entered_call => continue,
+                _ => (edge.target(), entered_call),
+            };
+            if let Entry::Vacant(entry) = predecessors.entry(next_state) {
+                entry.insert(Some(((node, entered_call), edge.id())));
+                worklist.push_back(next_state);
+            }
+        }
+    }
+    None
+}
+
+/// Convert the edges of a path into the steps of a witness trace.
+///
+/// Calls to the source or the sink of the trace are skipped,
+/// since they are already contained in the trace as the source or sink step.
+fn get_trace_steps(
+    graph: &Graph,
+    path: &[EdgeIndex],
+    source_and_sink: [&str; 2],
+    function_names: &HashMap<&Tid, &str>,
+) -> Vec<TraceStep> {
+    let call_step = |jmp: &Term<Jmp>, caller: &Term<Sub>| {
+        let callee = match &jmp.term {
+            Jmp::Call { target, .. } => function_names
+                .get(target)
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| target.to_string()),
+            _ => "unknown function".to_string(),
+        };
+        let mut step = TraceStep::new(TraceStepKind::Call, &jmp.tid);
+        step.function = caller.term.name.clone();
+        step.description = format!("Call to {}", callee);
+        step
+    };
+    let mut steps = Vec::new();
+    let mut previous_edge = None;
+    for edge in path {
+        let (start, end) = graph.edge_endpoints(*edge).unwrap();
+        match (graph[*edge], graph[start]) {
+            (
+                Edge::Call(jmp),
+                Node::CallSource {
+                    source: (_, caller),
+                    ..
+                },
+            )
+            | (Edge::ExternCallStub(jmp), Node::BlkEnd(_, caller))
+            | (
+                Edge::ReturnCombine(jmp),
+                Node::CallReturn {
+                    call: (_, caller), ..
+                },
+            ) => {
+                let is_skipped_call = !matches!(graph[*edge], Edge::ReturnCombine(_))
+                    || previous_edge == Some(Edge::CrCallStub);
+                if is_skipped_call && !source_and_sink.contains(&jmp.tid.to_string().as_str()) {
+                    steps.push(call_step(jmp, caller));
+                }
+            }
+            (Edge::CrReturnStub, Node::BlkEnd(blk, callee)) => {
+                let return_tid = blk
+                    .term
+                    .jmps
+                    .iter()
+                    .find(|jmp| matches!(jmp.term, Jmp::Return(_)))
+                    .map(|jmp| &jmp.tid)
+                    .unwrap_or(&blk.tid);
+                let mut step = TraceStep::new(TraceStepKind::Return, return_tid);
+                step.function = callee.term.name.clone();
+                if let Node::CallReturn {
+                    call: (_, caller), ..
+                } = graph[end]
+                {
+                    step.description = format!("Return to {}", caller.term.name);
+                }
+                steps.push(step);
+            }
+            _ => (),
+        }
+        if let Node::BlkStart(blk, sub) = graph[end] {
+            let mut step = TraceStep::new(TraceStepKind::Block, &blk.tid);
+            step.function = sub.term.name.clone();
+            step.description = format!("Block in {}", sub.term.name);
+            steps.push(step);
+        }
+        previous_edge = Some(graph[*edge]);
+    }
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intermediate_representation::{Blk, Def, Expression, ExternSymbol, Variable};
+
+    fn mock_block(tid: &str, defs: Vec<&str>, jmp: Jmp) -> Term<Blk> {
+        Term {
+            tid: Tid::new(tid),
+            term: Blk {
+                defs: defs
+                    .into_iter()
+                    .map(|def_tid| {
+                        Def::assign(
+                            def_tid,
+                            Variable::mock("RAX", 8),
+                            Expression::const_from_i64(0),
+                        )
+                    })
+                    .collect(),
+                jmps: vec![Term {
+                    tid: Tid::new(format!("{}_jmp", tid)),
+                    term: jmp,
+                }],
+                indirect_jmp_targets: Vec::new(),
+            },
+        }
+    }
+
+    /// A `main` function calling the extern function `malloc` and the function `callee`.
+    fn mock_project() -> Project {
+        let return_ = || Jmp::Return(Expression::const_from_i64(0));
+        let mut main = Sub::mock("main");
+        main.term.blocks = vec![
+            mock_block(
+                "main_blk_1",
+                Vec::new(),
+                Jmp::Call {
+                    target: Tid::new("malloc"),
+                    return_: Some(Tid::new("main_blk_2")),
+                },
+            ),
+            mock_block(
+                "main_blk_2",
+                Vec::new(),
+                Jmp::Call {
+                    target: Tid::new("callee"),
+                    return_: Some(Tid::new("main_blk_3")),
+                },
+            ),
+            mock_block("main_blk_3", Vec::new(), return_()),
+        ];
+        let mut callee = Sub::mock("callee");
+        callee.term.blocks = vec![mock_block("callee_blk", vec!["callee_def"], return_())];
+        let mut malloc = ExternSymbol::mock();
+        malloc.tid = Tid::new("malloc");
+        malloc.name = "malloc".to_string();
+        let mut project = Project::mock_empty();
+        project.program.term.subs = vec![main, callee];
+        project.program.term.extern_symbols = vec![malloc];
+        project
+    }
+
+    #[test]
+    fn witness_traces() {
+        let project = mock_project();
+        let graph = get_program_cfg(&project.program, HashSet::from([Tid::new("malloc")]));
+        let warning = |source: &str, sink: &str| {
+            CweWarning::new("CWE416", "0.3", "")
+                .trace_source_and_sink(&Tid::new(source), &Tid::new(sink))
+        };
+        let mut warnings = vec![
+            // From the call to `malloc` into the callee
+            warning("main_blk_1_jmp", "callee_def"),
+            // From the callee back to its caller
+            warning("callee_def", "main_blk_3_jmp"),
+            // From the start of `main` to the end of `main`, skipping the call to the callee
+            warning("main", "main_blk_3_jmp"),
+            warning("unknown", "callee_def"),
+        ];
+        add_witness_traces(&mut warnings, &graph, &project);
+        let steps = |warning: &CweWarning| -> Vec<(TraceStepKind, String, String)> {
+            warning
+                .trace
+                .iter()
+                .map(|step| (step.kind, step.tid.clone(), step.function.clone()))
+                .collect()
+        };
+        let step = |kind, tid: &str, function: &str| (kind, tid.to_string(), function.to_string());
+        use TraceStepKind::*;
+        assert_eq!(
+            steps(&warnings[0]),
+            vec![
+                step(Source, "main_blk_1_jmp", "main"),
+                step(Block, "main_blk_2", "main"),
+                step(Call, "main_blk_2_jmp", "main"),
+                step(Block, "callee_blk", "callee"),
+                step(Sink, "callee_def", "callee"),
+            ]
+        );
+        assert_eq!(warnings[0].trace[2].description, "Call to callee");
+        assert_eq!(
+            steps(&warnings[1]),
+            vec![
+                step(Source, "callee_def", "callee"),
+                step(Return, "callee_blk_jmp", "callee"),
+                step(Block, "main_blk_3", "main"),
+                step(Sink, "main_blk_3_jmp", "main"),
+            ]
+        );
+        assert_eq!(
+            steps(&warnings[2]),
+            vec![
+                step(Source, "main", "main"),
+                step(Call, "main_blk_1_jmp", "main"),
+                step(Block, "main_blk_2", "main"),
+                step(Call, "main_blk_2_jmp", "main"),
+                step(Block, "main_blk_3", "main"),
+                step(Sink, "main_blk_3_jmp", "main"),
+            ]
+        );
+        assert_eq!(warnings[2].trace[1].description, "Call to malloc");
+        assert_eq!(
+            steps(&warnings[3]),
+            vec![
+                step(Source, "unknown", ""),
+                step(Sink, "callee_def", "callee"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,613 @@
+//! Generation of a self-contained HTML report of the analysis results.
+//!
+//! The report is a single HTML file without external assets, so that it can be viewed offline.
+//! It contains
+//! - a banner if the analysis results are partial,
+//! - metadata about the analyzed binary, e.g. its architecture and base address,
+//! - the versions, running times and numbers of warnings of the CWE modules,
+//! - the CWE warnings grouped by CWE and by the function containing them,
+//!   each together with a collapsible view of the P-Code of the block containing the warning
+//! - and the log messages of the analysis.
+
+use super::log::{
+    BaselineState, CweWarning, LogLevel, LogMessage, TraceStep, TraceStepKind,
+    PARTIAL_RESULTS_MESSAGE,
+};
+use crate::intermediate_representation::{Blk, Project, Sub, Term};
+use crate::pipeline::Report;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+/// The style sheet of the report.
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; margin-bottom: 1em; }
+th, td { border: 1px solid #ccc; padding: 0.2em 0.6em; text-align: left; vertical-align: top; }
+th { background: #eee; }
+code, .pcode td { font-family: monospace; }
+.warning { border: 1px solid #ccc; border-left: 6px solid #999; margin: 0.8em 0; padding: 0.4em 0.8em; }
+.warning.high { border-left-color: #c0392b; }
+.warning.medium { border-left-color: #e67e22; }
+.warning.low { border-left-color: #2980b9; }
+.badge { display: inline-block; background: #eee; border-radius: 0.3em; padding: 0 0.4em; margin-left: 0.4em; font-size: 0.9em; }
+.pcode tr.highlight, :target { background: #fff3c4; }
+summary { cursor: pointer; }
+.partial { border: 1px solid #e67e22; background: #fdebd0; padding: 0.4em 0.8em; }
+";
+
+/// A script opening all collapsed sections containing the target of a link to an anchor.
+const SCRIPT: &str = "
+function openTarget() {
+  var element = document.getElementById(decodeURIComponent(location.hash.slice(1)));
+  for (; element; element = element.parentElement) {
+    if (element.tagName === 'DETAILS') { element.open = true; }
+  }
+}
+window.addEventListener('hashchange', openTarget);
+window.addEventListener('load', openTarget);
+";
+
+/// Generate a self-contained HTML report for the analysis results of a binary.
+///
+/// The `logs` are shown instead of the logs contained in the `report`,
+/// so that the caller can add log messages of the frontend or filter out debug messages.
+/// The base address of the binary is read from the `binary` if possible.
+pub fn generate_html_report(
+    report: &Report,
+    logs: &[LogMessage],
+    project: &Project,
+    binary: &[u8],
+    binary_path: &str,
+) -> String {
+    let mut html = String::new();
+    writeln!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>cwe_checker report: {}</title>\n<style>{}</style>\n<script>{}</script>\n</head>\n<body>",
+        escape(binary_path),
+        STYLE,
+        SCRIPT
+    )
+    .unwrap();
+    writeln!(html, "<h1>cwe_checker report: {}</h1>", escape(binary_path)).unwrap();
+    if report.is_partial {
+        writeln!(
+            html,
+            "<p class=\"partial\"><strong>Partial results:</strong> {}</p>",
+            PARTIAL_RESULTS_MESSAGE
+        )
+        .unwrap();
+    }
+    write_binary_metadata(&mut html, report, project, binary, binary_path);
+    write_module_table(&mut html, report);
+
+    let blocks = BlockMap::new(project);
+    let warning_locations: Vec<Option<(&Term<Sub>, &Term<Blk>)>> = report
+        .warnings
+        .iter()
+        .map(|warning| blocks.get_location(warning))
+        .collect();
+    write_warnings_by_cwe(&mut html, &report.warnings);
+    write_warnings_by_function(&mut html, &report.warnings, &warning_locations);
+    write_logs(&mut html, logs);
+    writeln!(html, "</body>\n</html>").unwrap();
+    html
+}
+
+/// Write the table with the metadata of the binary.
+fn write_binary_metadata(
+    html: &mut String,
+    report: &Report,
+    project: &Project,
+    binary: &[u8],
+    binary_path: &str,
+) {
+    // Addresses in the report are shifted by the base offset of the program relative to the binary.
+    let base_address = match super::get_binary_base_address(binary) {
+        Ok(base_address) => format!(
+            "0x{:x}",
+            base_address.wrapping_add(project.program.term.address_base_offset)
+        ),
+        Err(_) => "unknown".to_string(),
+    };
+    let statistics = &report.statistics;
+    let rows = [
+        ("Path", escape(binary_path)),
+        ("Architecture", escape(&project.cpu_architecture)),
+        (
+            "Pointer size",
+            format!("{} bits", project.get_pointer_bytesize().as_bit_length()),
+        ),
+        ("Base address", base_address),
+        (
+            "Address offset to the binary",
+            format!("0x{:x}", project.program.term.address_base_offset),
+        ),
+        ("Functions", statistics.num_subs.to_string()),
+        ("Blocks", statistics.num_blocks.to_string()),
+        ("Extern symbols", statistics.num_extern_symbols.to_string()),
+        ("CWE warnings", report.warnings.len().to_string()),
+        (
+            "Partial analysis",
+            if report.is_partial {
+                "yes, the disassembly of the binary is incomplete"
+            } else {
+                "no"
+            }
+            .to_string(),
+        ),
+    ];
+    writeln!(html, "<h2 id=\"binary\">Binary</h2>\n<table>").unwrap();
+    for (name, value) in rows {
+        writeln!(html, "<tr><th>{}</th><td>{}</td></tr>", name, value).unwrap();
+    }
+    writeln!(html, "</table>").unwrap();
+}
+
+/// Write the table with the versions, running times and numbers of warnings of the CWE modules.
+fn write_module_table(html: &mut String, report: &Report) {
+    let statistics = &report.statistics;
+    writeln!(
+        html,
+        "<h2 id=\"modules\">Modules</h2>\n<table>\n<tr><th>Module</th><th>Version</th><th>Warnings</th><th>Running time</th></tr>"
+    )
+    .unwrap();
+    for (module, version) in statistics.module_versions.iter() {
+        writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape(module),
+            escape(version),
+            statistics.num_warnings.get(module).copied().unwrap_or(0),
+            format_running_time(statistics.running_time_ms.get(module)),
+        )
+        .unwrap();
+    }
+    writeln!(html, "</table>").unwrap();
+    if let Some(running_time) = statistics.running_time_ms.get("PointerInference") {
+        writeln!(
+            html,
+            "<p>The pointer inference analysis took {}.</p>",
+            format_running_time(Some(running_time))
+        )
+        .unwrap();
+    }
+}
+
+/// Write the list of warnings grouped by CWE, linking to the warnings in the list grouped by function.
+fn write_warnings_by_cwe(html: &mut String, warnings: &[CweWarning]) {
+    let mut warnings_by_cwe: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (index, warning) in warnings.iter().enumerate() {
+        warnings_by_cwe
+            .entry(&warning.name)
+            .or_default()
+            .push(index);
+    }
+    writeln!(html, "<h2 id=\"cwes\">Warnings by CWE</h2>").unwrap();
+    if warnings.is_empty() {
+        writeln!(html, "<p>No CWE warnings were found.</p>").unwrap();
+    }
+    for (name, indices) in warnings_by_cwe {
+        writeln!(
+            html,
+            "<details open>\n<summary><strong>{}</strong> ({})</summary>\n<ul>",
+            escape(name),
+            indices.len()
+        )
+        .unwrap();
+        for index in indices {
+            writeln!(
+                html,
+                "<li><a href=\"#warning-{}\">{}</a></li>",
+                index,
+                escape(&warnings[index].description)
+            )
+            .unwrap();
+        }
+        writeln!(html, "</ul>\n</details>").unwrap();
+    }
+}
+
+/// Write the warnings grouped by the functions containing them.
+///
+/// Warnings for which no containing function is known are listed last.
+fn write_warnings_by_function(
+    html: &mut String,
+    warnings: &[CweWarning],
+    warning_locations: &[Option<(&Term<Sub>, &Term<Blk>)>],
+) {
+    let mut warnings_by_function: BTreeMap<Option<&str>, Vec<usize>> = BTreeMap::new();
+    for (index, location) in warning_locations.iter().enumerate() {
+        warnings_by_function
+            .entry(location.map(|(sub, _)| sub.term.name.as_str()))
+            .or_default()
+            .push(index);
+    }
+    // Warnings without known function are sorted first, but should be listed last.
+    let mut functions: Vec<_> = warnings_by_function.into_iter().collect();
+    if matches!(functions.first(), Some((None, _))) {
+        functions.rotate_left(1);
+    }
+    writeln!(html, "<h2 id=\"functions\">Warnings by function</h2>").unwrap();
+    for (function_index, (function, mut indices)) in functions.into_iter().enumerate() {
+        indices.sort_by(|index, other| warnings[*index].addresses.cmp(&warnings[*other].addresses));
+        let function_name = match (function, warning_locations[indices[0]]) {
+            (Some(_), Some((sub, _))) => sub.term.get_display_name(),
+            _ => "Unknown function".to_string(),
+        };
+        writeln!(
+            html,
+            "<h3 id=\"function-{}\">{} ({})</h3>",
+            function_index,
+            escape(&function_name),
+            indices.len()
+        )
+        .unwrap();
+        for index in indices {
+            write_warning(html, index, &warnings[index], warning_locations[index]);
+        }
+    }
+}
+
+/// Write a single warning together with its witness trace and the P-Code of the block containing it.
+fn write_warning(
+    html: &mut String,
+    index: usize,
+    warning: &CweWarning,
+    location: Option<(&Term<Sub>, &Term<Blk>)>,
+) {
+    let block = location.map(|(_, blk)| blk);
+    writeln!(
+        html,
+        "<div class=\"warning {}\" id=\"warning-{}\">\n<div><strong>{}</strong> <span class=\"badge\">version {}</span><span class=\"badge\">severity: {}</span><span class=\"badge\">confidence: {}</span>{}</div>\n<p>{}</p>",
+        warning.severity,
+        index,
+        escape(&warning.name),
+        escape(&warning.version),
+        warning.severity,
+        warning.confidence,
+        match warning.baseline_state {
+            Some(BaselineState::New) => "<span class=\"badge\">new</span>",
+            Some(BaselineState::Unchanged) => "<span class=\"badge\">unchanged</span>",
+            Some(BaselineState::Fixed) => "<span class=\"badge\">fixed</span>",
+            None => "",
+        },
+        escape(&warning.description)
+    )
+    .unwrap();
+    // Link the addresses of the warning to the corresponding instructions of the P-Code view.
+    let block_addresses = block.map(get_block_addresses).unwrap_or_default();
+    let addresses: Vec<String> = warning
+        .addresses
+        .iter()
+        .map(|address| {
+            if block_addresses.contains(&address.as_str()) {
+                format!(
+                    "<a href=\"#warning-{}-{}\"><code>{}</code></a>",
+                    index,
+                    escape(address),
+                    escape(address)
+                )
+            } else {
+                format!("<code>{}</code>", escape(address))
+            }
+        })
+        .collect();
+    if !addresses.is_empty() {
+        writeln!(html, "<p>Addresses: {}</p>", addresses.join(", ")).unwrap();
+    }
+    if !warning.symbols.is_empty() {
+        writeln!(
+            html,
+            "<p>Symbols: <code>{}</code></p>",
+            escape(&warning.symbols.join(", "))
+        )
+        .unwrap();
+    }
+    if !warning.trace.is_empty() {
+        write_trace(html, &warning.trace);
+    }
+    if let Some(blk) = block {
+        write_block(html, index, warning, blk);
+    }
+    writeln!(html, "</div>").unwrap();
+}
+
+/// Write the witness trace of a warning as a collapsible list.
+fn write_trace(html: &mut String, trace: &[TraceStep]) {
+    writeln!(
+        html,
+        "<details>\n<summary>Witness trace ({} steps)</summary>\n<ol>",
+        trace.len()
+    )
+    .unwrap();
+    for step in trace {
+        writeln!(
+            html,
+            "<li><code>{}</code> {} in <code>{}</code>: {}</li>",
+            escape(&step.address),
+            match step.kind {
+                TraceStepKind::Source => "source",
+                TraceStepKind::Block => "block",
+                TraceStepKind::Call => "call",
+                TraceStepKind::Return => "return",
+                TraceStepKind::Sink => "sink",
+            },
+            escape(&step.function),
+            escape(&step.description)
+        )
+        .unwrap();
+    }
+    writeln!(html, "</ol>\n</details>").unwrap();
+}
+
+/// Write the P-Code of the block containing a warning as a collapsible table.
+///
+/// The instructions at the addresses or with the term IDs of the warning are highlighted.
+/// The first instruction at each address of the warning is the target of the corresponding address link.
+fn write_block(html: &mut String, index: usize, warning: &CweWarning, blk: &Term<Blk>) {
+    writeln!(
+        html,
+        "<details>\n<summary>P-Code of block <code>{}</code></summary>\n<table class=\"pcode\">",
+        escape(&blk.tid.to_string())
+    )
+    .unwrap();
+    let mut linked_addresses = Vec::new();
+    let instructions = blk
+        .term
+        .defs
+        .iter()
+        .map(|def| (&def.tid, def.term.to_string()))
+        .chain(
+            blk.term
+                .jmps
+                .iter()
+                .map(|jmp| (&jmp.tid, jmp.term.to_string())),
+        );
+    for (tid, instruction) in instructions {
+        let is_highlighted =
+            warning.tids.contains(&tid.to_string()) || warning.addresses.contains(&tid.address);
+        let anchor = if warning.addresses.contains(&tid.address)
+            && !linked_addresses.contains(&&tid.address)
+        {
+            linked_addresses.push(&tid.address);
+            format!(" id=\"warning-{}-{}\"", index, escape(&tid.address))
+        } else {
+            String::new()
+        };
+        writeln!(
+            html,
+            "<tr{}{}><td>{}</td><td>{}</td><td>{}</td></tr>",
+            anchor,
+            if is_highlighted {
+                " class=\"highlight\""
+            } else {
+                ""
+            },
+            escape(&tid.address),
+            escape(&tid.to_string()),
+            escape(&instruction)
+        )
+        .unwrap();
+    }
+    writeln!(html, "</table>\n</details>").unwrap();
+}
+
+/// Write the table of log messages.
+fn write_logs(html: &mut String, logs: &[LogMessage]) {
+    writeln!(html, "<h2 id=\"logs\">Log messages</h2>").unwrap();
+    if logs.is_empty() {
+        writeln!(html, "<p>No log messages.</p>").unwrap();
+        return;
+    }
+    writeln!(
+        html,
+        "<table>\n<tr><th>Level</th><th>Source</th><th>Location</th><th>Message</th></tr>"
+    )
+    .unwrap();
+    for log in logs {
+        writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            match log.level {
+                LogLevel::Debug => "debug",
+                LogLevel::Error => "error",
+                LogLevel::Info => "info",
+            },
+            escape(log.source.as_deref().unwrap_or_default()),
+            log.location
+                .as_ref()
+                .map(|tid| format!("<code>{}</code>", escape(&tid.to_string())))
+                .unwrap_or_default(),
+            escape(&log.text)
+        )
+        .unwrap();
+    }
+    writeln!(html, "</table>").unwrap();
+}
+
+/// Maps term IDs and addresses to the functions and blocks containing them.
+struct BlockMap<'a> {
+    /// The blocks keyed by the term IDs of the blocks and their `Def` and `Jmp` terms.
+    tids: HashMap<String, (&'a Term<Sub>, &'a Term<Blk>)>,
+    /// The blocks keyed by the addresses of their `Def` and `Jmp` terms.
+    addresses: HashMap<&'a str, (&'a Term<Sub>, &'a Term<Blk>)>,
+}
+
+impl<'a> BlockMap<'a> {
+    /// Create the map for all blocks of the project.
+    ///
+    /// If a block is contained in several functions, the first function is used.
+    fn new(project: &'a Project) -> BlockMap<'a> {
+        let mut tids = HashMap::new();
+        let mut addresses = HashMap::new();
+        for sub in project.program.term.subs.iter() {
+            for blk in sub.term.blocks.iter() {
+                tids.entry(blk.tid.to_string()).or_insert((sub, blk));
+                for tid in blk
+                    .term
+                    .defs
+                    .iter()
+                    .map(|def| &def.tid)
+                    .chain(blk.term.jmps.iter().map(|jmp| &jmp.tid))
+                {
+                    tids.entry(tid.to_string()).or_insert((sub, blk));
+                    addresses.entry(tid.address.as_str()).or_insert((sub, blk));
+                }
+            }
+        }
+        BlockMap { tids, addresses }
+    }
+
+    /// Get the function and block containing the warning.
+    ///
+    /// The location is determined by the term IDs of the warning if possible
+    /// and by its first address otherwise.
+    fn get_location(&self, warning: &CweWarning) -> Option<(&'a Term<Sub>, &'a Term<Blk>)> {
+        warning
+            .tids
+            .iter()
+            .find_map(|tid| self.tids.get(tid))
+            .or_else(|| {
+                warning
+                    .addresses
+                    .first()
+                    .and_then(|address| self.addresses.get(address.as_str()))
+            })
+            .copied()
+    }
+}
+
+/// Get the addresses of the `Def` and `Jmp` terms of the block.
+fn get_block_addresses(blk: &Term<Blk>) -> Vec<&str> {
+    blk.term
+        .defs
+        .iter()
+        .map(|def| def.tid.address.as_str())
+        .chain(blk.term.jmps.iter().map(|jmp| jmp.tid.address.as_str()))
+        .collect()
+}
+
+/// Format a running time given in milliseconds.
+fn format_running_time(running_time_ms: Option<&u64>) -> String {
+    match running_time_ms {
+        Some(running_time_ms) => format!("{} ms", running_time_ms),
+        None => "-".to_string(),
+    }
+}
+
+/// Escape the characters of a text that have a special meaning in HTML.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intermediate_representation::{Def, Expression, Jmp, Tid, Variable};
+    use crate::pipeline::Statistics;
+    use crate::utils::log::Severity;
+
+    fn mock_tid(id: &str, address: &str) -> Tid {
+        let mut tid = Tid::new(id);
+        tid.address = address.to_string();
+        tid
+    }
+
+    fn mock_project() -> Project {
+        let blk = Term {
+            tid: mock_tid("blk_00401000", "00401000"),
+            term: Blk {
+                defs: vec![Term {
+                    tid: mock_tid("instr_00401000_0", "00401000"),
+                    term: Def::Load {
+                        var: Variable::mock("RAX", 8),
+                        address: Expression::Var(Variable::mock("RDI", 8)),
+                    },
+                }],
+                jmps: vec![Term {
+                    tid: mock_tid("instr_00401004_0", "00401004"),
+                    term: Jmp::Return(Expression::Var(Variable::mock("RSP", 8))),
+                }],
+                indirect_jmp_targets: Vec::new(),
+            },
+        };
+        let mut sub = Sub::mock("main");
+        sub.term.name = "_ZN4Main3runEv".to_string();
+        sub.term.blocks = vec![blk];
+        let mut project = Project::mock_empty();
+        project.program.term.subs = vec![sub];
+        project
+    }
+
+    #[test]
+    fn html_report() {
+        let warnings = vec![
+            CweWarning::new(
+                "CWE476",
+                "0.3",
+                "(NULL Pointer Dereference) <check> at 00401000",
+            )
+            .addresses(vec!["00401000".to_string()])
+            .tids(vec!["instr_00401000_0".to_string()])
+            .severity(Severity::High),
+            CweWarning::new("CWE676", "0.1", "Call to gets")
+                .addresses(vec!["00402000".to_string()]),
+        ];
+        let report = Report {
+            warnings,
+            logs: Vec::new(),
+            statistics: Statistics {
+                module_versions: BTreeMap::from([("CWE476".to_string(), "0.3".to_string())]),
+                num_warnings: BTreeMap::from([("CWE476".to_string(), 1)]),
+                ..Statistics::default()
+            },
+            is_partial: false,
+        };
+        let logs = vec![LogMessage::new_info("Analysis finished.").source("CWE476")];
+        let html = generate_html_report(&report, &logs, &mock_project(), &[], "bin/main");
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        // The report must not reference external assets.
+        assert!(!html.contains("src="));
+        assert!(!html.contains("<link"));
+        assert!(html.contains("&lt;check&gt;"));
+        assert!(html.contains("<tr><td>CWE476</td><td>0.3</td><td>1</td><td>-</td></tr>"));
+        // The warnings are grouped by (demangled) function, with warnings in unknown functions last.
+        let main_index = html
+            .find("<h3 id=\"function-0\">Main::run() (1)</h3>")
+            .unwrap();
+        let unknown_index = html
+            .find("<h3 id=\"function-1\">Unknown function (1)</h3>")
+            .unwrap();
+        assert!(main_index < unknown_index);
+        // The address is linked to the highlighted instruction in the P-Code of the block.
+        assert!(html.contains("<a href=\"#warning-0-00401000\"><code>00401000</code></a>"));
+        assert!(html.contains(
+            "<tr id=\"warning-0-00401000\" class=\"highlight\"><td>00401000</td><td>instr_00401000_0</td><td>RAX:64 := Load from RDI:64</td></tr>"
+        ));
+        assert!(html.contains("<td>Return to RSP:64</td>"));
+        assert!(html.contains("<td>Analysis finished.</td>"));
+        assert!(!html.contains("class=\"partial\""));
+
+        let report = Report {
+            is_partial: true,
+            ..report
+        };
+        let html = generate_html_report(&report, &logs, &mock_project(), &[], "bin/main");
+        assert!(html.contains(&format!(
+            "<p class=\"partial\"><strong>Partial results:</strong> {}</p>",
+            PARTIAL_RESULTS_MESSAGE
+        )));
+    }
+}
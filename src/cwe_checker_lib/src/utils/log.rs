@@ -1,6 +1,9 @@
 //! Structs and functions for generating log messages and CWE warnings.
 
 use crate::prelude::*;
+use crate::utils::dwarf::SourceLocation;
+use crate::utils::error::{ErrorKind, WithErrorKind};
+use crate::utils::sarif::SarifLog;
 use std::{collections::BTreeMap, thread::JoinHandle};
 
 /// A CWE warning message.
@@ -24,6 +27,175 @@ pub struct CweWarning {
     /// Should contain all essential information necessary to understand the warning,
     /// including the address in the binary for which the warning was generated.
     pub description: String,
+    /// The source code locations of the addresses of the CWE warning.
+    /// Only available if the binary contains DWARF debug information.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub source_locations: Vec<SourceLocation>,
+    /// The demangled names of the mangled function and symbol names referenced by the CWE warning,
+    /// given as a map from raw names to demangled names.
+    /// The description of the warning already contains the demangled names.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub demangled_names: BTreeMap<String, String>,
+    /// A fingerprint identifying the warning independently of absolute addresses,
+    /// so that it is stable across rebuilds of the binary.
+    /// See [`add_fingerprints`](crate::utils::fingerprint::add_fingerprints) for details.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+    /// Whether the warning is new, unchanged or fixed compared to a baseline.
+    /// Only set if the warnings were compared to a baseline,
+    /// see [`compare_with_baseline`](crate::utils::fingerprint::compare_with_baseline).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline_state: Option<BaselineState>,
+    /// The severity of the warning, i.e. the potential impact of the weakness if the warning is correct.
+    #[serde(default)]
+    pub severity: Severity,
+    /// The confidence of the check that the warning is correct.
+    /// Depends on how exactly the check was able to track the values that the warning was derived from.
+    #[serde(default)]
+    pub confidence: Confidence,
+    /// A witness trace showing how the value that the warning was derived from
+    /// flows from its source to the sink where the warning was generated.
+    /// Only available for some dataflow-based checks.
+    /// See [`add_witness_traces`](crate::utils::graph_utils::add_witness_traces) for details.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trace: Vec<TraceStep>,
+}
+
+/// A step of the witness trace of a CWE warning.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, PartialOrd, Ord)]
+pub struct TraceStep {
+    /// The kind of the step.
+    pub kind: TraceStepKind,
+    /// The address of the step in the binary.
+    pub address: String,
+    /// The term ID of the block, call or instruction corresponding to the step.
+    pub tid: String,
+    /// The name of the function containing the step.
+    pub function: String,
+    /// A short description of the step.
+    pub description: String,
+}
+
+/// The kind of a step of a witness trace.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum TraceStepKind {
+    /// The origin of the value that the warning was derived from.
+    Source,
+    /// The start of a basic block.
+    Block,
+    /// A call to a function.
+    Call,
+    /// A return from a function to its caller.
+    Return,
+    /// The program point where the warning was generated.
+    Sink,
+}
+
+impl TraceStep {
+    /// Create a new trace step for the term with the given ID.
+    /// The function and description of the step are left empty.
+    pub fn new(kind: TraceStepKind, tid: &Tid) -> TraceStep {
+        TraceStep {
+            kind,
+            address: tid.address.clone(),
+            tid: tid.to_string(),
+            function: String::new(),
+            description: String::new(),
+        }
+    }
+}
+
+/// The severity of a CWE warning.
+#[derive(
+    Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord, Default,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Weaknesses that are unlikely to be exploitable on their own,
+    /// e.g. the inclusion of debug information.
+    Low,
+    /// Weaknesses that may lead to unexpected behavior or information leaks.
+    #[default]
+    Medium,
+    /// Weaknesses that may lead to memory corruption or code execution.
+    High,
+}
+
+impl std::str::FromStr for Severity {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Severity, String> {
+        match value {
+            "low" => Ok(Severity::Low),
+            "medium" => Ok(Severity::Medium),
+            "high" => Ok(Severity::High),
+            _ => Err(format!("{} is not a valid severity.", value)),
+        }
+    }
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Low => write!(formatter, "low"),
+            Severity::Medium => write!(formatter, "medium"),
+            Severity::High => write!(formatter, "high"),
+        }
+    }
+}
+
+/// The confidence of a check that a CWE warning is correct.
+#[derive(
+    Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord, Default,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    /// The check is a coarse heuristic or the involved values were only partially tracked,
+    /// e.g. a pointer that may also contain `Top` values.
+    Low,
+    /// The involved values were tracked, but the warning may not apply to all of them,
+    /// e.g. a pointer with several possible targets.
+    #[default]
+    Medium,
+    /// The warning was derived from exactly known values,
+    /// e.g. a pointer with exactly one known target.
+    High,
+}
+
+impl std::str::FromStr for Confidence {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Confidence, String> {
+        match value {
+            "low" => Ok(Confidence::Low),
+            "medium" => Ok(Confidence::Medium),
+            "high" => Ok(Confidence::High),
+            _ => Err(format!("{} is not a valid confidence.", value)),
+        }
+    }
+}
+
+impl std::fmt::Display for Confidence {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Confidence::Low => write!(formatter, "low"),
+            Confidence::Medium => write!(formatter, "medium"),
+            Confidence::High => write!(formatter, "high"),
+        }
+    }
+}
+
+/// The state of a CWE warning compared to the CWE warnings of a baseline.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum BaselineState {
+    /// The warning is not contained in the baseline.
+    New,
+    /// The warning is contained in the baseline.
+    Unchanged,
+    /// The warning is only contained in the baseline.
+    Fixed,
 }
 
 impl CweWarning {
@@ -41,6 +213,13 @@ impl CweWarning {
             symbols: Vec::new(),
             other: Vec::new(),
             description: description.to_string(),
+            source_locations: Vec::new(),
+            demangled_names: BTreeMap::new(),
+            fingerprint: None,
+            baseline_state: None,
+            severity: Severity::default(),
+            confidence: Confidence::default(),
+            trace: Vec::new(),
         }
     }
 
@@ -67,10 +246,39 @@ impl CweWarning {
         self.other = other;
         self
     }
+
+    /// Sets the severity field of the CweWarning
+    pub fn severity(mut self, severity: Severity) -> CweWarning {
+        self.severity = severity;
+        self
+    }
+
+    /// Sets the confidence field of the CweWarning
+    pub fn confidence(mut self, confidence: Confidence) -> CweWarning {
+        self.confidence = confidence;
+        self
+    }
+
+    /// Sets the source and the sink of the witness trace of the CweWarning.
+    /// The steps in between are filled in after all checks ran,
+    /// see [`add_witness_traces`](crate::utils::graph_utils::add_witness_traces).
+    pub fn trace_source_and_sink(mut self, source: &Tid, sink: &Tid) -> CweWarning {
+        self.trace = vec![
+            TraceStep::new(TraceStepKind::Source, source),
+            TraceStep::new(TraceStepKind::Sink, sink),
+        ];
+        self
+    }
 }
 
 impl std::fmt::Display for CweWarning {
     fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.baseline_state {
+            Some(BaselineState::New) => write!(formatter, "NEW: ")?,
+            Some(BaselineState::Unchanged) => write!(formatter, "UNCHANGED: ")?,
+            Some(BaselineState::Fixed) => write!(formatter, "FIXED: ")?,
+            None => (),
+        }
         write!(
             formatter,
             "[{}] ({}) {}",
@@ -164,37 +372,120 @@ impl std::fmt::Display for LogMessage {
     }
 }
 
+/// The message attached to the output of analysis results that are partial,
+/// see [`Report::is_partial`](crate::pipeline::Report::is_partial).
+pub const PARTIAL_RESULTS_MESSAGE: &str =
+    "The analysis results are partial, since the disassembly of the binary is incomplete.";
+
+/// The content of the JSON output of the CWE warnings.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(from = "JsonOutputFormat")]
+pub struct JsonOutput {
+    /// Set if the analysis results are partial, see [`Report::is_partial`](crate::pipeline::Report::is_partial).
+    pub is_partial: bool,
+    /// The CWE warnings.
+    pub warnings: Vec<CweWarning>,
+}
+
+/// The accepted formats when parsing a [`JsonOutput`].
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonOutputFormat {
+    /// The current format.
+    Object {
+        #[serde(default)]
+        is_partial: bool,
+        warnings: Vec<CweWarning>,
+    },
+    /// The plain array of CWE warnings printed by earlier versions of the cwe_checker.
+    Array(Vec<CweWarning>),
+}
+
+impl From<JsonOutputFormat> for JsonOutput {
+    fn from(format: JsonOutputFormat) -> JsonOutput {
+        match format {
+            JsonOutputFormat::Object {
+                is_partial,
+                warnings,
+            } => JsonOutput {
+                is_partial,
+                warnings,
+            },
+            JsonOutputFormat::Array(warnings) => JsonOutput {
+                is_partial: false,
+                warnings,
+            },
+        }
+    }
+}
+
+/// The output format for CWE warnings.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum OutputFormat {
+    /// One line of plain text per CWE warning.
+    Text,
+    /// A pretty-printed JSON object containing the CWE warnings, see [`JsonOutput`].
+    Json,
+    /// A SARIF 2.1.0 log file containing the CWE warnings and the log messages.
+    /// See [`SarifLog`] for details.
+    Sarif {
+        /// The path to the analyzed binary, used as the location of CWE warnings in the binary.
+        binary_path: Option<String>,
+    },
+}
+
 /// Print all provided log- and CWE-messages.
 ///
-/// Log-messages will always be printed to `stdout`.
+/// Log-messages will be printed to `stdout`,
+/// except for the SARIF output format, where they are part of the SARIF log instead.
 /// CWE-warnings will either be printed to `stdout` or to the file path provided in `out_path`.
+/// If `is_partial` is set, the CWE-warnings are marked as partial results in all output formats.
 ///
-/// If `emit_json` is set, the CWE-warnings will be converted to json for the output.
+/// Returns an [`Io`](ErrorKind::Io) error if writing to the file at `out_path` fails.
 pub fn print_all_messages(
     logs: Vec<LogMessage>,
     cwes: Vec<CweWarning>,
+    is_partial: bool,
     out_path: Option<&str>,
-    emit_json: bool,
-) {
-    for log in logs {
-        println!("{}", log);
-    }
-    let output: String = if emit_json {
-        serde_json::to_string_pretty(&cwes).unwrap()
-    } else {
-        cwes.iter()
-            .map(|cwe| format!("{}", cwe))
-            .collect::<Vec<String>>()
-            .join("\n")
-            + "\n"
+    format: OutputFormat,
+) -> Result<(), Error> {
+    let output: String = match format {
+        OutputFormat::Text | OutputFormat::Json => {
+            for log in logs {
+                println!("{}", log);
+            }
+            if format == OutputFormat::Json {
+                let json_output = JsonOutput {
+                    is_partial,
+                    warnings: cwes,
+                };
+                serde_json::to_string_pretty(&json_output).unwrap()
+            } else {
+                let mut lines: Vec<String> = cwes.iter().map(|cwe| format!("{}", cwe)).collect();
+                if is_partial {
+                    lines.insert(0, format!("Note: {}", PARTIAL_RESULTS_MESSAGE));
+                }
+                lines.join("\n") + "\n"
+            }
+        }
+        OutputFormat::Sarif { binary_path } => {
+            let sarif = SarifLog::new(&cwes, &logs, is_partial, binary_path.as_deref());
+            serde_json::to_string_pretty(&sarif).unwrap() + "\n"
+        }
     };
+    write_output(&output, out_path)
+}
+
+/// Write the output to the file at the given path or to `stdout` if no path is given.
+pub fn write_output(output: &str, out_path: Option<&str>) -> Result<(), Error> {
     if let Some(file_path) = out_path {
-        std::fs::write(file_path, output).unwrap_or_else(|error| {
-            panic!("Writing to output path {} failed: {}", file_path, error)
-        });
+        std::fs::write(file_path, output)
+            .map_err(|err| anyhow!("Writing to output path {} failed: {}", file_path, err))
+            .error_kind(ErrorKind::Io)?;
     } else {
         print!("{}", output);
     }
+    Ok(())
 }
 
 /// For each analysis count the number of debug log messages in `all_logs`
@@ -324,3 +615,32 @@ impl LogThread {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_output_formats() {
+        let warning = CweWarning::new("CWE676", "0.1", "Call to gets");
+        let json_output = JsonOutput {
+            is_partial: true,
+            warnings: vec![warning.clone()],
+        };
+        let json = serde_json::to_value(&json_output).unwrap();
+        assert_eq!(json["is_partial"], true);
+        assert_eq!(
+            serde_json::from_value::<JsonOutput>(json).unwrap(),
+            json_output
+        );
+        // The plain array of warnings of earlier versions is still accepted.
+        let json = serde_json::to_value(vec![warning.clone()]).unwrap();
+        assert_eq!(
+            serde_json::from_value::<JsonOutput>(json).unwrap(),
+            JsonOutput {
+                is_partial: false,
+                warnings: vec![warning],
+            }
+        );
+    }
+}
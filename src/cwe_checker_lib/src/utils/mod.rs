@@ -2,21 +2,47 @@
 
 pub mod arguments;
 pub mod binary;
+pub mod demangle;
+pub mod dwarf;
+pub mod error;
+pub mod fingerprint;
 pub mod graph_utils;
+pub mod html;
 pub mod log;
+pub mod prototypes;
+pub mod sarif;
 pub mod symbol_utils;
 
 use crate::prelude::*;
+use error::{ErrorKind, WithErrorKind};
 
 /// Get the contents of a configuration file.
-pub fn read_config_file(filename: &str) -> serde_json::Value {
+///
+/// Returns a [`Configuration`](ErrorKind::Configuration) error
+/// if the file does not exist or does not contain valid JSON.
+pub fn read_config_file(filename: &str) -> Result<serde_json::Value, Error> {
     let project_dirs = directories::ProjectDirs::from("", "", "cwe_checker")
-        .expect("Could not discern location of configuration files.");
-    let config_dir = project_dirs.config_dir();
-    let config_path = config_dir.join(filename);
-    let config_file =
-        std::fs::read_to_string(config_path).expect("Could not read configuration file");
-    serde_json::from_str(&config_file).unwrap()
+        .ok_or_else(|| anyhow!("Could not discern location of configuration files."))
+        .error_kind(ErrorKind::Configuration)?;
+    let config_path = project_dirs.config_dir().join(filename);
+    let config_file = std::fs::read_to_string(&config_path)
+        .map_err(|err| {
+            anyhow!(
+                "Could not read configuration file {}: {}",
+                config_path.display(),
+                err
+            )
+        })
+        .error_kind(ErrorKind::Configuration)?;
+    serde_json::from_str(&config_file)
+        .map_err(|err| {
+            anyhow!(
+                "Parsing of the configuration file {} failed: {}",
+                config_path.display(),
+                err
+            )
+        })
+        .error_kind(ErrorKind::Configuration)
 }
 
 /// Get the folder path to a Ghidra plugin bundled with the cwe_checker.
@@ -31,6 +57,9 @@ pub fn get_ghidra_plugin_path(plugin_name: &str) -> std::path::PathBuf {
 pub fn get_binary_base_address(binary: &[u8]) -> Result<u64, Error> {
     use goblin::Object;
     match Object::parse(binary)? {
+        Object::Elf(elf_file) if elf_file.header.e_type == goblin::elf::header::ET_REL => {
+            Ok(binary::get_relocatable_elf_base_address(&elf_file))
+        }
         Object::Elf(elf_file) => {
             for header in elf_file.program_headers.iter() {
                 let vm_range = header.vm_range();
@@ -42,6 +71,27 @@ pub fn get_binary_base_address(binary: &[u8]) -> Result<u64, Error> {
             }
             Err(anyhow!("No loadable segment bounds found."))
         }
+        // The preferred base address of the image is given by the optional header.
+        // All section addresses in the file are relative to it.
+        Object::PE(pe_file) => Ok(pe_file.image_base as u64),
+        Object::Mach(goblin::mach::Mach::Binary(macho_file)) => {
+            // The `__TEXT` segment starts at the base address of the memory image.
+            // It is the first segment after the inaccessible `__PAGEZERO` segment.
+            if let Some(text_segment) = macho_file
+                .segments
+                .iter()
+                .find(|segment| segment.name().ok() == Some("__TEXT"))
+            {
+                return Ok(text_segment.vmaddr);
+            }
+            macho_file
+                .segments
+                .iter()
+                .filter(|segment| segment.name().ok() != Some("__PAGEZERO"))
+                .map(|segment| segment.vmaddr)
+                .min()
+                .ok_or_else(|| anyhow!("No loadable segment bounds found."))
+        }
         _ => Err(anyhow!("Binary type not yet supported")),
     }
 }
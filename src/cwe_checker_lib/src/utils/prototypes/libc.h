@@ -0,0 +1,415 @@
+/*
+ * Prototypes of C standard library functions bundled with the cwe_checker.
+ *
+ * The prototypes are used to fill in the parameters and return values of extern symbols
+ * for which Ghidra could not determine a signature.
+ * Besides the functions of the C standard and POSIX, the file contains the internal functions
+ * of glibc, newlib, uClibc and the ARM EABI runtime that commonly appear in embedded firmware.
+ *
+ * Additional prototypes can be provided via the `--prototypes` command line option,
+ * using the same (simplified) C declaration syntax as this file.
+ */
+
+/* Memory management */
+void *malloc(size_t size);
+void *calloc(size_t nmemb, size_t size);
+void *realloc(void *ptr, size_t size);
+void *reallocarray(void *ptr, size_t nmemb, size_t size);
+void free(void *ptr);
+void *aligned_alloc(size_t alignment, size_t size);
+void *memalign(size_t alignment, size_t size);
+int posix_memalign(void **memptr, size_t alignment, size_t size);
+void *valloc(size_t size);
+void *alloca(size_t size);
+void *_alloca(size_t size);
+void *xmalloc(size_t size);
+void *xcalloc(size_t nmemb, size_t size);
+void *xrealloc(void *ptr, size_t size);
+char *xstrdup(const char *s);
+void *mmap(void *addr, size_t length, int prot, int flags, int fd, off_t offset);
+int munmap(void *addr, size_t length);
+int mprotect(void *addr, size_t len, int prot);
+void *sbrk(intptr_t increment);
+
+/* Memory and string handling */
+void *memcpy(void *dest, const void *src, size_t n);
+void *memmove(void *dest, const void *src, size_t n);
+void *memset(void *s, int c, size_t n);
+int memcmp(const void *s1, const void *s2, size_t n);
+void *memchr(const void *s, int c, size_t n);
+void *memrchr(const void *s, int c, size_t n);
+void *mempcpy(void *dest, const void *src, size_t n);
+void *memccpy(void *dest, const void *src, int c, size_t n);
+void *memmem(const void *haystack, size_t haystacklen, const void *needle, size_t needlelen);
+void bzero(void *s, size_t n);
+void bcopy(const void *src, void *dest, size_t n);
+void explicit_bzero(void *s, size_t n);
+size_t strlen(const char *s);
+size_t strnlen(const char *s, size_t maxlen);
+char *strcpy(char *dest, const char *src);
+char *strncpy(char *dest, const char *src, size_t n);
+char *stpcpy(char *dest, const char *src);
+char *stpncpy(char *dest, const char *src, size_t n);
+size_t strlcpy(char *dst, const char *src, size_t size);
+size_t strlcat(char *dst, const char *src, size_t size);
+char *strcat(char *dest, const char *src);
+char *strncat(char *dest, const char *src, size_t n);
+int strcmp(const char *s1, const char *s2);
+int strncmp(const char *s1, const char *s2, size_t n);
+int strcasecmp(const char *s1, const char *s2);
+int strncasecmp(const char *s1, const char *s2, size_t n);
+int strcoll(const char *s1, const char *s2);
+size_t strxfrm(char *dest, const char *src, size_t n);
+char *strchr(const char *s, int c);
+char *strrchr(const char *s, int c);
+char *strchrnul(const char *s, int c);
+char *strstr(const char *haystack, const char *needle);
+char *strcasestr(const char *haystack, const char *needle);
+char *strpbrk(const char *s, const char *accept);
+size_t strspn(const char *s, const char *accept);
+size_t strcspn(const char *s, const char *reject);
+char *strtok(char *str, const char *delim);
+char *strtok_r(char *str, const char *delim, char **saveptr);
+char *strsep(char **stringp, const char *delim);
+char *strdup(const char *s);
+char *strndup(const char *s, size_t n);
+char *strerror(int errnum);
+int strerror_r(int errnum, char *buf, size_t buflen);
+char *index(const char *s, int c);
+char *rindex(const char *s, int c);
+
+/* Wide character strings */
+size_t wcslen(const wchar_t *s);
+wchar_t *wcscpy(wchar_t *dest, const wchar_t *src);
+wchar_t *wcsncpy(wchar_t *dest, const wchar_t *src, size_t n);
+wchar_t *wcpcpy(wchar_t *dest, const wchar_t *src);
+wchar_t *wcpncpy(wchar_t *dest, const wchar_t *src, size_t n);
+wchar_t *wcscat(wchar_t *dest, const wchar_t *src);
+wchar_t *wcsncat(wchar_t *dest, const wchar_t *src, size_t n);
+int wcscmp(const wchar_t *s1, const wchar_t *s2);
+int wcsncmp(const wchar_t *s1, const wchar_t *s2, size_t n);
+wchar_t *wcschr(const wchar_t *wcs, wchar_t wc);
+wchar_t *wcsrchr(const wchar_t *wcs, wchar_t wc);
+wchar_t *wcsstr(const wchar_t *haystack, const wchar_t *needle);
+wchar_t *wcspbrk(const wchar_t *wcs, const wchar_t *accept);
+wchar_t *wcstok(wchar_t *wcs, const wchar_t *delim, wchar_t **ptr);
+wchar_t *wmemcpy(wchar_t *dest, const wchar_t *src, size_t n);
+wchar_t *wmemmove(wchar_t *dest, const wchar_t *src, size_t n);
+wchar_t *wmemset(wchar_t *wcs, wchar_t wc, size_t n);
+int wmemcmp(const wchar_t *s1, const wchar_t *s2, size_t n);
+wchar_t *wmemchr(const wchar_t *s, wchar_t c, size_t n);
+size_t wcrtomb(char *s, wchar_t wc, mbstate_t *ps);
+int wctomb(char *s, wchar_t wc);
+size_t wcstombs(char *dest, const wchar_t *src, size_t n);
+size_t wcsrtombs(char *dest, const wchar_t **src, size_t len, mbstate_t *ps);
+size_t wcsnrtombs(char *dest, const wchar_t **src, size_t nwc, size_t len, mbstate_t *ps);
+size_t mbstowcs(wchar_t *dest, const char *src, size_t n);
+int mbtowc(wchar_t *pwc, const char *s, size_t n);
+
+/* Formatted input and output */
+int printf(const char *format, ...);
+int fprintf(FILE *stream, const char *format, ...);
+int dprintf(int fd, const char *format, ...);
+int sprintf(char *str, const char *format, ...);
+int snprintf(char *str, size_t size, const char *format, ...);
+int asprintf(char **strp, const char *fmt, ...);
+int vprintf(const char *format, va_list ap);
+int vfprintf(FILE *stream, const char *format, va_list ap);
+int vdprintf(int fd, const char *format, va_list ap);
+int vsprintf(char *str, const char *format, va_list ap);
+int vsnprintf(char *str, size_t size, const char *format, va_list ap);
+int vasprintf(char **strp, const char *fmt, va_list ap);
+int scanf(const char *format, ...);
+int fscanf(FILE *stream, const char *format, ...);
+int sscanf(const char *str, const char *format, ...);
+int vscanf(const char *format, va_list ap);
+int vfscanf(FILE *stream, const char *format, va_list ap);
+int vsscanf(const char *str, const char *format, va_list ap);
+int __isoc99_scanf(const char *format, ...);
+int __isoc99_fscanf(FILE *stream, const char *format, ...);
+int __isoc99_sscanf(const char *str, const char *format, ...);
+int wprintf(const wchar_t *format, ...);
+int swprintf(wchar_t *wcs, size_t maxlen, const wchar_t *format, ...);
+int vswprintf(wchar_t *wcs, size_t maxlen, const wchar_t *format, va_list args);
+int wscanf(const wchar_t *format, ...);
+int swscanf(const wchar_t *ws, const wchar_t *format, ...);
+
+/* Stream input and output */
+FILE *fopen(const char *pathname, const char *mode);
+FILE *fdopen(int fd, const char *mode);
+FILE *freopen(const char *pathname, const char *mode, FILE *stream);
+FILE *popen(const char *command, const char *type);
+int pclose(FILE *stream);
+int fclose(FILE *stream);
+int fflush(FILE *stream);
+size_t fread(void *ptr, size_t size, size_t nmemb, FILE *stream);
+size_t fwrite(const void *ptr, size_t size, size_t nmemb, FILE *stream);
+int fgetc(FILE *stream);
+int getc(FILE *stream);
+int getchar(void);
+char *fgets(char *s, int size, FILE *stream);
+wchar_t *fgetws(wchar_t *ws, int n, FILE *stream);
+char *gets(char *s);
+int fputc(int c, FILE *stream);
+int putc(int c, FILE *stream);
+int putchar(int c);
+int fputs(const char *s, FILE *stream);
+int puts(const char *s);
+int ungetc(int c, FILE *stream);
+ssize_t getline(char **lineptr, size_t *n, FILE *stream);
+ssize_t getdelim(char **lineptr, size_t *n, int delim, FILE *stream);
+int fseek(FILE *stream, long offset, int whence);
+long ftell(FILE *stream);
+void rewind(FILE *stream);
+int feof(FILE *stream);
+int ferror(FILE *stream);
+int fileno(FILE *stream);
+void setbuf(FILE *stream, char *buf);
+int setvbuf(FILE *stream, char *buf, int mode, size_t size);
+void perror(const char *s);
+FILE *tmpfile(void);
+char *tmpnam(char *s);
+char *tempnam(const char *dir, const char *pfx);
+int mkstemp(char *template);
+char *mktemp(char *template);
+char *mkdtemp(char *template);
+int remove(const char *pathname);
+int rename(const char *oldpath, const char *newpath);
+
+/* File descriptors and the file system */
+int open(const char *pathname, int flags, ...);
+int openat(int dirfd, const char *pathname, int flags, ...);
+int creat(const char *pathname, mode_t mode);
+int close(int fd);
+ssize_t read(int fd, void *buf, size_t count);
+ssize_t write(int fd, const void *buf, size_t count);
+ssize_t pread(int fd, void *buf, size_t count, off_t offset);
+ssize_t pwrite(int fd, const void *buf, size_t count, off_t offset);
+off_t lseek(int fd, off_t offset, int whence);
+int dup(int oldfd);
+int dup2(int oldfd, int newfd);
+int pipe(int *pipefd);
+int fcntl(int fd, int cmd, ...);
+int ioctl(int fd, unsigned long request, ...);
+int access(const char *pathname, int mode);
+int stat(const char *pathname, struct stat *statbuf);
+int fstat(int fd, struct stat *statbuf);
+int lstat(const char *pathname, struct stat *statbuf);
+int chmod(const char *pathname, mode_t mode);
+int fchmod(int fd, mode_t mode);
+int chown(const char *pathname, uid_t owner, gid_t group);
+int fchown(int fd, uid_t owner, gid_t group);
+int lchown(const char *pathname, uid_t owner, gid_t group);
+mode_t umask(mode_t mask);
+int mkdir(const char *pathname, mode_t mode);
+int rmdir(const char *pathname);
+int unlink(const char *pathname);
+int link(const char *oldpath, const char *newpath);
+int symlink(const char *target, const char *linkpath);
+ssize_t readlink(const char *pathname, char *buf, size_t bufsiz);
+int chdir(const char *path);
+int fchdir(int fd);
+int chroot(const char *path);
+char *getcwd(char *buf, size_t size);
+char *getwd(char *buf);
+char *realpath(const char *path, char *resolved_path);
+DIR *opendir(const char *name);
+struct dirent *readdir(DIR *dirp);
+int closedir(DIR *dirp);
+int truncate(const char *path, off_t length);
+int ftruncate(int fd, off_t length);
+int fsync(int fd);
+
+/* Processes, environment and privileges */
+int system(const char *command);
+int execl(const char *pathname, const char *arg, ...);
+int execlp(const char *file, const char *arg, ...);
+int execle(const char *pathname, const char *arg, ...);
+int execv(const char *pathname, char **argv);
+int execvp(const char *file, char **argv);
+int execve(const char *pathname, char **argv, char **envp);
+pid_t fork(void);
+pid_t vfork(void);
+pid_t waitpid(pid_t pid, int *wstatus, int options);
+pid_t wait(int *wstatus);
+int kill(pid_t pid, int sig);
+int raise(int sig);
+sighandler_t signal(int signum, sighandler_t handler);
+unsigned int sleep(unsigned int seconds);
+int usleep(useconds_t usec);
+unsigned int alarm(unsigned int seconds);
+pid_t getpid(void);
+pid_t getppid(void);
+char *getenv(const char *name);
+char *secure_getenv(const char *name);
+int setenv(const char *name, const char *value, int overwrite);
+int unsetenv(const char *name);
+int putenv(char *string);
+uid_t getuid(void);
+uid_t geteuid(void);
+gid_t getgid(void);
+gid_t getegid(void);
+int setuid(uid_t uid);
+int seteuid(uid_t euid);
+int setreuid(uid_t ruid, uid_t euid);
+int setresuid(uid_t ruid, uid_t euid, uid_t suid);
+int setgid(gid_t gid);
+int setegid(gid_t egid);
+int setregid(gid_t rgid, gid_t egid);
+int setresgid(gid_t rgid, gid_t egid, gid_t sgid);
+int setgroups(size_t size, const gid_t *list);
+__attribute__((noreturn)) void exit(int status);
+__attribute__((noreturn)) void _exit(int status);
+__attribute__((noreturn)) void _Exit(int status);
+__attribute__((noreturn)) void abort(void);
+int atexit(void *function);
+char *setlocale(int category, const char *locale);
+
+/* Conversion and utility functions */
+int atoi(const char *nptr);
+long atol(const char *nptr);
+long long atoll(const char *nptr);
+double atof(const char *nptr);
+long strtol(const char *nptr, char **endptr, int base);
+unsigned long strtoul(const char *nptr, char **endptr, int base);
+long long strtoll(const char *nptr, char **endptr, int base);
+unsigned long long strtoull(const char *nptr, char **endptr, int base);
+double strtod(const char *nptr, char **endptr);
+float strtof(const char *nptr, char **endptr);
+int abs(int j);
+long labs(long j);
+int rand(void);
+int rand_r(unsigned int *seedp);
+void srand(unsigned int seed);
+long random(void);
+void srandom(unsigned int seed);
+void qsort(void *base, size_t nmemb, size_t size, void *compar);
+void *bsearch(const void *key, const void *base, size_t nmemb, size_t size, void *compar);
+int toupper(int c);
+int tolower(int c);
+int isalpha(int c);
+int isdigit(int c);
+int isspace(int c);
+int isalnum(int c);
+int isprint(int c);
+time_t time(time_t *tloc);
+struct tm *localtime(const time_t *timep);
+struct tm *gmtime(const time_t *timep);
+size_t strftime(char *s, size_t max, const char *format, const struct tm *tm);
+char *ctime(const time_t *timep);
+int gettimeofday(struct timeval *tv, void *tz);
+
+/* Sockets */
+int socket(int domain, int type, int protocol);
+int bind(int sockfd, const struct sockaddr *addr, socklen_t addrlen);
+int listen(int sockfd, int backlog);
+int accept(int sockfd, struct sockaddr *addr, socklen_t *addrlen);
+int connect(int sockfd, const struct sockaddr *addr, socklen_t addrlen);
+ssize_t recv(int sockfd, void *buf, size_t len, int flags);
+ssize_t recvfrom(int sockfd, void *buf, size_t len, int flags, struct sockaddr *src_addr, socklen_t *addrlen);
+ssize_t recvmsg(int sockfd, struct msghdr *msg, int flags);
+ssize_t send(int sockfd, const void *buf, size_t len, int flags);
+ssize_t sendto(int sockfd, const void *buf, size_t len, int flags, const struct sockaddr *dest_addr, socklen_t addrlen);
+ssize_t sendmsg(int sockfd, const struct msghdr *msg, int flags);
+int setsockopt(int sockfd, int level, int optname, const void *optval, socklen_t optlen);
+int getsockopt(int sockfd, int level, int optname, void *optval, socklen_t *optlen);
+int shutdown(int sockfd, int how);
+int select(int nfds, fd_set *readfds, fd_set *writefds, fd_set *exceptfds, struct timeval *timeout);
+int poll(struct pollfd *fds, nfds_t nfds, int timeout);
+struct hostent *gethostbyname(const char *name);
+int getaddrinfo(const char *node, const char *service, const struct addrinfo *hints, struct addrinfo **res);
+void freeaddrinfo(struct addrinfo *res);
+uint32_t htonl(uint32_t hostlong);
+uint16_t htons(uint16_t hostshort);
+uint32_t ntohl(uint32_t netlong);
+uint16_t ntohs(uint16_t netshort);
+in_addr_t inet_addr(const char *cp);
+int inet_pton(int af, const char *src, void *dst);
+const char *inet_ntop(int af, const void *src, char *dst, socklen_t size);
+
+/* Threads */
+int pthread_create(pthread_t *thread, const pthread_attr_t *attr, void *start_routine, void *arg);
+int pthread_join(pthread_t thread, void **retval);
+int pthread_mutex_lock(pthread_mutex_t *mutex);
+int pthread_mutex_unlock(pthread_mutex_t *mutex);
+int pthread_mutex_init(pthread_mutex_t *mutex, const pthread_mutexattr_t *attr);
+
+/* glibc internals and fortified functions */
+int __libc_start_main(void *main, int argc, char **argv, void *init, void *fini, void *rtld_fini, void *stack_end);
+__attribute__((noreturn)) void __stack_chk_fail(void);
+__attribute__((noreturn)) void __assert_fail(const char *assertion, const char *file, unsigned int line, const char *function);
+int *__errno_location(void);
+const unsigned short **__ctype_b_loc(void);
+const int32_t **__ctype_tolower_loc(void);
+const int32_t **__ctype_toupper_loc(void);
+void __cxa_finalize(void *d);
+int __cxa_atexit(void *func, void *arg, void *dso_handle);
+void *__memcpy_chk(void *dest, const void *src, size_t len, size_t destlen);
+void *__memmove_chk(void *dest, const void *src, size_t len, size_t destlen);
+void *__memset_chk(void *dest, int c, size_t len, size_t destlen);
+char *__strcpy_chk(char *dest, const char *src, size_t destlen);
+char *__strncpy_chk(char *dest, const char *src, size_t len, size_t destlen);
+char *__strcat_chk(char *dest, const char *src, size_t destlen);
+char *__strncat_chk(char *dest, const char *src, size_t len, size_t destlen);
+char *__stpcpy_chk(char *dest, const char *src, size_t destlen);
+int __printf_chk(int flag, const char *format, ...);
+int __fprintf_chk(FILE *stream, int flag, const char *format, ...);
+int __sprintf_chk(char *str, int flag, size_t strlen, const char *format, ...);
+int __snprintf_chk(char *str, size_t maxlen, int flag, size_t strlen, const char *format, ...);
+int __vprintf_chk(int flag, const char *format, va_list ap);
+int __vfprintf_chk(FILE *stream, int flag, const char *format, va_list ap);
+int __vsprintf_chk(char *str, int flag, size_t slen, const char *format, va_list ap);
+int __vsnprintf_chk(char *str, size_t maxlen, int flag, size_t slen, const char *format, va_list ap);
+char *__fgets_chk(char *buf, size_t size, int n, FILE *fp);
+ssize_t __read_chk(int fd, void *buf, size_t nbytes, size_t buflen);
+ssize_t __recv_chk(int fd, void *buf, size_t n, size_t buflen, int flags);
+char *__realpath_chk(const char *buf, char *resolved, size_t resolvedlen);
+char *__getcwd_chk(char *buf, size_t size, size_t buflen);
+__attribute__((noreturn)) void __chk_fail(void);
+
+/* newlib and uClibc internals */
+int *__errno(void);
+void *_malloc_r(struct _reent *reent, size_t size);
+void *_calloc_r(struct _reent *reent, size_t nmemb, size_t size);
+void *_realloc_r(struct _reent *reent, void *ptr, size_t size);
+void _free_r(struct _reent *reent, void *ptr);
+int _printf_r(struct _reent *reent, const char *format, ...);
+int _fprintf_r(struct _reent *reent, FILE *stream, const char *format, ...);
+int _sprintf_r(struct _reent *reent, char *str, const char *format, ...);
+int _snprintf_r(struct _reent *reent, char *str, size_t size, const char *format, ...);
+int _vfprintf_r(struct _reent *reent, FILE *stream, const char *format, va_list ap);
+int _scanf_r(struct _reent *reent, const char *format, ...);
+int _sscanf_r(struct _reent *reent, const char *str, const char *format, ...);
+int _puts_r(struct _reent *reent, const char *s);
+int _putchar_r(struct _reent *reent, int c);
+int _system_r(struct _reent *reent, const char *command);
+char *_getenv_r(struct _reent *reent, const char *name);
+char *_strdup_r(struct _reent *reent, const char *s);
+char *_strtok_r(char *str, const char *delim, char **saveptr);
+int iprintf(const char *format, ...);
+int siprintf(char *str, const char *format, ...);
+int sniprintf(char *str, size_t size, const char *format, ...);
+int iscanf(const char *format, ...);
+int siscanf(const char *str, const char *format, ...);
+ssize_t _read(int fd, void *buf, size_t count);
+ssize_t _write(int fd, const void *buf, size_t count);
+int _open(const char *pathname, int flags, ...);
+int _close(int fd);
+void *_sbrk(intptr_t increment);
+int _isatty(int fd);
+
+/* ARM EABI runtime */
+void __aeabi_memcpy(void *dest, const void *src, size_t n);
+void __aeabi_memcpy4(void *dest, const void *src, size_t n);
+void __aeabi_memcpy8(void *dest, const void *src, size_t n);
+void __aeabi_memmove(void *dest, const void *src, size_t n);
+void __aeabi_memmove4(void *dest, const void *src, size_t n);
+void __aeabi_memmove8(void *dest, const void *src, size_t n);
+void __aeabi_memset(void *dest, size_t n, int c);
+void __aeabi_memset4(void *dest, size_t n, int c);
+void __aeabi_memset8(void *dest, size_t n, int c);
+void __aeabi_memclr(void *dest, size_t n);
+void __aeabi_memclr4(void *dest, size_t n);
+void __aeabi_memclr8(void *dest, size_t n);
+int __aeabi_idiv(int numerator, int denominator);
+unsigned int __aeabi_uidiv(unsigned int numerator, unsigned int denominator);
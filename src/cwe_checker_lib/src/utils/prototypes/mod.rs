@@ -0,0 +1,610 @@
+//! A database of C function prototypes for filling in missing signatures of extern symbols.
+//!
+//! Ghidra does not know the signatures of many extern symbols, e.g. of functions from embedded libc variants
+//! or vendor SDKs. For such symbols the parameter and return value lists are empty,
+//! so that the analyses have to assume that all parameter registers of the calling convention are used.
+//! The [`PrototypeDatabase`] maps function names to their prototypes
+//! and computes the parameter and return value locations from them.
+//!
+//! Prototypes are given as (simplified) C function declarations.
+//! A database of common C standard library functions is bundled with the cwe_checker (see [`PrototypeDatabase::bundled`]).
+//! Additional declarations can be read from C header files.
+//! Note that preprocessor directives are ignored, i.e. macros are not expanded.
+//! Typedefs contained in the header files are recognized.
+//! Declarations that cannot be parsed, e.g. because they contain unknown types or structs passed by value, are skipped.
+
+use crate::intermediate_representation::*;
+use crate::prelude::*;
+use crate::utils::log::LogMessage;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// The C declarations of the bundled prototype database.
+const BUNDLED_PROTOTYPES: &str = include_str!("libc.h");
+
+/// The source name for log messages generated by the prototype database.
+const LOG_SOURCE: &str = "Prototypes";
+
+/// The prototype of a function.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Prototype {
+    /// The data types of the (non-variadic) parameters of the function.
+    pub parameters: Vec<Datatype>,
+    /// The data type of the return value or `None` if the function returns `void`.
+    pub return_type: Option<Datatype>,
+    /// Set to `true` if the function has a variable number of parameters.
+    pub has_var_args: bool,
+    /// Set to `true` if the function never returns to its caller.
+    pub no_return: bool,
+}
+
+impl Prototype {
+    /// Compute the locations of the parameters and the return value of the prototype for the given calling convention.
+    ///
+    /// Integer and pointer parameters are passed in the next unused integer parameter register,
+    /// floating point parameters in the next unused float parameter register.
+    /// If no float parameter registers are left, floating point parameters are passed like integers
+    /// on 32-bit architectures and for calling conventions without float parameter registers
+    /// (e.g. the soft-float ARM EABI or the MIPS o32 calling convention).
+    /// On 32-bit architectures 8-byte values are passed in a register pair starting at an even register
+    /// and are aligned to 8 bytes on the stack.
+    /// Parameters that do not fit into the remaining registers are passed on the stack.
+    /// Calling conventions that assign register slots by parameter position (like the Windows x64 calling convention)
+    /// are only approximated by this scheme.
+    ///
+    /// Stack offsets are relative to the stack pointer at the call instruction (after pushing the return address on x86).
+    pub fn get_parameters_and_return_values(
+        &self,
+        cconv: &CallingConvention,
+        project: &Project,
+    ) -> (Vec<Arg>, Vec<Arg>) {
+        let pointer_size = u64::from(project.get_pointer_bytesize());
+        let floats_fall_back_to_integer_registers =
+            pointer_size == 4 || cconv.float_parameter_register.is_empty();
+        let mut integer_registers = cconv.integer_parameter_register.iter();
+        let mut float_registers = cconv.float_parameter_register.iter();
+        let mut stack_offset = get_stack_parameter_start_offset(project);
+        let mut parameters = Vec::new();
+        for data_type in self.parameters.iter() {
+            let size = project
+                .datatype_properties
+                .get_size_from_data_type(data_type.clone());
+            if is_float_type(data_type) {
+                if let Some(register) = float_registers.next() {
+                    let register_size = get_register_size(project, register).unwrap_or(size);
+                    parameters.push(create_register_arg(register, register_size, data_type));
+                    continue;
+                }
+            }
+            let is_register_pair = pointer_size == 4
+                && u64::from(size) == 8
+                && !cconv.integer_parameter_register.is_empty();
+            if !is_float_type(data_type) || floats_fall_back_to_integer_registers {
+                if is_register_pair
+                    && (cconv.integer_parameter_register.len() - integer_registers.len()) % 2 == 1
+                {
+                    // Skip the odd register, so that the register pair starts at an even register.
+                    integer_registers.next();
+                }
+                // Values larger than a register occupy several consecutive registers.
+                let num_registers =
+                    std::cmp::max(round_up_to_multiple(size, pointer_size) / pointer_size, 1);
+                if integer_registers.len() as u64 >= num_registers {
+                    for register in integer_registers.by_ref().take(num_registers as usize) {
+                        let register_size = get_register_size(project, register)
+                            .unwrap_or_else(|| project.get_pointer_bytesize());
+                        parameters.push(create_register_arg(register, register_size, data_type));
+                    }
+                    continue;
+                }
+                if is_register_pair {
+                    // Once a register pair does not fit, the following parameters are also passed on the stack.
+                    integer_registers.by_ref().for_each(drop);
+                }
+            }
+            if is_register_pair {
+                stack_offset = (stack_offset + 7) / 8 * 8;
+            }
+            parameters.push(Arg::Stack {
+                offset: stack_offset,
+                size,
+                data_type: Some(data_type.clone()),
+            });
+            // Stack parameters occupy a multiple of the pointer size.
+            stack_offset += round_up_to_multiple(size, pointer_size) as i64;
+        }
+        let mut return_values = Vec::new();
+        if let Some(return_type) = &self.return_type {
+            let mut return_registers = cconv.return_register.iter();
+            let register = if is_float_type(return_type) {
+                return_registers
+                    .clone()
+                    .find(|register| is_float_register(register, cconv))
+                    .or_else(|| return_registers.next())
+            } else {
+                return_registers.find(|register| !is_float_register(register, cconv))
+            };
+            if let Some(register) = register {
+                let register_size = get_register_size(project, register).unwrap_or_else(|| {
+                    project
+                        .datatype_properties
+                        .get_size_from_data_type(return_type.clone())
+                });
+                return_values.push(create_register_arg(register, register_size, return_type));
+            }
+        }
+        (parameters, return_values)
+    }
+}
+
+/// A database mapping function names to their prototypes.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct PrototypeDatabase {
+    /// The prototypes of the database, keyed by function name.
+    prototypes: HashMap<String, Prototype>,
+    /// Type names defined by typedefs in the parsed declarations.
+    typedefs: HashMap<String, Datatype>,
+}
+
+impl PrototypeDatabase {
+    /// Get the database containing the prototypes bundled with the cwe_checker.
+    pub fn bundled() -> PrototypeDatabase {
+        let mut database = PrototypeDatabase::default();
+        database.add_declarations(BUNDLED_PROTOTYPES);
+        database
+    }
+
+    /// Get the prototype of the function with the given name.
+    pub fn get(&self, name: &str) -> Option<&Prototype> {
+        self.prototypes.get(name)
+    }
+
+    /// Add the function prototypes contained in the given C declarations to the database.
+    ///
+    /// Prototypes of functions already contained in the database are replaced.
+    /// Returns debug log messages for declarations of functions that could not be parsed.
+    pub fn add_declarations(&mut self, declarations: &str) -> Vec<LogMessage> {
+        let mut logs = Vec::new();
+        for statement in split_statements(&remove_comments_and_directives(declarations)) {
+            if let Some(typedef) = statement.strip_prefix("typedef ") {
+                if let Some((name, data_type)) = self.parse_typedef(typedef) {
+                    self.typedefs.insert(name, data_type);
+                }
+            } else if statement.contains('(') {
+                match self.parse_function_declaration(&statement) {
+                    Ok((name, prototype)) => {
+                        self.prototypes.insert(name, prototype);
+                    }
+                    Err(err) => logs.push(
+                        LogMessage::new_debug(format!(
+                            "Skipped declaration `{}`: {}",
+                            statement, err
+                        ))
+                        .source(LOG_SOURCE),
+                    ),
+                }
+            }
+        }
+        logs
+    }
+
+    /// Add the function prototypes contained in the C header file at the given path to the database.
+    ///
+    /// See [`PrototypeDatabase::add_declarations`] for details.
+    pub fn add_declarations_from_file(&mut self, path: &Path) -> Result<Vec<LogMessage>, Error> {
+        let declarations = std::fs::read_to_string(path)
+            .map_err(|err| anyhow!("Could not read prototype file {}: {}", path.display(), err))?;
+        Ok(self.add_declarations(&declarations))
+    }
+
+    /// Fill in the parameters and return values of all extern symbols of the project
+    /// for which neither parameters nor return values are known
+    /// and for which the database contains a prototype.
+    ///
+    /// This should be done before normalizing the project (see [`Project::normalize`]).
+    /// Returns a debug log message with the number of changed symbols.
+    pub fn add_missing_signatures(&self, project: &mut Project) -> Vec<LogMessage> {
+        let mut num_changed_symbols = 0;
+        for index in 0..project.program.term.extern_symbols.len() {
+            let symbol = &project.program.term.extern_symbols[index];
+            if !symbol.parameters.is_empty() || !symbol.return_values.is_empty() {
+                continue;
+            }
+            let prototype = match self.prototypes.get(&symbol.name) {
+                Some(prototype) => prototype,
+                None => continue,
+            };
+            let cconv = match symbol
+                .calling_convention
+                .as_ref()
+                .and_then(|name| {
+                    project
+                        .calling_conventions
+                        .iter()
+                        .find(|cconv| cconv.name == *name)
+                })
+                .or_else(|| project.get_standard_calling_convention())
+            {
+                Some(cconv) => cconv,
+                None => continue,
+            };
+            let (parameters, return_values) =
+                prototype.get_parameters_and_return_values(cconv, project);
+            let symbol = &mut project.program.term.extern_symbols[index];
+            symbol.parameters = parameters;
+            symbol.return_values = return_values;
+            symbol.has_var_args |= prototype.has_var_args;
+            symbol.no_return |= prototype.no_return;
+            num_changed_symbols += 1;
+        }
+        if num_changed_symbols > 0 {
+            vec![LogMessage::new_debug(format!(
+                "Added signatures of {} extern symbols from the prototype database.",
+                num_changed_symbols
+            ))
+            .source(LOG_SOURCE)]
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Parse a function declaration into the function name and its prototype.
+    fn parse_function_declaration(&self, declaration: &str) -> Result<(String, Prototype), Error> {
+        let (declaration, mut no_return) = remove_attributes(declaration);
+        let declaration_regex = Regex::new(r"^(?s)(.*?)\b([A-Za-z_]\w*)\s*\((.*)\)$").unwrap();
+        let captures = declaration_regex
+            .captures(&declaration)
+            .ok_or_else(|| anyhow!("Not a function declaration"))?;
+        let (return_type, name, parameters) = (&captures[1], &captures[2], captures[3].trim());
+        if return_type.contains('(') {
+            return Err(anyhow!(
+                "Functions returning function pointers are not supported"
+            ));
+        }
+        let mut return_type_tokens = Vec::new();
+        for token in return_type.split_whitespace() {
+            match token {
+                "_Noreturn" | "noreturn" => no_return = true,
+                "extern" | "static" | "inline" | "__inline" | "__inline__" | "__extension__"
+                | "__cdecl" | "__stdcall" | "__fastcall" | "WINAPI" | "CALLBACK" | "APIENTRY" => (),
+                _ => return_type_tokens.push(token),
+            }
+        }
+        let return_type = return_type_tokens.join(" ");
+        let return_type = match return_type.as_str() {
+            "" => return Err(anyhow!("Missing return type")),
+            "void" => None,
+            _ => Some(self.parse_type(&return_type, false)?),
+        };
+        let mut prototype = Prototype {
+            parameters: Vec::new(),
+            return_type,
+            has_var_args: false,
+            no_return,
+        };
+        if parameters != "void" {
+            for parameter in split_parameters(parameters) {
+                if parameter == "..." {
+                    prototype.has_var_args = true;
+                } else {
+                    prototype
+                        .parameters
+                        .push(self.parse_type(&parameter, true)?);
+                }
+            }
+        }
+        Ok((name.to_string(), prototype))
+    }
+
+    /// Parse the type of a typedef (without the `typedef` keyword) into the defined type name and its data type.
+    ///
+    /// Returns `None` for typedefs of structs, unions and unknown types.
+    fn parse_typedef(&self, typedef: &str) -> Option<(String, Datatype)> {
+        if typedef.contains('(') {
+            // A typedef of a function pointer
+            let function_pointer_regex = Regex::new(r"\(\s*\*\s*([A-Za-z_]\w*)\s*\)").unwrap();
+            let captures = function_pointer_regex.captures(typedef)?;
+            return Some((captures[1].to_string(), Datatype::Pointer));
+        }
+        // Array typedefs decay to pointers when used as parameter types.
+        let (declarator, is_array) = match typedef.split_once('[') {
+            Some((declarator, _)) => (declarator, true),
+            None => (typedef, false),
+        };
+        let (type_part, name) = declarator
+            .trim()
+            .rsplit_once(|c: char| !is_identifier_char(c))?;
+        if name.is_empty() {
+            return None;
+        }
+        let data_type = if type_part.contains('*') || is_array {
+            Datatype::Pointer
+        } else {
+            self.resolve_type_tokens(&get_type_tokens(type_part))?
+        };
+        Some((name.to_string(), data_type))
+    }
+
+    /// Parse a C type, optionally followed by a parameter name.
+    fn parse_type(&self, declaration: &str, may_contain_name: bool) -> Result<Datatype, Error> {
+        if declaration.contains(['*', '[', '(']) {
+            // Pointers, arrays (which decay to pointers as parameters) and function pointers
+            return Ok(Datatype::Pointer);
+        }
+        let tokens = get_type_tokens(declaration);
+        if let Some(data_type) = self.resolve_type_tokens(&tokens) {
+            return Ok(data_type);
+        }
+        if may_contain_name && tokens.len() > 1 {
+            if let Some(data_type) = self.resolve_type_tokens(&tokens[..tokens.len() - 1]) {
+                return Ok(data_type);
+            }
+        }
+        Err(anyhow!("Unsupported type `{}`", declaration.trim()))
+    }
+
+    /// Get the data type for the tokens of a C type without pointers.
+    ///
+    /// Returns `None` for unknown types and for structs and unions.
+    fn resolve_type_tokens(&self, tokens: &[&str]) -> Option<Datatype> {
+        match tokens {
+            [] | ["struct" | "union", ..] => return None,
+            ["enum", ..] => return Some(Datatype::Integer),
+            [name] => {
+                if let Some(data_type) = self
+                    .typedefs
+                    .get(*name)
+                    .cloned()
+                    .or_else(|| get_builtin_typedef(name))
+                {
+                    return Some(data_type);
+                }
+            }
+            _ => (),
+        }
+        let mut num_long = 0;
+        let mut base_type = None;
+        for token in tokens {
+            match *token {
+                "long" => num_long += 1,
+                "signed" | "unsigned" | "int" => (),
+                "char" | "_Bool" | "short" | "float" | "double" => base_type = Some(*token),
+                _ => return None,
+            }
+        }
+        Some(match (base_type, num_long) {
+            (Some("double"), 0) => Datatype::Double,
+            (Some("double"), _) => Datatype::LongDouble,
+            (Some("float"), _) => Datatype::Float,
+            (Some("char" | "_Bool"), _) => Datatype::Char,
+            (Some("short"), _) => Datatype::Short,
+            (_, 0) => Datatype::Integer,
+            (_, 1) => Datatype::Long,
+            (_, _) => Datatype::LongLong,
+        })
+    }
+}
+
+/// Get the data type of common typedefs of the C standard library, POSIX and the Windows API.
+///
+/// Since there is no special data type for pointer-sized integers, `size_t` and similar types are mapped to [`Datatype::Pointer`].
+fn get_builtin_typedef(name: &str) -> Option<Datatype> {
+    let data_type = match name {
+        "size_t" | "ssize_t" | "ptrdiff_t" | "intptr_t" | "uintptr_t" | "va_list"
+        | "__builtin_va_list" | "sighandler_t" | "pthread_t" | "SIZE_T" | "ULONG_PTR"
+        | "LONG_PTR" | "HANDLE" | "HMODULE" | "HWND" | "PVOID" | "LPVOID" | "LPCVOID" | "LPSTR"
+        | "LPCSTR" | "LPWSTR" | "LPCWSTR" | "LPDWORD" => Datatype::Pointer,
+        "off_t" | "time_t" | "clock_t" | "nfds_t" => Datatype::Long,
+        "int8_t" | "uint8_t" | "bool" | "BYTE" | "BOOLEAN" | "CHAR" => Datatype::Char,
+        "int16_t" | "uint16_t" | "WORD" | "SHORT" | "USHORT" | "WCHAR" => Datatype::Short,
+        "int32_t" | "uint32_t" | "wchar_t" | "wint_t" | "pid_t" | "uid_t" | "gid_t" | "mode_t"
+        | "socklen_t" | "useconds_t" | "in_addr_t" | "errno_t" | "BOOL" | "INT" | "UINT"
+        | "LONG" | "ULONG" | "DWORD" => Datatype::Integer,
+        "int64_t" | "uint64_t" | "LONGLONG" | "ULONGLONG" | "DWORD64" => Datatype::LongLong,
+        _ => return None,
+    };
+    Some(data_type)
+}
+
+/// Get the identifier tokens of a C type, omitting type qualifiers.
+fn get_type_tokens(declaration: &str) -> Vec<&str> {
+    declaration
+        .split(|c: char| !is_identifier_char(c))
+        .filter(|token| {
+            !matches!(
+                *token,
+                "" | "const"
+                    | "volatile"
+                    | "restrict"
+                    | "__restrict"
+                    | "__restrict__"
+                    | "register"
+                    | "__const"
+            )
+        })
+        .collect()
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Returns `true` if the data type is passed in floating point registers.
+fn is_float_type(data_type: &Datatype) -> bool {
+    matches!(
+        data_type,
+        Datatype::Float | Datatype::Double | Datatype::LongDouble
+    )
+}
+
+/// Guess whether the given return register of the calling convention is a floating point register.
+///
+/// Since the calling convention does not distinguish between integer and float return registers,
+/// float registers are recognized by their names.
+fn is_float_register(register: &str, cconv: &CallingConvention) -> bool {
+    lazy_static! {
+        static ref FLOAT_REGISTER_REGEX: Regex =
+            Regex::new(r"^(?i)(st\d|xmm|ymm|[fdsq]\d)").unwrap();
+    }
+    cconv
+        .float_parameter_register
+        .iter()
+        .any(|name| name == register)
+        || FLOAT_REGISTER_REGEX.is_match(register)
+}
+
+/// Get the size of the register with the given name if it is contained in the register list of the project.
+fn get_register_size(project: &Project, register: &str) -> Option<ByteSize> {
+    project
+        .register_list
+        .iter()
+        .find(|var| var.name == register)
+        .map(|var| var.size)
+}
+
+/// Round the given size up to the next multiple of `alignment`.
+fn round_up_to_multiple(size: ByteSize, alignment: u64) -> u64 {
+    match u64::from(size) % alignment {
+        0 => u64::from(size),
+        remainder => u64::from(size) + (alignment - remainder),
+    }
+}
+
+/// Get the offset of the first stack parameter relative to the stack pointer at the call instruction.
+fn get_stack_parameter_start_offset(project: &Project) -> i64 {
+    let pointer_size = u64::from(project.get_pointer_bytesize()) as i64;
+    if project.cpu_architecture.contains("x86") {
+        // The return address is pushed onto the stack by the call instruction.
+        pointer_size
+    } else if project.cpu_architecture.contains("MIPS") && pointer_size == 4 {
+        // The O32 ABI reserves stack space for the four register parameters.
+        16
+    } else {
+        0
+    }
+}
+
+fn create_register_arg(register: &str, size: ByteSize, data_type: &Datatype) -> Arg {
+    Arg::Register {
+        var: Variable {
+            name: register.to_string(),
+            size,
+            is_temp: false,
+        },
+        data_type: Some(data_type.clone()),
+    }
+}
+
+/// Remove comments, preprocessor directives and `extern "C"` linkage specifications from C source code.
+fn remove_comments_and_directives(source: &str) -> String {
+    let comment_regex = Regex::new(r"(?s)/\*.*?\*/|//[^\n]*").unwrap();
+    let source = comment_regex.replace_all(source, " ");
+    let mut result = String::new();
+    let mut is_continued_directive = false;
+    for line in source.lines() {
+        if is_continued_directive || line.trim_start().starts_with('#') {
+            is_continued_directive = line.trim_end().ends_with('\\');
+        } else {
+            result.push_str(line);
+            result.push('\n');
+        }
+    }
+    let linkage_regex = Regex::new(r#"extern\s*"C(\+\+)?"\s*\{?"#).unwrap();
+    linkage_regex.replace_all(&result, " ").to_string()
+}
+
+/// Split C source code into top-level statements with normalized whitespace.
+///
+/// The contents of braces are removed.
+/// Function definitions are terminated by their closing brace.
+fn split_statements(source: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut statement = String::new();
+    let mut brace_depth: usize = 0;
+    let mut is_function_body = false;
+    for c in source.chars() {
+        match c {
+            '{' => {
+                if brace_depth == 0 {
+                    is_function_body = statement.trim_end().ends_with(')');
+                }
+                brace_depth += 1;
+            }
+            '}' => {
+                brace_depth = brace_depth.saturating_sub(1);
+                if brace_depth == 0 && is_function_body {
+                    statements.push(std::mem::take(&mut statement));
+                    is_function_body = false;
+                }
+            }
+            ';' if brace_depth == 0 => statements.push(std::mem::take(&mut statement)),
+            _ if brace_depth == 0 => statement.push(c),
+            _ => (),
+        }
+    }
+    statements.push(statement);
+    statements
+        .into_iter()
+        .map(|statement| statement.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|statement| !statement.is_empty())
+        .collect()
+}
+
+/// Split a parameter list at top-level commas.
+fn split_parameters(parameters: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut parameter = String::new();
+    let mut paren_depth: usize = 0;
+    for c in parameters.chars() {
+        match c {
+            '(' => paren_depth += 1,
+            ')' => paren_depth = paren_depth.saturating_sub(1),
+            ',' if paren_depth == 0 => {
+                result.push(std::mem::take(&mut parameter).trim().to_string());
+                continue;
+            }
+            _ => (),
+        }
+        parameter.push(c);
+    }
+    result.push(parameter.trim().to_string());
+    result
+}
+
+/// Remove GCC attributes, MSVC declspecs and assembler labels from a declaration.
+///
+/// Returns the remaining declaration and whether one of the attributes marks the function as not returning.
+fn remove_attributes(declaration: &str) -> (String, bool) {
+    let attribute_regex =
+        Regex::new(r"\b(__attribute__|__attribute|__declspec|__asm__|__asm)\s*\(").unwrap();
+    let mut result = declaration.to_string();
+    let mut no_return = false;
+    while let Some(attribute_match) = attribute_regex.find(&result) {
+        // Find the closing parenthesis of the attribute.
+        let mut paren_depth = 0;
+        let mut end = result.len();
+        for (index, c) in result[attribute_match.end() - 1..].char_indices() {
+            match c {
+                '(' => paren_depth += 1,
+                ')' => {
+                    paren_depth -= 1;
+                    if paren_depth == 0 {
+                        end = attribute_match.end() + index;
+                        break;
+                    }
+                }
+                _ => (),
+            }
+        }
+        if result[attribute_match.start()..end].contains("noreturn") {
+            no_return = true;
+        }
+        result.replace_range(attribute_match.start()..end, " ");
+    }
+    (result.trim().to_string(), no_return)
+}
+
+#[cfg(test)]
+mod tests;
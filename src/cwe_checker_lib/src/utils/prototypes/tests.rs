@@ -0,0 +1,248 @@
+use super::*;
+
+#[test]
+fn bundled_prototypes() {
+    let mut database = PrototypeDatabase::default();
+    assert!(database.add_declarations(BUNDLED_PROTOTYPES).is_empty());
+    assert_eq!(
+        database.get("memcpy"),
+        Some(&Prototype {
+            parameters: vec![Datatype::Pointer, Datatype::Pointer, Datatype::Pointer],
+            return_type: Some(Datatype::Pointer),
+            has_var_args: false,
+            no_return: false,
+        })
+    );
+    let printf = database.get("printf").unwrap();
+    assert_eq!(printf.parameters, vec![Datatype::Pointer]);
+    assert_eq!(printf.return_type, Some(Datatype::Integer));
+    assert!(printf.has_var_args);
+    let exit = database.get("exit").unwrap();
+    assert_eq!(exit.return_type, None);
+    assert!(exit.no_return);
+    assert_eq!(database.get("getchar").unwrap().parameters, Vec::new());
+}
+
+#[test]
+fn parse_header() {
+    let header = r#"
+        #ifndef SDK_H
+        #define SDK_H \
+            1
+        #include <stdint.h>
+        #ifdef __cplusplus
+        extern "C" {
+        #endif
+        typedef enum { SDK_OK = 0, SDK_ERROR } sdk_status_t; // status codes
+        typedef struct { int baudrate; } sdk_uart_t;
+        typedef void (*sdk_callback_t)(int event);
+        typedef unsigned long long sdk_tick_t;
+        typedef char sdk_name_t[16];
+        /* Transmit data over the UART. */
+        sdk_status_t SDK_UART_Transmit(sdk_uart_t *uart, const uint8_t *data, uint16_t size, uint32_t timeout);
+        static inline int sdk_add(int a, int b) { return a + b; }
+        extern double sdk_scale(float factor, long double value, sdk_tick_t ticks);
+        void sdk_register(sdk_callback_t callback, void (*fallback)(int, int), sdk_name_t name);
+        __attribute__((noreturn)) void sdk_panic(const char *format, ...) __attribute__((format(printf, 1, 2)));
+        int sdk_configure(sdk_uart_t config);
+        int sdk_counter;
+        #ifdef __cplusplus
+        }
+        #endif
+        #endif
+    "#;
+    let mut database = PrototypeDatabase::default();
+    let logs = database.add_declarations(header);
+    assert_eq!(logs.len(), 1);
+    assert!(logs[0].text.contains("sdk_configure"));
+    assert!(database.get("sdk_configure").is_none());
+    assert!(database.get("sdk_counter").is_none());
+    assert_eq!(
+        database.get("SDK_UART_Transmit").unwrap().parameters,
+        vec![
+            Datatype::Pointer,
+            Datatype::Pointer,
+            Datatype::Short,
+            Datatype::Integer
+        ]
+    );
+    assert_eq!(
+        database.get("SDK_UART_Transmit").unwrap().return_type,
+        Some(Datatype::Integer)
+    );
+    assert_eq!(
+        database.get("sdk_add").unwrap().parameters,
+        vec![Datatype::Integer, Datatype::Integer]
+    );
+    let sdk_scale = database.get("sdk_scale").unwrap();
+    assert_eq!(
+        sdk_scale.parameters,
+        vec![Datatype::Float, Datatype::LongDouble, Datatype::LongLong]
+    );
+    assert_eq!(sdk_scale.return_type, Some(Datatype::Double));
+    assert_eq!(
+        database.get("sdk_register").unwrap().parameters,
+        vec![Datatype::Pointer, Datatype::Pointer, Datatype::Pointer]
+    );
+    let sdk_panic = database.get("sdk_panic").unwrap();
+    assert!(sdk_panic.no_return);
+    assert!(sdk_panic.has_var_args);
+    assert_eq!(sdk_panic.parameters, vec![Datatype::Pointer]);
+}
+
+#[test]
+fn parameter_locations() {
+    let mut project = Project::mock_empty();
+    let cconv = CallingConvention {
+        name: "__stdcall".to_string(),
+        integer_parameter_register: vec!["RDI".to_string(), "RSI".to_string()],
+        float_parameter_register: vec!["XMM0_Qa".to_string()],
+        return_register: vec!["XMM0_Qa".to_string(), "RAX".to_string()],
+        callee_saved_register: vec!["RBX".to_string()],
+    };
+    let prototype = Prototype {
+        parameters: vec![
+            Datatype::Integer,
+            Datatype::Double,
+            Datatype::Pointer,
+            Datatype::Double,
+            Datatype::Char,
+        ],
+        return_type: Some(Datatype::Long),
+        has_var_args: false,
+        no_return: false,
+    };
+    let (parameters, return_values) = prototype.get_parameters_and_return_values(&cconv, &project);
+    assert_eq!(
+        parameters,
+        vec![
+            create_register_arg("RDI", ByteSize::new(8), &Datatype::Integer),
+            create_register_arg("XMM0_Qa", ByteSize::new(8), &Datatype::Double),
+            create_register_arg("RSI", ByteSize::new(8), &Datatype::Pointer),
+            Arg::Stack {
+                offset: 8,
+                size: ByteSize::new(8),
+                data_type: Some(Datatype::Double)
+            },
+            Arg::Stack {
+                offset: 16,
+                size: ByteSize::new(1),
+                data_type: Some(Datatype::Char)
+            },
+        ]
+    );
+    assert_eq!(
+        return_values,
+        vec![create_register_arg(
+            "RAX",
+            ByteSize::new(8),
+            &Datatype::Long
+        )]
+    );
+
+    let mut database = PrototypeDatabase::default();
+    database.add_declarations("void *malloc(size_t size); void free(void *ptr);");
+    let mut malloc = ExternSymbol::mock();
+    malloc.name = "malloc".to_string();
+    malloc.calling_convention = Some("__stdcall".to_string());
+    malloc.parameters = Vec::new();
+    malloc.return_values = Vec::new();
+    let mut free = ExternSymbol::mock();
+    free.name = "free".to_string();
+    free.calling_convention = Some("__stdcall".to_string());
+    project.calling_conventions = vec![cconv];
+    project.program.term.extern_symbols = vec![malloc, free.clone()];
+    assert_eq!(database.add_missing_signatures(&mut project).len(), 1);
+    let malloc = &project.program.term.extern_symbols[0];
+    assert_eq!(
+        malloc.parameters,
+        vec![create_register_arg(
+            "RDI",
+            ByteSize::new(8),
+            &Datatype::Pointer
+        )]
+    );
+    assert_eq!(
+        malloc.return_values,
+        vec![create_register_arg(
+            "RAX",
+            ByteSize::new(8),
+            &Datatype::Pointer
+        )]
+    );
+    // Known signatures are not changed.
+    assert_eq!(project.program.term.extern_symbols[1], free);
+}
+
+#[test]
+fn soft_float_parameter_locations() {
+    let mut project = Project::mock_empty();
+    project.cpu_architecture = "ARM:LE:32:v8".to_string();
+    project.stack_pointer_register = Variable::mock("sp", 4u64);
+    project.register_list = vec!["r0", "r1", "r2", "r3"]
+        .into_iter()
+        .map(|name| Variable::mock(name, 4u64))
+        .collect();
+    project.datatype_properties.pointer_size = ByteSize::new(4);
+    let cconv = CallingConvention::mock_with_parameter_registers(
+        vec!["r0", "r1", "r2", "r3"]
+            .into_iter()
+            .map(|name| name.to_string())
+            .collect(),
+        Vec::new(),
+    );
+    let stack_arg = |offset: i64, size: u64, data_type: Datatype| Arg::Stack {
+        offset,
+        size: ByteSize::new(size),
+        data_type: Some(data_type),
+    };
+
+    // Doubles are passed in register pairs starting at an even register.
+    let prototype = Prototype {
+        parameters: vec![
+            Datatype::Integer,
+            Datatype::Double,
+            Datatype::Float,
+            Datatype::Double,
+        ],
+        return_type: None,
+        has_var_args: false,
+        no_return: false,
+    };
+    let (parameters, _) = prototype.get_parameters_and_return_values(&cconv, &project);
+    assert_eq!(
+        parameters,
+        vec![
+            create_register_arg("r0", ByteSize::new(4), &Datatype::Integer),
+            create_register_arg("r2", ByteSize::new(4), &Datatype::Double),
+            create_register_arg("r3", ByteSize::new(4), &Datatype::Double),
+            stack_arg(0, 4, Datatype::Float),
+            stack_arg(8, 8, Datatype::Double),
+        ]
+    );
+
+    // Once a register pair does not fit, all following parameters are passed on the stack.
+    let prototype = Prototype {
+        parameters: vec![
+            Datatype::Integer,
+            Datatype::Integer,
+            Datatype::Float,
+            Datatype::Double,
+            Datatype::Integer,
+        ],
+        return_type: None,
+        has_var_args: false,
+        no_return: false,
+    };
+    let (parameters, _) = prototype.get_parameters_and_return_values(&cconv, &project);
+    assert_eq!(
+        parameters,
+        vec![
+            create_register_arg("r0", ByteSize::new(4), &Datatype::Integer),
+            create_register_arg("r1", ByteSize::new(4), &Datatype::Integer),
+            create_register_arg("r2", ByteSize::new(4), &Datatype::Float),
+            stack_arg(0, 8, Datatype::Double),
+            stack_arg(8, 4, Datatype::Integer),
+        ]
+    );
+}
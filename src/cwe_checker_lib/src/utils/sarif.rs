@@ -0,0 +1,821 @@
+//! Conversion of CWE warnings and log messages to the
+//! [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html) format.
+//!
+//! Each CWE module is mapped to a rule that references the CWEs detected by it in the CWE taxonomy.
+//! Each CWE warning is mapped to a result of the corresponding rule.
+//! The addresses of a warning are given as physical locations of the result,
+//! using the source code location instead of the binary if known from DWARF debug information.
+//! The term IDs of a warning are given as logical locations of the first location of the result.
+//! The fingerprint and the baseline state of a warning are given as partial fingerprint and baseline state of the result.
+//! The witness trace of a warning is given as a code flow of the result.
+//! The versions of the CWE modules are reported as tool configuration notifications
+//! and log messages are reported as tool execution notifications.
+//! Partial analysis results are marked as an unsuccessful execution with a corresponding tool execution notification.
+
+use super::log::{
+    BaselineState, CweWarning, LogLevel, LogMessage, Severity, TraceStepKind,
+    PARTIAL_RESULTS_MESSAGE,
+};
+use crate::prelude::*;
+use std::collections::BTreeMap;
+
+/// The URI of the JSON schema of SARIF 2.1.0.
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// The name of the fingerprint of CWE warnings in the partial fingerprints of results.
+const FINGERPRINT_NAME: &str = "cweCheckerFingerprint/v1";
+
+/// The name of the CWE taxonomy.
+const CWE_TAXONOMY_NAME: &str = "CWE";
+
+/// The CWEs detected by the cwe_checker together with their names.
+const CWE_NAMES: &[(&str, &str)] = &[
+    ("78", "Improper Neutralization of Special Elements used in an OS Command ('OS Command Injection')"),
+    ("119", "Improper Restriction of Operations within the Bounds of a Memory Buffer"),
+    ("125", "Out-of-bounds Read"),
+    ("134", "Use of Externally-Controlled Format String"),
+    ("190", "Integer Overflow or Wraparound"),
+    ("215", "Insertion of Sensitive Information Into Debugging Code"),
+    ("243", "Creation of chroot Jail Without Changing Working Directory"),
+    ("332", "Insufficient Entropy in PRNG"),
+    ("367", "Time-of-check Time-of-use (TOCTOU) Race Condition"),
+    ("415", "Double Free"),
+    ("416", "Use After Free"),
+    ("426", "Untrusted Search Path"),
+    ("467", "Use of sizeof() on a Pointer Type"),
+    ("476", "NULL Pointer Dereference"),
+    ("560", "Use of umask() with chmod-style Argument"),
+    ("676", "Use of Potentially Dangerous Function"),
+    ("782", "Exposed IOCTL with Insufficient Access Control"),
+    ("787", "Out-of-bounds Write"),
+];
+
+/// The CWEs detected by the **Memory** check of the pointer inference analysis.
+const MEMORY_CWE_IDS: &[&str] = &["119", "125", "415", "416", "787"];
+
+/// The top-level object of a SARIF log file.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct SarifLog {
+    /// The URI of the JSON schema of the SARIF format.
+    #[serde(rename = "$schema")]
+    pub schema: String,
+    /// The SARIF format version, i.e. `2.1.0`.
+    pub version: String,
+    /// The runs contained in the log file. The cwe_checker always generates exactly one run.
+    pub runs: Vec<Run>,
+}
+
+/// A run of the cwe_checker on one binary.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Run {
+    /// The cwe_checker together with its rules.
+    pub tool: Tool,
+    /// The notifications generated during the run.
+    pub invocations: Vec<Invocation>,
+    /// The CWE taxonomy referenced by the rules.
+    pub taxonomies: Vec<ToolComponent>,
+    /// The results, i.e. the CWE warnings, of the run.
+    pub results: Vec<SarifResult>,
+}
+
+/// The tool that generated the SARIF log.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Tool {
+    /// The cwe_checker itself.
+    pub driver: ToolComponent,
+}
+
+/// A tool or a taxonomy.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolComponent {
+    /// The name of the component.
+    pub name: String,
+    /// The version of the component.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// The organization that created the component.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub organization: Option<String>,
+    /// A URI pointing to further information about the component.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub information_uri: Option<String>,
+    /// The rules of a tool.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<ReportingDescriptor>,
+    /// The entries of a taxonomy.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub taxa: Vec<ReportingDescriptor>,
+}
+
+/// A rule of the cwe_checker or an entry of the CWE taxonomy.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportingDescriptor {
+    /// The ID of the rule (i.e. the name of the CWE module) or of the CWE (e.g. `476`).
+    pub id: String,
+    /// A human-readable name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// A short description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub short_description: Option<Message>,
+    /// A URI pointing to the documentation of the CWE.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub help_uri: Option<String>,
+    /// The CWEs detected by a rule.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relationships: Vec<Relationship>,
+    /// Additional properties, e.g. the tags used by GitHub code scanning.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub properties: Option<serde_json::Value>,
+}
+
+/// A relation between a rule and an entry of the CWE taxonomy.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Relationship {
+    /// The referenced CWE.
+    pub target: ReportingDescriptorReference,
+    /// The kinds of the relation.
+    pub kinds: Vec<String>,
+}
+
+/// A reference to a rule or a taxonomy entry.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportingDescriptorReference {
+    /// The ID of the referenced rule or taxonomy entry.
+    pub id: String,
+    /// The index of the referenced rule or taxonomy entry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
+    /// The taxonomy containing the referenced entry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_component: Option<ToolComponentReference>,
+}
+
+/// A reference to a taxonomy.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct ToolComponentReference {
+    /// The name of the taxonomy.
+    pub name: String,
+}
+
+/// The invocation of the cwe_checker.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Invocation {
+    /// Whether the analysis finished successfully.
+    /// Not set if the analysis results are partial.
+    pub execution_successful: bool,
+    /// The versions of the CWE modules.
+    pub tool_configuration_notifications: Vec<Notification>,
+    /// The log messages generated during the analysis.
+    pub tool_execution_notifications: Vec<Notification>,
+}
+
+/// A notification, i.e. a module version or a log message.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    /// The text of the notification.
+    pub message: Message,
+    /// The SARIF level of the notification, i.e. `error`, `warning`, `note` or `none`.
+    pub level: String,
+    /// The location inside the binary that the notification is related to.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub locations: Vec<Location>,
+    /// The rule (i.e. CWE module) that the notification is related to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub associated_rule: Option<ReportingDescriptorReference>,
+    /// Additional properties, e.g. the analysis where a log message originated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub properties: Option<serde_json::Value>,
+}
+
+/// A result, i.e. a CWE warning.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifResult {
+    /// The ID of the rule (i.e. the name of the CWE module) that generated the warning.
+    pub rule_id: String,
+    /// The index of the rule in the rules of the tool.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule_index: Option<usize>,
+    /// The SARIF level of the result, i.e. `error`, `warning` or `note` for high, medium or low severity.
+    pub level: String,
+    /// The description of the CWE warning.
+    pub message: Message,
+    /// The locations of the CWE warning.
+    pub locations: Vec<Location>,
+    /// The witness trace of the CWE warning, see [`CweWarning::trace`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub code_flows: Vec<CodeFlow>,
+    /// The CWE of the warning in the CWE taxonomy.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub taxa: Vec<ReportingDescriptorReference>,
+    /// The fingerprint of the warning, see [`CweWarning::fingerprint`].
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub partial_fingerprints: BTreeMap<String, String>,
+    /// The SARIF baseline state of the warning, i.e. `new`, `unchanged` or `absent`.
+    /// Only set if the warnings were compared to a baseline.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline_state: Option<String>,
+    /// The fields of the CWE warning without a SARIF counterpart,
+    /// i.e. the name and version of the check, the severity and confidence, the symbols and other information.
+    pub properties: serde_json::Value,
+}
+
+/// A message.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Message {
+    /// The text of the message.
+    pub text: String,
+}
+
+impl Message {
+    /// Create a new message with the given text.
+    fn new(text: impl ToString) -> Message {
+        Message {
+            text: text.to_string(),
+        }
+    }
+}
+
+/// A location in the binary or in its source code.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    /// The address in the binary and, if known, the corresponding source code location.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub physical_location: Option<PhysicalLocation>,
+    /// The term IDs associated to the location.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub logical_locations: Vec<LogicalLocation>,
+    /// A message describing the location.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<Message>,
+}
+
+/// A code flow, i.e. the witness trace of a CWE warning.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeFlow {
+    /// The witness trace as a single thread flow.
+    pub thread_flows: Vec<ThreadFlow>,
+}
+
+/// A sequence of locations visited by a thread of execution.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct ThreadFlow {
+    /// The steps of the witness trace.
+    pub locations: Vec<ThreadFlowLocation>,
+}
+
+/// A step of a thread flow.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct ThreadFlowLocation {
+    /// The location of the step.
+    pub location: Location,
+    /// The kinds of the step, e.g. `call` or `return`.
+    pub kinds: Vec<String>,
+}
+
+/// An address in the binary or a location in its source code.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PhysicalLocation {
+    /// The source file if known from DWARF debug information and the binary otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artifact_location: Option<ArtifactLocation>,
+    /// The lines and columns in the source file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<Region>,
+    /// The address in the binary.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address>,
+}
+
+/// The location of a file.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct ArtifactLocation {
+    /// The path to the file.
+    pub uri: String,
+}
+
+/// A region of a source file.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Region {
+    /// The line number.
+    pub start_line: u32,
+    /// The column number.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_column: Option<u32>,
+}
+
+/// An address in the binary.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Address {
+    /// The absolute address.
+    pub absolute_address: u64,
+}
+
+/// A term ID.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LogicalLocation {
+    /// The term ID.
+    pub fully_qualified_name: String,
+}
+
+impl SarifLog {
+    /// Generate a SARIF log containing the given CWE warnings and log messages.
+    ///
+    /// If `is_partial` is set, the invocation is marked as not successful.
+    /// If the path to the analyzed binary is given,
+    /// then it is used as the artifact location of addresses without known source code location.
+    pub fn new(
+        cwes: &[CweWarning],
+        logs: &[LogMessage],
+        is_partial: bool,
+        binary_path: Option<&str>,
+    ) -> SarifLog {
+        let modules = crate::get_modules();
+        let rules: Vec<ReportingDescriptor> = modules
+            .iter()
+            .map(|module| create_rule(module.name))
+            .collect();
+        let get_rule_reference = |name: &str| {
+            rules.iter().position(|rule| rule.id == name).map(|index| {
+                ReportingDescriptorReference {
+                    id: name.to_string(),
+                    index: Some(index),
+                    tool_component: None,
+                }
+            })
+        };
+        let tool_configuration_notifications = modules
+            .iter()
+            .map(|module| Notification {
+                message: Message::new(format!("{} version {}", module.name, module.version)),
+                level: "note".to_string(),
+                locations: Vec::new(),
+                associated_rule: get_rule_reference(module.name),
+                properties: None,
+            })
+            .collect();
+        let mut tool_execution_notifications: Vec<Notification> = logs
+            .iter()
+            .map(|log| Notification {
+                message: Message::new(&log.text),
+                level: match log.level {
+                    LogLevel::Error => "error",
+                    LogLevel::Info => "note",
+                    LogLevel::Debug => "none",
+                }
+                .to_string(),
+                locations: log
+                    .location
+                    .iter()
+                    .map(|tid| Location {
+                        physical_location: None,
+                        logical_locations: vec![LogicalLocation {
+                            fully_qualified_name: tid.to_string(),
+                        }],
+                        message: None,
+                    })
+                    .collect(),
+                associated_rule: log.source.as_deref().and_then(&get_rule_reference),
+                properties: log
+                    .source
+                    .as_ref()
+                    .map(|source| serde_json::json!({ "source": source })),
+            })
+            .collect();
+        if is_partial {
+            tool_execution_notifications.insert(
+                0,
+                Notification {
+                    message: Message::new(PARTIAL_RESULTS_MESSAGE),
+                    level: "warning".to_string(),
+                    locations: Vec::new(),
+                    associated_rule: None,
+                    properties: None,
+                },
+            );
+        }
+        let results = cwes
+            .iter()
+            .map(|cwe| {
+                let rule_id = get_rule_name_of_warning(&cwe.name)
+                    .unwrap_or(&cwe.name)
+                    .to_string();
+                SarifResult {
+                    rule_index: get_rule_reference(&rule_id).and_then(|rule| rule.index),
+                    rule_id,
+                    level: match cwe.severity {
+                        Severity::High => "error",
+                        Severity::Medium => "warning",
+                        Severity::Low => "note",
+                    }
+                    .to_string(),
+                    message: Message::new(&cwe.description),
+                    locations: create_locations(cwe, binary_path),
+                    code_flows: create_code_flows(cwe, binary_path),
+                    taxa: cwe
+                        .name
+                        .strip_prefix("CWE")
+                        .map(create_taxon_reference)
+                        .into_iter()
+                        .collect(),
+                    partial_fingerprints: cwe
+                        .fingerprint
+                        .iter()
+                        .map(|fingerprint| (FINGERPRINT_NAME.to_string(), fingerprint.clone()))
+                        .collect(),
+                    baseline_state: cwe.baseline_state.map(|state| {
+                        match state {
+                            BaselineState::New => "new",
+                            BaselineState::Unchanged => "unchanged",
+                            BaselineState::Fixed => "absent",
+                        }
+                        .to_string()
+                    }),
+                    properties: serde_json::json!({
+                        "name": cwe.name,
+                        "version": cwe.version,
+                        "severity": cwe.severity,
+                        "confidence": cwe.confidence,
+                        "symbols": cwe.symbols,
+                        "other": cwe.other,
+                    }),
+                }
+            })
+            .collect();
+        let taxa = CWE_NAMES
+            .iter()
+            .map(|(id, name)| ReportingDescriptor {
+                id: id.to_string(),
+                name: Some(name.to_string()),
+                help_uri: Some(get_cwe_uri(id)),
+                ..Default::default()
+            })
+            .collect();
+        SarifLog {
+            schema: SARIF_SCHEMA.to_string(),
+            version: "2.1.0".to_string(),
+            runs: vec![Run {
+                tool: Tool {
+                    driver: ToolComponent {
+                        name: "cwe_checker".to_string(),
+                        version: Some(env!("CARGO_PKG_VERSION").to_string()),
+                        organization: Some("Fraunhofer FKIE".to_string()),
+                        information_uri: Some(
+                            "https://github.com/fkie-cad/cwe_checker".to_string(),
+                        ),
+                        rules,
+                        taxa: Vec::new(),
+                    },
+                },
+                invocations: vec![Invocation {
+                    execution_successful: !is_partial,
+                    tool_configuration_notifications,
+                    tool_execution_notifications,
+                }],
+                taxonomies: vec![ToolComponent {
+                    name: CWE_TAXONOMY_NAME.to_string(),
+                    version: None,
+                    organization: Some("MITRE".to_string()),
+                    information_uri: Some("https://cwe.mitre.org/".to_string()),
+                    rules: Vec::new(),
+                    taxa,
+                }],
+                results,
+            }],
+        }
+    }
+}
+
+/// Get the IDs of the CWEs detected by the CWE module with the given name.
+fn get_cwe_ids_of_module(module_name: &str) -> Vec<&str> {
+    if module_name == crate::analysis::pointer_inference::CWE_MODULE.name {
+        MEMORY_CWE_IDS.to_vec()
+    } else {
+        module_name.strip_prefix("CWE").into_iter().collect()
+    }
+}
+
+/// Get the name of the CWE module that generates CWE warnings with the given name.
+fn get_rule_name_of_warning(warning_name: &str) -> Option<&'static str> {
+    let modules = crate::get_modules();
+    if let Some(module) = modules.iter().find(|module| module.name == warning_name) {
+        return Some(module.name);
+    }
+    let cwe_id = warning_name.strip_prefix("CWE")?;
+    modules
+        .iter()
+        .find(|module| get_cwe_ids_of_module(module.name).contains(&cwe_id))
+        .map(|module| module.name)
+}
+
+/// Get the URI of the documentation of the CWE with the given ID.
+fn get_cwe_uri(cwe_id: &str) -> String {
+    format!("https://cwe.mitre.org/data/definitions/{}.html", cwe_id)
+}
+
+/// Create a reference to the CWE with the given ID in the CWE taxonomy.
+fn create_taxon_reference(cwe_id: &str) -> ReportingDescriptorReference {
+    ReportingDescriptorReference {
+        id: cwe_id.to_string(),
+        index: CWE_NAMES.iter().position(|(id, _)| *id == cwe_id),
+        tool_component: Some(ToolComponentReference {
+            name: CWE_TAXONOMY_NAME.to_string(),
+        }),
+    }
+}
+
+/// Create the rule for the CWE module with the given name.
+///
+/// The rule references the CWEs detected by the module in the CWE taxonomy.
+/// The CWEs are also added as tags in the format expected by GitHub code scanning.
+fn create_rule(module_name: &str) -> ReportingDescriptor {
+    let cwe_ids = get_cwe_ids_of_module(module_name);
+    let cwe_names: Vec<&str> = cwe_ids
+        .iter()
+        .filter_map(|cwe_id| {
+            CWE_NAMES
+                .iter()
+                .find(|(id, _)| id == cwe_id)
+                .map(|(_, name)| *name)
+        })
+        .collect();
+    let mut tags = vec!["security".to_string()];
+    tags.extend(
+        cwe_ids
+            .iter()
+            .map(|cwe_id| format!("external/cwe/cwe-{}", cwe_id)),
+    );
+    ReportingDescriptor {
+        id: module_name.to_string(),
+        name: Some(module_name.to_string()),
+        short_description: (!cwe_names.is_empty()).then(|| Message::new(cwe_names.join(", "))),
+        help_uri: match cwe_ids.as_slice() {
+            [cwe_id] => Some(get_cwe_uri(cwe_id)),
+            _ => None,
+        },
+        relationships: cwe_ids
+            .iter()
+            .map(|cwe_id| Relationship {
+                target: create_taxon_reference(cwe_id),
+                kinds: vec!["superset".to_string()],
+            })
+            .collect(),
+        properties: Some(serde_json::json!({ "tags": tags })),
+    }
+}
+
+/// Create the locations of a CWE warning.
+///
+/// Each address is mapped to a physical location.
+/// The term IDs of the warning are added as logical locations to the first location.
+fn create_locations(cwe: &CweWarning, binary_path: Option<&str>) -> Vec<Location> {
+    let mut locations: Vec<Location> = cwe
+        .addresses
+        .iter()
+        .map(|address| Location {
+            physical_location: Some(create_physical_location(cwe, address, binary_path)),
+            ..Default::default()
+        })
+        .collect();
+    if !cwe.tids.is_empty() {
+        if locations.is_empty() {
+            locations.push(Location::default());
+        }
+        locations[0].logical_locations = cwe
+            .tids
+            .iter()
+            .map(|tid| LogicalLocation {
+                fully_qualified_name: tid.clone(),
+            })
+            .collect();
+    }
+    locations
+}
+
+/// Create the physical location of an address of a CWE warning.
+///
+/// The source code location of the address is used if it is known,
+/// otherwise the address is given relative to the binary.
+fn create_physical_location(
+    cwe: &CweWarning,
+    address: &str,
+    binary_path: Option<&str>,
+) -> PhysicalLocation {
+    let source_location = cwe
+        .source_locations
+        .iter()
+        .find(|location| location.address == address && location.file.is_some());
+    let (artifact_location, region) = match source_location {
+        Some(source_location) => (
+            source_location.file.clone(),
+            source_location.line.map(|line| Region {
+                start_line: line,
+                start_column: source_location.column,
+            }),
+        ),
+        None => (binary_path.map(|path| path.to_string()), None),
+    };
+    PhysicalLocation {
+        artifact_location: artifact_location.map(|uri| ArtifactLocation { uri }),
+        region,
+        address: u64::from_str_radix(address, 16)
+            .ok()
+            .map(|absolute_address| Address { absolute_address }),
+    }
+}
+
+/// Create the code flow of the witness trace of a CWE warning.
+///
+/// The kinds of the steps are mapped to the thread flow location kinds proposed by the SARIF standard.
+fn create_code_flows(cwe: &CweWarning, binary_path: Option<&str>) -> Vec<CodeFlow> {
+    if cwe.trace.is_empty() {
+        return Vec::new();
+    }
+    let locations = cwe
+        .trace
+        .iter()
+        .map(|step| ThreadFlowLocation {
+            location: Location {
+                physical_location: Some(create_physical_location(cwe, &step.address, binary_path)),
+                logical_locations: vec![LogicalLocation {
+                    fully_qualified_name: step.tid.clone(),
+                }],
+                message: Some(Message::new(format!(
+                    "{} ({})",
+                    step.description, step.function
+                ))),
+            },
+            kinds: match step.kind {
+                TraceStepKind::Source => vec!["taint".to_string()],
+                TraceStepKind::Block => vec!["branch".to_string()],
+                TraceStepKind::Call => vec!["call".to_string()],
+                TraceStepKind::Return => vec!["return".to_string()],
+                TraceStepKind::Sink => vec!["danger".to_string()],
+            },
+        })
+        .collect();
+    vec![CodeFlow {
+        thread_flows: vec![ThreadFlow { locations }],
+    }]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::dwarf::SourceLocation;
+    use crate::utils::log::TraceStep;
+
+    #[test]
+    fn sarif_log() {
+        let mut warning = CweWarning::new("CWE416", "0.3", "(Use After Free) at 00401000")
+            .addresses(vec!["00401000".to_string(), "00401010".to_string()])
+            .tids(vec!["instr_00401000_1".to_string()])
+            .symbols(vec!["free".to_string()])
+            .severity(Severity::High);
+        warning.source_locations = vec![SourceLocation {
+            address: "00401010".to_string(),
+            file: Some("src/main.c".to_string()),
+            line: Some(42),
+            column: Some(7),
+            inlined_frames: Vec::new(),
+        }];
+        warning.fingerprint = Some("0123abcd".to_string());
+        warning.baseline_state = Some(BaselineState::Fixed);
+        warning.trace = vec![
+            TraceStep {
+                kind: TraceStepKind::Source,
+                address: "00400ff0".to_string(),
+                tid: "instr_00400ff0_1".to_string(),
+                function: "main".to_string(),
+                description: "Source of the value".to_string(),
+            },
+            TraceStep {
+                kind: TraceStepKind::Sink,
+                address: "00401010".to_string(),
+                tid: "instr_00401010_1".to_string(),
+                function: "main".to_string(),
+                description: "Location of the warning".to_string(),
+            },
+        ];
+        let log = LogMessage::new_info("Analysis finished.").source("Memory");
+        let sarif = SarifLog::new(&[warning], &[log], false, Some("bin/main"));
+        let run = &sarif.runs[0];
+
+        let memory_rule = run
+            .tool
+            .driver
+            .rules
+            .iter()
+            .position(|rule| rule.id == "Memory")
+            .unwrap();
+        let result = &run.results[0];
+        assert_eq!(result.rule_id, "Memory");
+        assert_eq!(result.rule_index, Some(memory_rule));
+        assert_eq!(result.level, "error");
+        assert_eq!(result.taxa, vec![create_taxon_reference("416")]);
+        assert_eq!(
+            run.taxonomies[0].taxa[result.taxa[0].index.unwrap()].id,
+            "416"
+        );
+        assert!(run.tool.driver.rules[memory_rule]
+            .relationships
+            .iter()
+            .any(|relation| relation.target.id == "416"));
+
+        assert_eq!(result.partial_fingerprints[FINGERPRINT_NAME], "0123abcd");
+        assert_eq!(result.baseline_state.as_deref(), Some("absent"));
+
+        assert_eq!(result.locations.len(), 2);
+        assert_eq!(
+            result.locations[0],
+            Location {
+                physical_location: Some(PhysicalLocation {
+                    artifact_location: Some(ArtifactLocation {
+                        uri: "bin/main".to_string()
+                    }),
+                    region: None,
+                    address: Some(Address {
+                        absolute_address: 0x401000
+                    }),
+                }),
+                logical_locations: vec![LogicalLocation {
+                    fully_qualified_name: "instr_00401000_1".to_string()
+                }],
+                message: None,
+            }
+        );
+        let source_location = result.locations[1].physical_location.as_ref().unwrap();
+        assert_eq!(
+            source_location.artifact_location.as_ref().unwrap().uri,
+            "src/main.c"
+        );
+        assert_eq!(
+            source_location.region,
+            Some(Region {
+                start_line: 42,
+                start_column: Some(7)
+            })
+        );
+
+        let trace = &result.code_flows[0].thread_flows[0].locations;
+        assert_eq!(trace.len(), 2);
+        assert_eq!(trace[0].kinds, vec!["taint".to_string()]);
+        assert_eq!(
+            trace[0].location.message,
+            Some(Message::new("Source of the value (main)"))
+        );
+        assert_eq!(
+            trace[1].location.physical_location,
+            result.locations[1].physical_location
+        );
+
+        let invocation = &run.invocations[0];
+        assert_eq!(
+            invocation.tool_configuration_notifications.len(),
+            run.tool.driver.rules.len()
+        );
+        let notification = &invocation.tool_execution_notifications[0];
+        assert_eq!(notification.level, "note");
+        assert_eq!(
+            notification.associated_rule.as_ref().unwrap().index,
+            Some(memory_rule)
+        );
+
+        assert!(invocation.execution_successful);
+        assert_eq!(invocation.tool_execution_notifications.len(), 1);
+
+        let json = serde_json::to_value(&sarif).unwrap();
+        assert_eq!(json["version"], "2.1.0");
+        assert_eq!(json["runs"][0]["results"][0]["ruleId"], "Memory");
+        assert_eq!(
+            json["runs"][0]["results"][0]["locations"][0]["physicalLocation"]["address"]
+                ["absoluteAddress"],
+            0x401000
+        );
+    }
+
+    #[test]
+    fn partial_sarif_log() {
+        let log = LogMessage::new_info("Analysis finished.");
+        let sarif = SarifLog::new(&[], &[log], true, None);
+        let invocation = &sarif.runs[0].invocations[0];
+        assert!(!invocation.execution_successful);
+        assert_eq!(invocation.tool_execution_notifications.len(), 2);
+        let notification = &invocation.tool_execution_notifications[0];
+        assert_eq!(notification.message, Message::new(PARTIAL_RESULTS_MESSAGE));
+        assert_eq!(notification.level, "warning");
+    }
+}
@@ -0,0 +1,126 @@
+import java.io.FileReader;
+import java.io.RandomAccessFile;
+import java.math.BigInteger;
+
+import com.google.gson.JsonArray;
+import com.google.gson.JsonElement;
+import com.google.gson.JsonObject;
+import com.google.gson.JsonParser;
+
+import ghidra.app.script.GhidraScript;
+import ghidra.program.model.address.Address;
+import ghidra.program.model.lang.Register;
+import ghidra.program.model.listing.Function;
+import ghidra.program.model.mem.Memory;
+import ghidra.program.model.mem.MemoryBlock;
+import ghidra.program.model.mem.MemoryConflictException;
+import ghidra.program.model.symbol.SourceType;
+
+public class BareMetalMemoryMap extends GhidraScript {
+
+    /**
+     * 
+     * Entry point to Ghidra Script. Creates the memory blocks and functions given in the memory map file.
+     * 
+     * The memory map file is a JSON object generated by the cwe_checker
+     * containing the memory blocks and the known functions (e.g. interrupt handlers) of the binary.
+     * Memory blocks that already exist (i.e. the block imported by the loader) only get their permissions updated.
+     * Memory blocks overlapping with blocks created by the loader (e.g. for Intel HEX files) are skipped.
+     */
+    @Override
+    protected void run() throws Exception {
+        String memoryMapPath = getScriptArgs()[0];
+        JsonObject memoryMap = new JsonParser().parse(new FileReader(memoryMapPath)).getAsJsonObject();
+        createBlocks(memoryMap.getAsJsonArray("memory_blocks"));
+        createFunctions(memoryMap.getAsJsonArray("functions"));
+    }
+
+
+    /**
+     * 
+     * @param blocks: The JSON array of memory blocks to create
+     * 
+     * Creates the given memory blocks and sets the permissions of all blocks.
+     */
+    protected void createBlocks(JsonArray blocks) throws Exception {
+        Memory memory = currentProgram.getMemory();
+        for (JsonElement element : blocks) {
+            JsonObject blockData = element.getAsJsonObject();
+            Address start = toAddr(Long.parseUnsignedLong(blockData.get("base_address").getAsString(), 16));
+            long size = blockData.get("size").getAsLong();
+            MemoryBlock block = memory.getBlock(start);
+            if (block == null || !block.getStart().equals(start)) {
+                try {
+                    block = createBlock(memory, blockData, start, size);
+                } catch (MemoryConflictException e) {
+                    println("Skipping memory region " + blockData.get("name").getAsString() + ": " + e.getMessage());
+                    continue;
+                }
+            }
+            block.setRead(blockData.get("read").getAsBoolean());
+            block.setWrite(blockData.get("write").getAsBoolean());
+            block.setExecute(blockData.get("execute").getAsBoolean());
+            block.setVolatile(blockData.get("volatile").getAsBoolean());
+        }
+    }
+
+
+    /**
+     * 
+     * @param functions: The JSON array of functions to create
+     * 
+     * Disassembles the code at the entry points of the given functions and creates the functions.
+     * The functions are also marked as external entry points of the program.
+     */
+    protected void createFunctions(JsonArray functions) throws Exception {
+        Register thumbModeRegister = currentProgram.getRegister("TMode");
+        for (JsonElement element : functions) {
+            JsonObject functionData = element.getAsJsonObject();
+            Address entry = toAddr(Long.parseUnsignedLong(functionData.get("address").getAsString(), 16));
+            String name = functionData.get("name").getAsString();
+            if (functionData.get("thumb").getAsBoolean() && thumbModeRegister != null) {
+                currentProgram.getProgramContext().setValue(thumbModeRegister, entry, entry, BigInteger.ONE);
+            }
+            currentProgram.getSymbolTable().addExternalEntryPoint(entry);
+            disassemble(entry);
+            Function function = getFunctionAt(entry);
+            if (function == null) {
+                function = createFunction(entry, name);
+            }
+            if (function == null) {
+                println("Could not create function " + name + " at " + entry);
+            } else {
+                function.setName(name, SourceType.USER_DEFINED);
+            }
+        }
+    }
+
+
+    /**
+     * 
+     * @param memory: The memory of the current program
+     * @param blockData: The JSON object describing the block
+     * @param start: The start address of the block
+     * @param size: The size of the block
+     * @return: The created memory block
+     * 
+     * Creates an initialized memory block if the block is backed by a file and an uninitialized memory block otherwise.
+     * Parts of initialized blocks not covered by the file are filled with null bytes.
+     */
+    protected MemoryBlock createBlock(Memory memory, JsonObject blockData, Address start, long size) throws Exception {
+        String name = blockData.get("name").getAsString();
+        if (!blockData.has("file") || blockData.get("file").isJsonNull()) {
+            return memory.createUninitializedBlock(name, start, size, false);
+        }
+        MemoryBlock block = memory.createInitializedBlock(name, start, size, (byte) 0, getMonitor(), false);
+        try (RandomAccessFile file = new RandomAccessFile(blockData.get("file").getAsString(), "r")) {
+            long offset = blockData.get("file_offset").getAsLong();
+            long length = Math.max(0, Math.min(size, file.length() - offset));
+            byte[] content = new byte[(int) length];
+            file.seek(offset);
+            file.readFully(content);
+            memory.setBytes(start, content);
+        }
+        return block;
+    }
+}
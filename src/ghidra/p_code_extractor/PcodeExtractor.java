@@ -13,7 +13,7 @@ import internal.HelperFunctions;
 import symbol.ExternSymbol;
 import symbol.ExternSymbolCreator;
 import serializer.Serializer;
-import ghidra.app.script.GhidraScript;
+import ghidra.app.util.headless.HeadlessScript;
 import ghidra.program.model.block.CodeBlock;
 import ghidra.program.model.block.CodeBlockIterator;
 import ghidra.program.model.block.CodeBlockReferenceIterator;
@@ -31,7 +31,7 @@ import ghidra.program.model.pcode.PcodeOp;
 import ghidra.program.util.VarnodeContext;
 import ghidra.util.exception.CancelledException;
 
-public class PcodeExtractor extends GhidraScript {
+public class PcodeExtractor extends HeadlessScript {
 
     /**
      * 
@@ -261,6 +261,11 @@ public class PcodeExtractor extends GhidraScript {
         }
         project.setRegisterProperties(HelperFunctions.getRegisterList());
         project.setDatatypeProperties(HelperFunctions.createDatatypeProperties());
+        // If the analysis timed out, we still extract the P-Code of the already disassembled parts of the binary.
+        project.setAnalysisTimeoutOccurred(analysisTimeoutOccurred());
+        if (project.getAnalysisTimeoutOccurred()) {
+            println("Ghidra analysis timed out. Extracting partial results.");
+        }
 
         return project;
     }
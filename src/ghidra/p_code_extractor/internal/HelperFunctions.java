@@ -234,6 +234,24 @@ public final class HelperFunctions {
     }
 
 
+    /**
+     * @return: The names and start addresses of all loaded memory blocks.
+     *
+     * For relocatable ELF files the memory blocks correspond to the allocatable sections,
+     * whose addresses are chosen by the Ghidra loader.
+     */
+    public static ArrayList<MemoryBlock> getMemoryBlocks() {
+        ArrayList<MemoryBlock> memoryBlocks = new ArrayList<MemoryBlock>();
+        for (ghidra.program.model.mem.MemoryBlock block : ghidraProgram.getMemory().getBlocks()) {
+            if (block.isLoaded()) {
+                memoryBlocks.add(new MemoryBlock(block.getName(), block.getStart().toString()));
+            }
+        }
+
+        return memoryBlocks;
+    }
+
+
     /**
      * 
      * @return: CPU architecture as string.
@@ -0,0 +1,34 @@
+package term;
+
+import com.google.gson.annotations.SerializedName;
+
+public class MemoryBlock {
+    @SerializedName("name")
+    private String name;
+    @SerializedName("address")
+    private String address;
+
+    public MemoryBlock() {
+    }
+
+    public MemoryBlock(String name, String address) {
+        this.setName(name);
+        this.setAddress(address);
+    }
+
+    public String getName() {
+        return name;
+    }
+
+    public void setName(String name) {
+        this.name = name;
+    }
+
+    public String getAddress() {
+        return address;
+    }
+
+    public void setAddress(String address) {
+        this.address = address;
+    }
+}
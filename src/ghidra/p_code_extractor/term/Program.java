@@ -16,6 +16,8 @@ public class Program {
     private ArrayList<Tid> entryPoints;
     @SerializedName("image_base")
     private String imageBase;
+    @SerializedName("memory_blocks")
+    private ArrayList<MemoryBlock> memoryBlocks;
 
     public Program() {
     }
@@ -24,10 +26,11 @@ public class Program {
         this.setSubs(subs);
     }
 
-    public Program(ArrayList<Term<Sub>> subs, ArrayList<Tid> entryPoints, String imageBase) {
+    public Program(ArrayList<Term<Sub>> subs, ArrayList<Tid> entryPoints, String imageBase, ArrayList<MemoryBlock> memoryBlocks) {
         this.setSubs(subs);
         this.setEntryPoints(entryPoints);
         this.setImageBase(imageBase);
+        this.setMemoryBlocks(memoryBlocks);
     }
 
 
@@ -66,4 +69,12 @@ public class Program {
     public void setImageBase(String imageBase) {
         this.imageBase = imageBase;
     }
+
+    public ArrayList<MemoryBlock> getMemoryBlocks() {
+        return memoryBlocks;
+    }
+
+    public void setMemoryBlocks(ArrayList<MemoryBlock> memoryBlocks) {
+        this.memoryBlocks = memoryBlocks;
+    }
 }
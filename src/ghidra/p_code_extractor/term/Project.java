@@ -22,6 +22,8 @@ public class Project {
     private ArrayList<RegisterConvention> conventions;
     @SerializedName("datatype_properties")
     private DatatypeProperties datatype_properties;
+    @SerializedName("analysis_timeout_occurred")
+    private boolean analysisTimeoutOccurred;
 
     public Project() {
     }
@@ -87,4 +89,12 @@ public class Project {
     public void setDatatypeProperties(DatatypeProperties datatype_properties) {
         this.datatype_properties = datatype_properties;
     }
+
+    public boolean getAnalysisTimeoutOccurred() {
+        return analysisTimeoutOccurred;
+    }
+
+    public void setAnalysisTimeoutOccurred(boolean analysisTimeoutOccurred) {
+        this.analysisTimeoutOccurred = analysisTimeoutOccurred;
+    }
 }
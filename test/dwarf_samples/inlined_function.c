@@ -0,0 +1,23 @@
+// A small sample with DWARF debug information for the unit tests of the source code location mapping.
+// The function `square` is always inlined into `compute`.
+//
+// Build command:
+// gcc -g -O1 -fdebug-prefix-map=$(pwd)=. -nostdlib -static -fno-asynchronous-unwind-tables -Wl,--build-id=none -o inlined_function inlined_function.c
+
+static inline __attribute__((always_inline)) int square(volatile int *value)
+{
+    return *value * *value;
+}
+
+int compute(volatile int *value)
+{
+    return square(value) + 1;
+}
+
+void _start(void)
+{
+    volatile int value = 3;
+    compute(&value);
+    for (;;)
+        ;
+}